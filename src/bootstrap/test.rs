@@ -1184,6 +1184,7 @@ impl Step for Compiletest {
 
         if builder.config.profiler {
             cmd.env("PROFILER_SUPPORT", "1");
+            cmd.arg("--profiler-support");
         }
 
         cmd.env("RUST_TEST_TMPDIR", builder.out.join("tmp"));
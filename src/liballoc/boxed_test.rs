@@ -11,12 +11,15 @@
 //! Test for `boxed` mod.
 
 use core::any::Any;
+use core::cell::Cell;
 use core::ops::Deref;
+use core::ptr::NonNull;
 use core::result::Result::{Err, Ok};
 use core::clone::Clone;
 use core::f64;
 use core::i64;
 
+use std::alloc::{Alloc, AllocErr, Global, Layout};
 use std::boxed::Box;
 
 #[test]
@@ -140,3 +143,74 @@ fn str_slice() {
     let boxed: Box<str> = Box::from(s);
     assert_eq!(&*boxed, s)
 }
+
+thread_local!(static LIVE: Cell<isize> = Cell::new(0));
+
+fn live() -> isize {
+    LIVE.with(|live| live.get())
+}
+
+/// Forwards to `Global`, keeping count of the allocations of the thread still live.
+#[derive(Clone)]
+struct Counting;
+
+unsafe impl Alloc for Counting {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        LIVE.with(|live| live.set(live.get() + 1));
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        LIVE.with(|live| live.set(live.get() - 1));
+        Global.dealloc(ptr, layout)
+    }
+}
+
+#[test]
+fn alloc_in() {
+    {
+        let a = Box::new_in(5, Counting);
+        assert_eq!(live(), 1);
+        let b = a.clone();
+        assert_eq!(live(), 2);
+        assert!(a == b);
+        assert_eq!(*a + *b, 10);
+
+        // Zero-sized values are not allocated.
+        let _unit = Box::new_in((), Counting);
+        assert_eq!(live(), 2);
+
+        let (raw, alloc) = Box::into_raw_with_alloc(a);
+        let a = unsafe { Box::from_raw_in(raw, alloc) };
+        assert_eq!(*a, 5);
+
+        let any: Box<dyn Any, _> = b;
+        let b = any.downcast::<i32>().unwrap();
+        assert_eq!(*b, 5);
+
+        let f: Box<dyn Fn(i32) -> i32, _> = Box::new_in(|x| x + 1, Counting);
+        assert_eq!(live(), 2);
+        assert_eq!(f(*a), 6);
+    }
+    assert_eq!(live(), 0);
+}
+
+/// Forwards to `Global`, but takes space.
+#[derive(Clone)]
+struct Stateful(u8);
+
+unsafe impl Alloc for Stateful {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        Global.dealloc(ptr, layout)
+    }
+}
+
+#[test]
+#[should_panic(expected = "`Box` only supports zero-sized allocators")]
+fn alloc_in_not_zero_sized() {
+    let _ = Box::new_in(5, Stateful(0));
+}
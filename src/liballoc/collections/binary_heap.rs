@@ -165,6 +165,7 @@ use slice;
 use vec::{self, Vec};
 
 use super::SpecExtend;
+use super::CollectionAllocErr;
 
 /// A priority queue implemented with a binary heap.
 ///
@@ -342,6 +343,29 @@ impl<T: Ord> BinaryHeap<T> {
         BinaryHeap { data: Vec::with_capacity(capacity) }
     }
 
+    /// Creates an empty `BinaryHeap` with a specific capacity, returning an
+    /// error instead of aborting if the allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BinaryHeap;
+    /// let mut heap = BinaryHeap::try_with_capacity(10).expect("out of memory");
+    /// heap.push(4);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity(capacity: usize) -> Result<BinaryHeap<T>, CollectionAllocErr> {
+        Ok(BinaryHeap { data: Vec::try_with_capacity(capacity)? })
+    }
+
     /// Returns an iterator visiting all values in the underlying vector, in
     /// arbitrary order.
     ///
@@ -490,6 +514,55 @@ impl<T: Ord> BinaryHeap<T> {
         self.data.reserve(additional);
     }
 
+    /// Tries to reserve the minimum capacity for exactly `additional` more elements
+    /// to be inserted in the given `BinaryHeap`. After calling `try_reserve_exact`,
+    /// capacity will be greater than or equal to `self.len() + additional`.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BinaryHeap;
+    /// let mut heap = BinaryHeap::new();
+    /// heap.try_reserve_exact(100).expect("out of memory");
+    /// assert!(heap.capacity() >= 100);
+    /// heap.push(4);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.data.try_reserve_exact(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted
+    /// in the given `BinaryHeap`. The collection may reserve more space to avoid
+    /// frequent reallocations.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BinaryHeap;
+    /// let mut heap = BinaryHeap::new();
+    /// heap.try_reserve(100).expect("out of memory");
+    /// assert!(heap.capacity() >= 100);
+    /// heap.push(4);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.data.try_reserve(additional)
+    }
+
     /// Discards as much additional capacity as possible.
     ///
     /// # Examples
@@ -583,6 +656,52 @@ impl<T: Ord> BinaryHeap<T> {
         self.sift_up(0, old_len);
     }
 
+    /// Pushes an item onto the binary heap, returning an error instead of
+    /// aborting if the heap can't be grown.
+    ///
+    /// If an error is returned, the heap is unchanged and `item` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BinaryHeap;
+    /// let mut heap = BinaryHeap::new();
+    /// heap.try_push(3).expect("out of memory");
+    /// heap.try_push(5).expect("out of memory");
+    ///
+    /// assert_eq!(heap.peek(), Some(&5));
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push(&mut self, item: T) -> Result<(), CollectionAllocErr> {
+        let old_len = self.len();
+        self.data.try_push(item)?;
+        self.sift_up(0, old_len);
+        Ok(())
+    }
+
+    /// Returns a copy of the binary heap, returning an error instead of
+    /// aborting if the allocation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BinaryHeap;
+    /// let heap = BinaryHeap::from(vec![1, 5, 2]);
+    /// let copy = heap.try_clone().expect("out of memory");
+    ///
+    /// assert_eq!(copy.into_sorted_vec(), [1, 2, 5]);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_clone(&self) -> Result<BinaryHeap<T>, CollectionAllocErr>
+        where T: Clone
+    {
+        Ok(BinaryHeap { data: self.data.try_clone()? })
+    }
+
     /// Consumes the `BinaryHeap` and returns the underlying vector
     /// in arbitrary order.
     ///
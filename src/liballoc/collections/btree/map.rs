@@ -13,14 +13,16 @@ use core::fmt::Debug;
 use core::hash::{Hash, Hasher};
 use core::iter::{FromIterator, Peekable, FusedIterator};
 use core::marker::PhantomData;
-use core::ops::Bound::{Excluded, Included, Unbounded};
+use core::ops::Bound::{self, Excluded, Included, Unbounded};
 use core::ops::Index;
 use core::ops::RangeBounds;
 use core::{fmt, intrinsics, mem, ptr};
 
+use alloc::{Alloc, Global};
 use borrow::Borrow;
+use collections::CollectionAllocErr;
 
-use super::node::{self, Handle, NodeRef, marker};
+use super::node::{self, Handle, NodePool, NodeRef, marker};
 use super::search;
 
 use super::node::InsertResult::*;
@@ -60,9 +62,13 @@ use self::Entry::*;
 /// any other key, as determined by the [`Ord`] trait, changes while it is in the map. This is
 /// normally only possible through [`Cell`], [`RefCell`], global state, I/O, or unsafe code.
 ///
+/// The nodes are allocated with `A`, which is the global allocator unless another one is given
+/// to [`new_in`].
+///
 /// [`Ord`]: ../../std/cmp/trait.Ord.html
 /// [`Cell`]: ../../std/cell/struct.Cell.html
 /// [`RefCell`]: ../../std/cell/struct.RefCell.html
+/// [`new_in`]: #method.new_in
 ///
 /// # Examples
 ///
@@ -132,13 +138,18 @@ use self::Entry::*;
 /// *stat += random_stat_buff();
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct BTreeMap<K, V> {
+pub struct BTreeMap<
+    K,
+    V,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: Alloc + Clone = Global,
+> {
     root: node::Root<K, V>,
     length: usize,
+    alloc: A,
 }
 
 #[stable(feature = "btree_drop", since = "1.7.0")]
-unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for BTreeMap<K, V> {
+unsafe impl<#[may_dangle] K, #[may_dangle] V, A: Alloc + Clone> Drop for BTreeMap<K, V, A> {
     fn drop(&mut self) {
         unsafe {
             drop(ptr::read(self).into_iter());
@@ -147,18 +158,20 @@ unsafe impl<#[may_dangle] K, #[may_dangle] V> Drop for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
-    fn clone(&self) -> BTreeMap<K, V> {
-        fn clone_subtree<'a, K: Clone, V: Clone>(
-            node: node::NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>
-        ) -> BTreeMap<K, V>
+impl<K: Clone, V: Clone, A: Alloc + Clone> Clone for BTreeMap<K, V, A> {
+    fn clone(&self) -> BTreeMap<K, V, A> {
+        fn clone_subtree<'a, K: Clone, V: Clone, A: Alloc + Clone>(
+            node: node::NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
+            alloc: A,
+        ) -> BTreeMap<K, V, A>
         where K: 'a, V: 'a,
         {
             match node.force() {
                 Leaf(leaf) => {
                     let mut out_tree = BTreeMap {
-                        root: node::Root::new_leaf(),
+                        root: node::Root::new_leaf(alloc.clone()),
                         length: 0,
+                        alloc,
                     };
 
                     {
@@ -180,10 +193,11 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
                     out_tree
                 }
                 Internal(internal) => {
-                    let mut out_tree = clone_subtree(internal.first_edge().descend());
+                    let mut out_tree = clone_subtree(internal.first_edge().descend(),
+                                                     alloc.clone());
 
                     {
-                        let mut out_node = out_tree.root.push_level();
+                        let mut out_node = out_tree.root.push_level(alloc.clone());
                         let mut in_edge = internal.first_edge();
                         while let Ok(kv) = in_edge.right_kv() {
                             let (k, v) = kv.into_kv();
@@ -191,13 +205,14 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
 
                             let k = (*k).clone();
                             let v = (*v).clone();
-                            let subtree = clone_subtree(in_edge.descend());
+                            let subtree = clone_subtree(in_edge.descend(), alloc.clone());
 
                             // We can't destructure subtree directly
                             // because BTreeMap implements Drop
                             let (subroot, sublength) = unsafe {
                                 let root = ptr::read(&subtree.root);
                                 let length = subtree.length;
+                                drop(ptr::read(&subtree.alloc));
                                 mem::forget(subtree);
                                 (root, length)
                             };
@@ -218,14 +233,15 @@ impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
             BTreeMap {
                 root: node::Root::shared_empty_root(),
                 length: 0,
+                alloc: self.alloc.clone(),
             }
         } else {
-            clone_subtree(self.root.as_ref())
+            clone_subtree(self.root.as_ref(), self.alloc.clone())
         }
     }
 }
 
-impl<K, Q: ?Sized> super::Recover<Q> for BTreeMap<K, ()>
+impl<K, Q: ?Sized, A: Alloc + Clone> super::Recover<Q> for BTreeMap<K, (), A>
     where K: Borrow<Q> + Ord,
           Q: Ord
 {
@@ -244,6 +260,7 @@ impl<K, Q: ?Sized> super::Recover<Q> for BTreeMap<K, ()>
                 Some(OccupiedEntry {
                          handle,
                          length: &mut self.length,
+                         alloc: &self.alloc,
                          _marker: PhantomData,
                      }
                      .remove_kv()
@@ -262,6 +279,7 @@ impl<K, Q: ?Sized> super::Recover<Q> for BTreeMap<K, ()>
                     key,
                     handle,
                     length: &mut self.length,
+                    alloc: &self.alloc,
                     _marker: PhantomData,
                 }
                 .insert(());
@@ -313,14 +331,19 @@ pub struct IterMut<'a, K: 'a, V: 'a> {
 /// [`into_iter`]: struct.BTreeMap.html#method.into_iter
 /// [`BTreeMap`]: struct.BTreeMap.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<K, V> {
+pub struct IntoIter<
+    K,
+    V,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: Alloc + Clone = Global,
+> {
     front: Handle<NodeRef<marker::Owned, K, V, marker::Leaf>, marker::Edge>,
     back: Handle<NodeRef<marker::Owned, K, V, marker::Leaf>, marker::Edge>,
     length: usize,
+    alloc: A,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IntoIter<K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, A: Alloc + Clone> fmt::Debug for IntoIter<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let range = Range {
             front: self.front.reborrow(),
@@ -428,6 +451,90 @@ impl<'a, K: 'a + fmt::Debug, V: 'a + fmt::Debug> fmt::Debug for RangeMut<'a, K,
     }
 }
 
+/// A cursor over a `BTreeMap`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// Cursors always point to an element in the map, or to a "ghost" non-element that is
+/// logically located after the last element and before the first element. Moving past the
+/// ghost wraps around to the other end of the map.
+///
+/// This `struct` is created by the [`lower_bound`] and [`upper_bound`] methods on
+/// [`BTreeMap`].
+///
+/// [`BTreeMap`]: struct.BTreeMap.html
+/// [`lower_bound`]: struct.BTreeMap.html#method.lower_bound
+/// [`upper_bound`]: struct.BTreeMap.html#method.upper_bound
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct Cursor<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K, V> Clone for Cursor<'a, K, V> {
+    fn clone(&self) -> Self {
+        Cursor {
+            current: self.current,
+            root: self.root,
+        }
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K: 'a + fmt::Debug, V: 'a + fmt::Debug> fmt::Debug for Cursor<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cursor").field(&self.key_value()).finish()
+    }
+}
+
+/// A cursor over a `BTreeMap` with editing operations.
+///
+/// A `CursorMut` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the map during iteration. This is because the lifetime of its yielded
+/// references is tied to its own lifetime, instead of just the underlying map. This means
+/// cursors cannot yield multiple elements at once.
+///
+/// Like [`Cursor`], a `CursorMut` always points to an element in the map or to the "ghost"
+/// non-element between the last and the first element.
+///
+/// This `struct` is created by the [`lower_bound_mut`] and [`upper_bound_mut`] methods on
+/// [`BTreeMap`].
+///
+/// [`Cursor`]: struct.Cursor.html
+/// [`BTreeMap`]: struct.BTreeMap.html
+/// [`lower_bound_mut`]: struct.BTreeMap.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.BTreeMap.html#method.upper_bound_mut
+#[unstable(feature = "btree_cursors", issue = "0")]
+pub struct CursorMut<
+    'a,
+    K: 'a,
+    V: 'a,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: 'a + Alloc + Clone = Global,
+> {
+    current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: *mut node::Root<K, V>,
+    length: &'a mut usize,
+    alloc: &'a A,
+
+    // Be invariant in `K` and `V`
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+unsafe impl<'a, K: Send, V: Send, A: Alloc + Clone + Sync> Send for CursorMut<'a, K, V, A> {}
+#[unstable(feature = "btree_cursors", issue = "0")]
+unsafe impl<'a, K: Sync, V: Sync, A: Alloc + Clone + Sync> Sync for CursorMut<'a, K, V, A> {}
+
+#[unstable(feature = "btree_cursors", issue = "0")]
+impl<'a, K: 'a + fmt::Debug, V: 'a + fmt::Debug, A: Alloc + Clone> fmt::Debug
+    for CursorMut<'a, K, V, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CursorMut").field(&self.key_value()).finish()
+    }
+}
+
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
 /// This `enum` is constructed from the [`entry`] method on [`BTreeMap`].
@@ -435,20 +542,25 @@ impl<'a, K: 'a + fmt::Debug, V: 'a + fmt::Debug> fmt::Debug for RangeMut<'a, K,
 /// [`BTreeMap`]: struct.BTreeMap.html
 /// [`entry`]: struct.BTreeMap.html#method.entry
 #[stable(feature = "rust1", since = "1.0.0")]
-pub enum Entry<'a, K: 'a, V: 'a> {
+pub enum Entry<
+    'a,
+    K: 'a,
+    V: 'a,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: 'a + Alloc + Clone = Global,
+> {
     /// A vacant entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Vacant(#[stable(feature = "rust1", since = "1.0.0")]
-           VacantEntry<'a, K, V>),
+           VacantEntry<'a, K, V, A>),
 
     /// An occupied entry.
     #[stable(feature = "rust1", since = "1.0.0")]
     Occupied(#[stable(feature = "rust1", since = "1.0.0")]
-             OccupiedEntry<'a, K, V>),
+             OccupiedEntry<'a, K, V, A>),
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for Entry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a + Debug, A: Alloc + Clone> Debug for Entry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Vacant(ref v) => f.debug_tuple("Entry")
@@ -466,17 +578,23 @@ impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for Entry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct VacantEntry<'a, K: 'a, V: 'a> {
+pub struct VacantEntry<
+    'a,
+    K: 'a,
+    V: 'a,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: 'a + Alloc + Clone = Global,
+> {
     key: K,
     handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>,
     length: &'a mut usize,
+    alloc: &'a A,
 
     // Be invariant in `K` and `V`
     _marker: PhantomData<&'a mut (K, V)>,
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a> Debug for VacantEntry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a, A: Alloc + Clone> Debug for VacantEntry<'a, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("VacantEntry")
          .field(self.key())
@@ -489,17 +607,25 @@ impl<'a, K: 'a + Debug + Ord, V: 'a> Debug for VacantEntry<'a, K, V> {
 ///
 /// [`Entry`]: enum.Entry.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct OccupiedEntry<'a, K: 'a, V: 'a> {
+pub struct OccupiedEntry<
+    'a,
+    K: 'a,
+    V: 'a,
+    #[unstable(feature = "allocator_api", issue = "32838")] A: 'a + Alloc + Clone = Global,
+> {
     handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
 
     length: &'a mut usize,
+    alloc: &'a A,
 
     // Be invariant in `K` and `V`
     _marker: PhantomData<&'a mut (K, V)>,
 }
 
 #[stable(feature= "debug_btree_map", since = "1.12.0")]
-impl<'a, K: 'a + Debug + Ord, V: 'a + Debug> Debug for OccupiedEntry<'a, K, V> {
+impl<'a, K: 'a + Debug + Ord, V: 'a + Debug, A: Alloc + Clone> Debug
+    for OccupiedEntry<'a, K, V, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
          .field("key", self.key())
@@ -531,9 +657,34 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> BTreeMap<K, V> {
+        BTreeMap::new_in(Global)
+    }
+}
+
+impl<K: Ord, V, A: Alloc + Clone> BTreeMap<K, V, A> {
+    /// Makes a new empty BTreeMap whose nodes are allocated from `alloc`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new_in(Global);
+    ///
+    /// // entries can now be inserted into the empty map
+    /// map.insert(1, "a");
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(alloc: A) -> BTreeMap<K, V, A> {
         BTreeMap {
             root: node::Root::shared_empty_root(),
             length: 0,
+            alloc,
         }
     }
 
@@ -553,7 +704,8 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn clear(&mut self) {
-        *self = BTreeMap::new();
+        let alloc = self.alloc.clone();
+        *self = BTreeMap::new_in(alloc);
     }
 
     /// Returns a reference to the value corresponding to the key.
@@ -704,6 +856,44 @@ impl<K: Ord, V> BTreeMap<K, V> {
         }
     }
 
+    /// Inserts a key-value pair into the map, returning an error instead of
+    /// aborting if a node can't be allocated.
+    ///
+    /// On success, this behaves like [`insert`]. If an error is returned, the
+    /// map is unchanged, and `key` and `value` are dropped.
+    ///
+    /// [`insert`]: #method.insert
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// assert_eq!(map.try_insert(37, "a"), Ok(None));
+    /// assert_eq!(map.try_insert(37, "b"), Ok(Some("a")));
+    /// assert_eq!(map[&37], "b");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, CollectionAllocErr> {
+        if self.root.is_shared_root() {
+            self.root = node::Root::try_new_leaf(self.alloc.clone())?;
+        }
+        match search::search_tree(self.root.as_mut(), &key) {
+            Found(handle) => Ok(Some(mem::replace(handle.into_kv_mut().1, value))),
+            GoDown(handle) => {
+                // Allocate every node the insertion needs before touching the tree.
+                let mut pool = NodePool::try_for_insert(&handle, self.alloc.clone())?;
+                insert_at_leaf_edge_with(handle, key, value, &mut pool);
+                self.length += 1;
+                Ok(None)
+            }
+        }
+    }
+
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     ///
@@ -732,6 +922,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 Some(OccupiedEntry {
                          handle,
                          length: &mut self.length,
+                         alloc: &self.alloc,
                          _marker: PhantomData,
                      }
                      .remove())
@@ -782,8 +973,10 @@ impl<K: Ord, V> BTreeMap<K, V> {
         }
 
         // First, we merge `self` and `other` into a sorted sequence in linear time.
-        let self_iter = mem::replace(self, BTreeMap::new()).into_iter();
-        let other_iter = mem::replace(other, BTreeMap::new()).into_iter();
+        let self_alloc = self.alloc.clone();
+        let other_alloc = other.alloc.clone();
+        let self_iter = mem::replace(self, BTreeMap::new_in(self_alloc)).into_iter();
+        let other_iter = mem::replace(other, BTreeMap::new_in(other_alloc)).into_iter();
         let iter = MergeIter {
             left: self_iter.peekable(),
             right: other_iter.peekable(),
@@ -878,6 +1071,168 @@ impl<K: Ord, V> BTreeMap<K, V> {
         }
     }
 
+    /// Returns a [`Cursor`] pointing at the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first element of the
+    /// map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: next_kv(lower_bound_edge(root, bound)),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first element of the
+    /// map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.lower_bound_mut(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<K, V, A>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = &mut self.root as *mut _;
+        CursorMut {
+            current: next_kv(lower_bound_edge(self.root.as_mut(), bound)),
+            root,
+            length: &mut self.length,
+            alloc: &self.alloc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing at the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last element of the
+    /// map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<K, V>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = self.root.as_ref();
+        Cursor {
+            current: prev_kv(upper_bound_edge(root, bound)),
+            root,
+        }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost" non-element is
+    /// returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last element of the
+    /// map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.upper_bound_mut(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<K, V, A>
+        where K: Borrow<Q>, Q: Ord
+    {
+        let root = &mut self.root as *mut _;
+        CursorMut {
+            current: prev_kv(upper_bound_edge(self.root.as_mut(), bound)),
+            root,
+            length: &mut self.length,
+            alloc: &self.alloc,
+            _marker: PhantomData,
+        }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -897,7 +1252,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// assert_eq!(count["a"], 3);
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn entry(&mut self, key: K) -> Entry<K, V> {
+    pub fn entry(&mut self, key: K) -> Entry<K, V, A> {
         // FIXME(@porglezomp) Avoid allocating if we don't insert
         self.ensure_root_is_owned();
         match search::search_tree(self.root.as_mut(), &key) {
@@ -905,6 +1260,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 Occupied(OccupiedEntry {
                     handle,
                     length: &mut self.length,
+                    alloc: &self.alloc,
                     _marker: PhantomData,
                 })
             }
@@ -913,6 +1269,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                     key,
                     handle,
                     length: &mut self.length,
+                    alloc: &self.alloc,
                     _marker: PhantomData,
                 })
             }
@@ -946,7 +1303,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                         }
                         Err(node) => {
                             // We are at the top, create a new root node and push there.
-                            open_node = node.into_root_mut().push_level();
+                            open_node = node.into_root_mut().push_level(self.alloc.clone());
                             break;
                         }
                     }
@@ -954,9 +1311,9 @@ impl<K: Ord, V> BTreeMap<K, V> {
 
                 // Push key-value pair and new right subtree.
                 let tree_height = open_node.height() - 1;
-                let mut right_tree = node::Root::new_leaf();
+                let mut right_tree = node::Root::new_leaf(self.alloc.clone());
                 for _ in 0..tree_height {
-                    right_tree.push_level();
+                    right_tree.push_level(self.alloc.clone());
                 }
                 open_node.push(key, value, right_tree);
 
@@ -1024,15 +1381,15 @@ impl<K: Ord, V> BTreeMap<K, V> {
         where K: Borrow<Q>
     {
         if self.is_empty() {
-            return Self::new();
+            return Self::new_in(self.alloc.clone());
         }
 
         let total_num = self.len();
 
-        let mut right = Self::new();
-        right.root = node::Root::new_leaf();
+        let mut right = Self::new_in(self.alloc.clone());
+        right.root = node::Root::new_leaf(right.alloc.clone());
         for _ in 0..(self.root.as_ref().height()) {
-            right.root.push_level();
+            right.root.push_level(right.alloc.clone());
         }
 
         {
@@ -1116,7 +1473,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                     break;
                 }
             }
-            self.root.pop_level();
+            self.root.pop_level(self.alloc.clone());
         }
     }
 
@@ -1130,7 +1487,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 let mut last_kv = node.last_kv();
 
                 if last_kv.can_merge() {
-                    cur_node = last_kv.merge().descend();
+                    cur_node = last_kv.merge(self.alloc.clone()).descend();
                 } else {
                     let right_len = last_kv.reborrow().right_edge().descend().len();
                     // `MINLEN + 1` to avoid readjust if merge happens on the next level.
@@ -1156,7 +1513,7 @@ impl<K: Ord, V> BTreeMap<K, V> {
                 let mut first_kv = node.first_kv();
 
                 if first_kv.can_merge() {
-                    cur_node = first_kv.merge().descend();
+                    cur_node = first_kv.merge(self.alloc.clone()).descend();
                 } else {
                     let left_len = first_kv.reborrow().left_edge().descend().len();
                     if left_len < node::MIN_LEN + 1 {
@@ -1173,13 +1530,13 @@ impl<K: Ord, V> BTreeMap<K, V> {
     /// If the root node is the shared root node, allocate our own node.
     fn ensure_root_is_owned(&mut self) {
         if self.root.is_shared_root() {
-            self.root = node::Root::new_leaf();
+            self.root = node::Root::new_leaf(self.alloc.clone());
         }
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: 'a, V: 'a> IntoIterator for &'a BTreeMap<K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc + Clone> IntoIterator for &'a BTreeMap<K, V, A> {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
 
@@ -1239,7 +1596,7 @@ impl<'a, K, V> Clone for Iter<'a, K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: 'a, V: 'a> IntoIterator for &'a mut BTreeMap<K, V> {
+impl<'a, K: 'a, V: 'a, A: Alloc + Clone> IntoIterator for &'a mut BTreeMap<K, V, A> {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
 
@@ -1289,13 +1646,14 @@ impl<'a, K: 'a, V: 'a> ExactSizeIterator for IterMut<'a, K, V> {
 impl<'a, K, V> FusedIterator for IterMut<'a, K, V> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> IntoIterator for BTreeMap<K, V> {
+impl<K, V, A: Alloc + Clone> IntoIterator for BTreeMap<K, V, A> {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
+    type IntoIter = IntoIter<K, V, A>;
 
-    fn into_iter(self) -> IntoIter<K, V> {
+    fn into_iter(self) -> IntoIter<K, V, A> {
         let root1 = unsafe { ptr::read(&self.root).into_ref() };
         let root2 = unsafe { ptr::read(&self.root).into_ref() };
+        let alloc = unsafe { ptr::read(&self.alloc) };
         let len = self.length;
         mem::forget(self);
 
@@ -1303,12 +1661,13 @@ impl<K, V> IntoIterator for BTreeMap<K, V> {
             front: first_leaf_edge(root1),
             back: last_leaf_edge(root2),
             length: len,
+            alloc,
         }
     }
 }
 
 #[stable(feature = "btree_drop", since = "1.7.0")]
-impl<K, V> Drop for IntoIter<K, V> {
+impl<K, V, A: Alloc + Clone> Drop for IntoIter<K, V, A> {
     fn drop(&mut self) {
         self.for_each(drop);
         unsafe {
@@ -1317,9 +1676,9 @@ impl<K, V> Drop for IntoIter<K, V> {
                 return;
             }
 
-            if let Some(first_parent) = leaf_node.deallocate_and_ascend() {
+            if let Some(first_parent) = leaf_node.deallocate_and_ascend(self.alloc.clone()) {
                 let mut cur_node = first_parent.into_node();
-                while let Some(parent) = cur_node.deallocate_and_ascend() {
+                while let Some(parent) = cur_node.deallocate_and_ascend(self.alloc.clone()) {
                     cur_node = parent.into_node()
                 }
             }
@@ -1328,7 +1687,7 @@ impl<K, V> Drop for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> Iterator for IntoIter<K, V> {
+impl<K, V, A: Alloc + Clone> Iterator for IntoIter<K, V, A> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<(K, V)> {
@@ -1348,7 +1707,7 @@ impl<K, V> Iterator for IntoIter<K, V> {
                 return Some((k, v));
             }
             Err(last_edge) => unsafe {
-                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend())
+                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend(self.alloc.clone()))
             },
         };
 
@@ -1361,7 +1720,8 @@ impl<K, V> Iterator for IntoIter<K, V> {
                     return Some((k, v));
                 }
                 Err(last_edge) => unsafe {
-                    cur_handle = unwrap_unchecked(last_edge.into_node().deallocate_and_ascend());
+                    let parent = last_edge.into_node().deallocate_and_ascend(self.alloc.clone());
+                    cur_handle = unwrap_unchecked(parent);
                 },
             }
         }
@@ -1373,7 +1733,7 @@ impl<K, V> Iterator for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc + Clone> DoubleEndedIterator for IntoIter<K, V, A> {
     fn next_back(&mut self) -> Option<(K, V)> {
         if self.length == 0 {
             return None;
@@ -1391,7 +1751,7 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
                 return Some((k, v));
             }
             Err(last_edge) => unsafe {
-                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend())
+                unwrap_unchecked(last_edge.into_node().deallocate_and_ascend(self.alloc.clone()))
             },
         };
 
@@ -1404,7 +1764,8 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
                     return Some((k, v));
                 }
                 Err(last_edge) => unsafe {
-                    cur_handle = unwrap_unchecked(last_edge.into_node().deallocate_and_ascend());
+                    let parent = last_edge.into_node().deallocate_and_ascend(self.alloc.clone());
+                    cur_handle = unwrap_unchecked(parent);
                 },
             }
         }
@@ -1412,14 +1773,14 @@ impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V, A: Alloc + Clone> ExactSizeIterator for IntoIter<K, V, A> {
     fn len(&self) -> usize {
         self.length
     }
 }
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V, A: Alloc + Clone> FusedIterator for IntoIter<K, V, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a, K, V> Iterator for Keys<'a, K, V> {
@@ -1718,6 +2079,316 @@ impl<'a, K, V> RangeMut<'a, K, V> {
     }
 }
 
+impl<'a, K, V> Cursor<'a, K, V> {
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the map. If it is pointing to the last element of the map
+    /// then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        let edge = match self.current {
+            Some(kv) => next_leaf_edge(kv),
+            None => first_leaf_edge(self.root),
+        };
+        self.current = next_kv(edge);
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the map. If it is pointing to the first element of the map
+    /// then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        let edge = match self.current {
+            Some(kv) => prev_leaf_edge(kv),
+            None => last_leaf_edge(self.root),
+        };
+        self.current = prev_kv(edge);
+    }
+
+    /// Returns a reference to the key of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&'a K> {
+        self.key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&'a V> {
+        self.key_value().map(|(_, v)| v)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&'a K, &'a V)> {
+        self.current.map(|kv| kv.into_kv())
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the first
+    /// element of the map. If it is pointing to the last element of the map then this
+    /// returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        let mut next = self.clone();
+        next.move_next();
+        next.key_value()
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the last
+    /// element of the map. If it is pointing to the first element of the map then this
+    /// returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        let mut prev = self.clone();
+        prev.move_prev();
+        prev.key_value()
+    }
+}
+
+impl<'a, K, V, A: Alloc + Clone> CursorMut<'a, K, V, A> {
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the map. If it is pointing to the last element of the map
+    /// then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_next(&mut self) {
+        let edge = match self.current.take() {
+            Some(kv) => next_leaf_edge(kv),
+            None => first_leaf_edge(self.root_mut()),
+        };
+        self.current = next_kv(edge);
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the map. If it is pointing to the first element of the map
+    /// then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn move_prev(&mut self) {
+        let edge = match self.current.take() {
+            Some(kv) => prev_leaf_edge(kv),
+            None => last_leaf_edge(self.root_mut()),
+        };
+        self.current = prev_kv(edge);
+    }
+
+    /// Returns a reference to the key of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key(&self) -> Option<&K> {
+        self.key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value(&self) -> Option<&V> {
+        self.key_value().map(|(_, v)| v)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value(&self) -> Option<(&K, &V)> {
+        self.current.as_ref().map(|kv| kv.reborrow().into_kv())
+    }
+
+    /// Returns a mutable reference to the value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.current.as_mut().map(|kv| kv.kv_mut().1)
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of the
+    /// element that the cursor is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost"
+    /// non-element.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn key_value_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.current.as_mut().map(|kv| {
+            let (k, v) = kv.kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the first
+    /// element of the map. If it is pointing to the last element of the map then this
+    /// returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_next(&self) -> Option<(&K, &V)> {
+        self.as_cursor().peek_next()
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the last
+    /// element of the map. If it is pointing to the first element of the map then this
+    /// returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn peek_prev(&self) -> Option<(&K, &V)> {
+        self.as_cursor().peek_prev()
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the `CursorMut`, which
+    /// means it cannot outlive the `CursorMut` and that the `CursorMut` is frozen for
+    /// the lifetime of the `Cursor`.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn as_cursor(&self) -> Cursor<K, V> {
+        Cursor {
+            current: self.current.as_ref().map(|kv| kv.reborrow()),
+            root: unsafe { (*self.root).as_ref() },
+        }
+    }
+
+    fn root_mut(&mut self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+        unsafe { (*self.root).as_mut() }
+    }
+}
+
+impl<'a, K: Ord, V, A: Alloc + Clone> CursorMut<'a, K, V, A> {
+    /// Inserts a new element into the `BTreeMap` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `BTreeMap`. The cursor keeps pointing at the same
+    /// element.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    /// - the given key compares less than or equal to the current element (if any).
+    /// - the given key compares greater than or equal to the next element (if any).
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn insert_after(&mut self, key: K, value: V) {
+        if let Some(current) = self.key() {
+            assert!(*current < key, "key must be ordered above the current element");
+        }
+        if let Some((next, _)) = self.peek_next() {
+            assert!(key < *next, "key must be ordered below the next element");
+        }
+
+        let edge = match self.current.take() {
+            Some(kv) => next_leaf_edge(kv),
+            None => {
+                self.ensure_root_is_owned();
+                let edge = first_leaf_edge(self.root_mut());
+                insert_at_leaf_edge(edge, key, value, self.alloc.clone());
+                *self.length += 1;
+                return;
+            }
+        };
+        let (key_ptr, _) = insert_at_leaf_edge(edge, key, value, self.alloc.clone());
+        *self.length += 1;
+
+        // Splitting nodes may have moved the current element around, so find it again as
+        // the predecessor of the new one.
+        let key = unsafe { &*key_ptr };
+        self.current = prev_kv(upper_bound_edge(self.root_mut(), Excluded(key)));
+    }
+
+    /// Inserts a new element into the `BTreeMap` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `BTreeMap`. The cursor keeps pointing at the same
+    /// element.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    /// - the given key compares greater than or equal to the current element (if any).
+    /// - the given key compares less than or equal to the previous element (if any).
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn insert_before(&mut self, key: K, value: V) {
+        if let Some(current) = self.key() {
+            assert!(key < *current, "key must be ordered below the current element");
+        }
+        if let Some((prev, _)) = self.peek_prev() {
+            assert!(*prev < key, "key must be ordered above the previous element");
+        }
+
+        let edge = match self.current.take() {
+            Some(kv) => prev_leaf_edge(kv),
+            None => {
+                self.ensure_root_is_owned();
+                let edge = last_leaf_edge(self.root_mut());
+                insert_at_leaf_edge(edge, key, value, self.alloc.clone());
+                *self.length += 1;
+                return;
+            }
+        };
+        let (key_ptr, _) = insert_at_leaf_edge(edge, key, value, self.alloc.clone());
+        *self.length += 1;
+
+        // Splitting nodes may have moved the current element around, so find it again as
+        // the successor of the new one.
+        let key = unsafe { &*key_ptr };
+        self.current = next_kv(lower_bound_edge(self.root_mut(), Excluded(key)));
+    }
+
+    /// Removes the current element from the `BTreeMap`.
+    ///
+    /// The element that was removed is returned, and the cursor is moved to point to the
+    /// next element in the `BTreeMap`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element is
+    /// removed and `None` is returned. The cursor is not moved in this case.
+    #[unstable(feature = "btree_cursors", issue = "0")]
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let current = self.current.take()?;
+        *self.length -= 1;
+        let (key, value) = remove_kv_at(current, self.alloc.clone());
+
+        // Rebalancing may have moved the following element around, so search for it.
+        self.current = next_kv(lower_bound_edge(self.root_mut(), Excluded(&key)));
+        Some((key, value))
+    }
+
+    fn ensure_root_is_owned(&mut self) {
+        unsafe {
+            if (*self.root).is_shared_root() {
+                *self.root = node::Root::new_leaf(self.alloc.clone());
+            }
+        }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<K: Ord, V> FromIterator<(K, V)> for BTreeMap<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> BTreeMap<K, V> {
@@ -1728,7 +2399,7 @@ impl<K: Ord, V> FromIterator<(K, V)> for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Ord, V> Extend<(K, V)> for BTreeMap<K, V> {
+impl<K: Ord, V, A: Alloc + Clone> Extend<(K, V)> for BTreeMap<K, V, A> {
     #[inline]
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (k, v) in iter {
@@ -1738,14 +2409,14 @@ impl<K: Ord, V> Extend<(K, V)> for BTreeMap<K, V> {
 }
 
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, K: Ord + Copy, V: Copy> Extend<(&'a K, &'a V)> for BTreeMap<K, V> {
+impl<'a, K: Ord + Copy, V: Copy, A: Alloc + Clone> Extend<(&'a K, &'a V)> for BTreeMap<K, V, A> {
     fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
         self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Hash, V: Hash> Hash for BTreeMap<K, V> {
+impl<K: Hash, V: Hash, A: Alloc + Clone> Hash for BTreeMap<K, V, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         for elt in self {
             elt.hash(state);
@@ -1762,40 +2433,40 @@ impl<K: Ord, V> Default for BTreeMap<K, V> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: PartialEq, V: PartialEq> PartialEq for BTreeMap<K, V> {
-    fn eq(&self, other: &BTreeMap<K, V>) -> bool {
+impl<K: PartialEq, V: PartialEq, A: Alloc + Clone> PartialEq for BTreeMap<K, V, A> {
+    fn eq(&self, other: &BTreeMap<K, V, A>) -> bool {
         self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Eq, V: Eq> Eq for BTreeMap<K, V> {}
+impl<K: Eq, V: Eq, A: Alloc + Clone> Eq for BTreeMap<K, V, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: PartialOrd, V: PartialOrd> PartialOrd for BTreeMap<K, V> {
+impl<K: PartialOrd, V: PartialOrd, A: Alloc + Clone> PartialOrd for BTreeMap<K, V, A> {
     #[inline]
-    fn partial_cmp(&self, other: &BTreeMap<K, V>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &BTreeMap<K, V, A>) -> Option<Ordering> {
         self.iter().partial_cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Ord, V: Ord> Ord for BTreeMap<K, V> {
+impl<K: Ord, V: Ord, A: Alloc + Clone> Ord for BTreeMap<K, V, A> {
     #[inline]
-    fn cmp(&self, other: &BTreeMap<K, V>) -> Ordering {
+    fn cmp(&self, other: &BTreeMap<K, V, A>) -> Ordering {
         self.iter().cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<K: Debug, V: Debug> Debug for BTreeMap<K, V> {
+impl<K: Debug, V: Debug, A: Alloc + Clone> Debug for BTreeMap<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, K: Ord, Q: ?Sized, V> Index<&'a Q> for BTreeMap<K, V>
+impl<'a, K: Ord, Q: ?Sized, V, A: Alloc + Clone> Index<&'a Q> for BTreeMap<K, V, A>
     where K: Borrow<Q>,
           Q: Ord
 {
@@ -1838,6 +2509,104 @@ fn last_leaf_edge<BorrowType, K, V>
     }
 }
 
+/// Finds the leaf edge immediately after the given key/value pair.
+fn next_leaf_edge<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    match kv.force() {
+        Leaf(leaf_kv) => leaf_kv.right_edge(),
+        Internal(internal_kv) => first_leaf_edge(internal_kv.right_edge().descend()),
+    }
+}
+
+/// Finds the leaf edge immediately before the given key/value pair.
+fn prev_leaf_edge<BorrowType, K, V>
+    (kv: Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>)
+     -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge> {
+    match kv.force() {
+        Leaf(leaf_kv) => leaf_kv.left_edge(),
+        Internal(internal_kv) => last_leaf_edge(internal_kv.left_edge().descend()),
+    }
+}
+
+/// Finds the key/value pair immediately after the given leaf edge, if there is one.
+fn next_kv<BorrowType, K, V>
+    (edge: Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let mut edge = edge.forget_node_type();
+    loop {
+        edge = match edge.right_kv() {
+            Ok(kv) => return Some(kv),
+            Err(last_edge) => match last_edge.into_node().ascend() {
+                Ok(parent_edge) => parent_edge.forget_node_type(),
+                Err(_) => return None,
+            },
+        }
+    }
+}
+
+/// Finds the key/value pair immediately before the given leaf edge, if there is one.
+fn prev_kv<BorrowType, K, V>
+    (edge: Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>)
+     -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>> {
+    let mut edge = edge.forget_node_type();
+    loop {
+        edge = match edge.left_kv() {
+            Ok(kv) => return Some(kv),
+            Err(first_edge) => match first_edge.into_node().ascend() {
+                Ok(parent_edge) => parent_edge.forget_node_type(),
+                Err(_) => return None,
+            },
+        }
+    }
+}
+
+/// Finds the leaf edge immediately before the first key that lies above `bound`.
+fn lower_bound_edge<BorrowType, K, V, Q: ?Sized>(
+    mut node: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>
+) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+        where Q: Ord, K: Borrow<Q>
+{
+    loop {
+        let idx = match bound {
+            Included(key) => search::search_linear(&node, key).0,
+            Excluded(key) => match search::search_linear(&node, key) {
+                (i, true) => i + 1,
+                (i, false) => i,
+            },
+            Unbounded => 0,
+        };
+        match Handle::new_edge(node, idx).force() {
+            Leaf(edge) => return edge,
+            Internal(edge) => node = edge.descend(),
+        }
+    }
+}
+
+/// Finds the leaf edge immediately after the last key that lies below `bound`.
+fn upper_bound_edge<BorrowType, K, V, Q: ?Sized>(
+    mut node: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>
+) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+        where Q: Ord, K: Borrow<Q>
+{
+    loop {
+        let idx = match bound {
+            Included(key) => match search::search_linear(&node, key) {
+                (i, true) => i + 1,
+                (i, false) => i,
+            },
+            Excluded(key) => search::search_linear(&node, key).0,
+            Unbounded => node.len(),
+        };
+        match Handle::new_edge(node, idx).force() {
+            Leaf(edge) => return edge,
+            Internal(edge) => node = edge.descend(),
+        }
+    }
+}
+
 fn range_search<BorrowType, K, V, Q: ?Sized, R: RangeBounds<Q>>(
     root1: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
     root2: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
@@ -1923,7 +2692,7 @@ unsafe fn unwrap_unchecked<T>(val: Option<T>) -> T {
     })
 }
 
-impl<K, V> BTreeMap<K, V> {
+impl<K, V, A: Alloc + Clone> BTreeMap<K, V, A> {
     /// Gets an iterator over the entries of the map, sorted by key.
     ///
     /// # Examples
@@ -2098,7 +2867,7 @@ impl<K, V> BTreeMap<K, V> {
     }
 }
 
-impl<'a, K: Ord, V> Entry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc + Clone> Entry<'a, K, V, A> {
     /// Ensures a value is in the entry by inserting the default if empty, and returns
     /// a mutable reference to the value in the entry.
     ///
@@ -2195,7 +2964,7 @@ impl<'a, K: Ord, V> Entry<'a, K, V> {
     }
 }
 
-impl<'a, K: Ord, V: Default> Entry<'a, K, V> {
+impl<'a, K: Ord, V: Default, A: Alloc + Clone> Entry<'a, K, V, A> {
     #[stable(feature = "entry_or_default", since = "1.28.0")]
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
@@ -2221,7 +2990,7 @@ impl<'a, K: Ord, V: Default> Entry<'a, K, V> {
 
 }
 
-impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc + Clone> VacantEntry<'a, K, V, A> {
     /// Gets a reference to the key that would be used when inserting a value
     /// through the VacantEntry.
     ///
@@ -2278,46 +3047,12 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
     pub fn insert(self, value: V) -> &'a mut V {
         *self.length += 1;
 
-        let out_ptr;
-
-        let mut ins_k;
-        let mut ins_v;
-        let mut ins_edge;
-
-        let mut cur_parent = match self.handle.insert(self.key, value) {
-            (Fit(handle), _) => return handle.into_kv_mut().1,
-            (Split(left, k, v, right), ptr) => {
-                ins_k = k;
-                ins_v = v;
-                ins_edge = right;
-                out_ptr = ptr;
-                left.ascend().map_err(|n| n.into_root_mut())
-            }
-        };
-
-        loop {
-            match cur_parent {
-                Ok(parent) => {
-                    match parent.insert(ins_k, ins_v, ins_edge) {
-                        Fit(_) => return unsafe { &mut *out_ptr },
-                        Split(left, k, v, right) => {
-                            ins_k = k;
-                            ins_v = v;
-                            ins_edge = right;
-                            cur_parent = left.ascend().map_err(|n| n.into_root_mut());
-                        }
-                    }
-                }
-                Err(root) => {
-                    root.push_level().push(ins_k, ins_v, ins_edge);
-                    return unsafe { &mut *out_ptr };
-                }
-            }
-        }
+        let (_, val_ptr) = insert_at_leaf_edge(self.handle, self.key, value, self.alloc.clone());
+        unsafe { &mut *val_ptr }
     }
 }
 
-impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+impl<'a, K: Ord, V, A: Alloc + Clone> OccupiedEntry<'a, K, V, A> {
     /// Gets a reference to the key in the entry.
     ///
     /// # Examples
@@ -2481,49 +3216,114 @@ impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
 
     fn remove_kv(self) -> (K, V) {
         *self.length -= 1;
+        remove_kv_at(self.handle, self.alloc.clone())
+    }
+}
+
+/// Inserts a key/value pair at a leaf edge, splitting nodes on the way up as needed.
+///
+/// The returned pointers point to the inserted key and value.
+fn insert_at_leaf_edge<'a, K, V, A: Alloc + Clone>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>,
+    key: K,
+    value: V,
+    alloc: A
+) -> (*mut K, *mut V) {
+    insert_at_leaf_edge_with(handle, key, value, &mut NodePool::new(alloc))
+}
+
+/// Like `insert_at_leaf_edge`, but takes the nodes needed for splits from `pool`, falling back
+/// to fresh allocations once it runs dry.
+fn insert_at_leaf_edge_with<'a, K, V, A: Alloc + Clone>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>,
+    key: K,
+    value: V,
+    pool: &mut NodePool<K, V, A>
+) -> (*mut K, *mut V) {
+    let out_ptrs;
+
+    let mut ins_k;
+    let mut ins_v;
+    let mut ins_edge;
+
+    let mut cur_parent = match handle.insert(key, value, pool) {
+        (Fit(_), key_ptr, val_ptr) => return (key_ptr, val_ptr),
+        (Split(left, k, v, right), key_ptr, val_ptr) => {
+            ins_k = k;
+            ins_v = v;
+            ins_edge = right;
+            out_ptrs = (key_ptr, val_ptr);
+            left.ascend().map_err(|n| n.into_root_mut())
+        }
+    };
 
-        let (small_leaf, old_key, old_val) = match self.handle.force() {
-            Leaf(leaf) => {
-                let (hole, old_key, old_val) = leaf.remove();
-                (hole.into_node(), old_key, old_val)
+    loop {
+        match cur_parent {
+            Ok(parent) => {
+                match parent.insert(ins_k, ins_v, ins_edge, pool) {
+                    Fit(_) => return out_ptrs,
+                    Split(left, k, v, right) => {
+                        ins_k = k;
+                        ins_v = v;
+                        ins_edge = right;
+                        cur_parent = left.ascend().map_err(|n| n.into_root_mut());
+                    }
+                }
             }
-            Internal(mut internal) => {
-                let key_loc = internal.kv_mut().0 as *mut K;
-                let val_loc = internal.kv_mut().1 as *mut V;
+            Err(root) => {
+                root.push_level_with(pool).push(ins_k, ins_v, ins_edge);
+                return out_ptrs;
+            }
+        }
+    }
+}
 
-                let to_remove = first_leaf_edge(internal.right_edge().descend()).right_kv().ok();
-                let to_remove = unsafe { unwrap_unchecked(to_remove) };
+/// Removes the key/value pair behind `handle`, rebalancing the tree as needed.
+fn remove_kv_at<'a, K, V, A: Alloc + Clone>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
+    alloc: A
+) -> (K, V) {
+    let (small_leaf, old_key, old_val) = match handle.force() {
+        Leaf(leaf) => {
+            let (hole, old_key, old_val) = leaf.remove();
+            (hole.into_node(), old_key, old_val)
+        }
+        Internal(mut internal) => {
+            let key_loc = internal.kv_mut().0 as *mut K;
+            let val_loc = internal.kv_mut().1 as *mut V;
 
-                let (hole, key, val) = to_remove.remove();
+            let to_remove = first_leaf_edge(internal.right_edge().descend()).right_kv().ok();
+            let to_remove = unsafe { unwrap_unchecked(to_remove) };
 
-                let old_key = unsafe { mem::replace(&mut *key_loc, key) };
-                let old_val = unsafe { mem::replace(&mut *val_loc, val) };
+            let (hole, key, val) = to_remove.remove();
 
-                (hole.into_node(), old_key, old_val)
-            }
-        };
+            let old_key = unsafe { mem::replace(&mut *key_loc, key) };
+            let old_val = unsafe { mem::replace(&mut *val_loc, val) };
 
-        // Handle underflow
-        let mut cur_node = small_leaf.forget_type();
-        while cur_node.len() < node::CAPACITY / 2 {
-            match handle_underfull_node(cur_node) {
-                AtRoot => break,
-                EmptyParent(_) => unreachable!(),
-                Merged(parent) => {
-                    if parent.len() == 0 {
-                        // We must be at the root
-                        parent.into_root_mut().pop_level();
-                        break;
-                    } else {
-                        cur_node = parent.forget_type();
-                    }
+            (hole.into_node(), old_key, old_val)
+        }
+    };
+
+    // Handle underflow
+    let mut cur_node = small_leaf.forget_type();
+    while cur_node.len() < node::CAPACITY / 2 {
+        match handle_underfull_node(cur_node, alloc.clone()) {
+            AtRoot => break,
+            EmptyParent(_) => unreachable!(),
+            Merged(parent) => {
+                if parent.len() == 0 {
+                    // We must be at the root
+                    parent.into_root_mut().pop_level(alloc);
+                    break;
+                } else {
+                    cur_node = parent.forget_type();
                 }
-                Stole(_) => break,
             }
+            Stole(_) => break,
         }
-
-        (old_key, old_val)
     }
+
+    (old_key, old_val)
 }
 
 enum UnderflowResult<'a, K, V> {
@@ -2533,8 +3333,10 @@ enum UnderflowResult<'a, K, V> {
     Stole(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
 }
 
-fn handle_underfull_node<'a, K, V>(node: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>)
-                                   -> UnderflowResult<'a, K, V> {
+fn handle_underfull_node<'a, K, V, A: Alloc>(
+    node: NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>,
+    alloc: A
+) -> UnderflowResult<'a, K, V> {
     let parent = if let Ok(parent) = node.ascend() {
         parent
     } else {
@@ -2554,7 +3356,7 @@ fn handle_underfull_node<'a, K, V>(node: NodeRef<marker::Mut<'a>, K, V, marker::
     };
 
     if handle.can_merge() {
-        Merged(handle.merge().into_node())
+        Merged(handle.merge(alloc).into_node())
     } else {
         if is_left {
             handle.steal_left();
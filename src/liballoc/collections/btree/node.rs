@@ -46,8 +46,9 @@ use core::mem;
 use core::ptr::{self, Unique, NonNull};
 use core::slice;
 
-use alloc::{Global, Alloc, Layout};
-use boxed::Box;
+use alloc::{Global, Alloc, Layout, handle_alloc_error};
+use collections::CollectionAllocErr;
+use vec::Vec;
 
 const B: usize = 6;
 pub const MIN_LEN: usize = B - 1;
@@ -151,6 +152,110 @@ impl<K, V> InternalNode<K, V> {
     }
 }
 
+/// A set of preallocated, uninitialized nodes, from which an insertion can take the nodes it needs
+/// to split. This allows fallible insertion: every allocation happens up front, before the tree is
+/// modified. When the pool runs dry, nodes are allocated from `alloc` as usual.
+pub struct NodePool<K, V, A: Alloc + Clone = Global> {
+    leaf: Option<NonNull<LeafNode<K, V>>>,
+    internals: Vec<NonNull<InternalNode<K, V>>>,
+    alloc: A,
+}
+
+impl<K, V, A: Alloc + Clone> NodePool<K, V, A> {
+    /// Creates an empty pool, which doesn't allocate.
+    pub fn new(alloc: A) -> Self {
+        NodePool { leaf: None, internals: Vec::new(), alloc }
+    }
+
+    /// Allocates every node that inserting a key/value pair at `edge` will need, so that the
+    /// insertion itself cannot fail.
+    pub fn try_for_insert<BorrowType>(
+        edge: &Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>,
+        alloc: A,
+    ) -> Result<Self, CollectionAllocErr> {
+        let mut pool = NodePool::new(alloc);
+
+        let mut node = edge.node.as_leaf() as *const LeafNode<K, V>;
+        if unsafe { (*node).len as usize } < CAPACITY {
+            return Ok(pool);
+        }
+
+        // The leaf splits, and so does every full ancestor above it.
+        let mut internals = 0;
+        unsafe {
+            loop {
+                let parent = (*node).parent;
+                if parent.is_null() {
+                    // The splits reach the root, so a new root is pushed on top.
+                    internals += 1;
+                    break;
+                }
+                node = &(*parent).data;
+                if ((*node).len as usize) < CAPACITY {
+                    break;
+                }
+                internals += 1;
+            }
+        }
+
+        pool.leaf = Some(try_alloc_node(&mut pool.alloc)?);
+        pool.internals = Vec::try_with_capacity(internals)?;
+        for _ in 0..internals {
+            let node = try_alloc_node(&mut pool.alloc)?;
+            pool.internals.push(node);
+        }
+        Ok(pool)
+    }
+
+    fn take_leaf(&mut self) -> NonNull<LeafNode<K, V>> {
+        let node = match self.leaf.take() {
+            Some(node) => node,
+            None => alloc_node(&mut self.alloc),
+        };
+        unsafe { ptr::write(node.as_ptr(), LeafNode::new()); }
+        node
+    }
+
+    fn take_internal(&mut self) -> NonNull<InternalNode<K, V>> {
+        let node = match self.internals.pop() {
+            Some(node) => node,
+            None => alloc_node(&mut self.alloc),
+        };
+        unsafe { ptr::write(node.as_ptr(), InternalNode::new()); }
+        node
+    }
+}
+
+/// Allocates uninitialized memory for a node with `alloc`, aborting if the allocation fails.
+///
+/// Nodes are allocated directly rather than through `Box`, so that the tree works with any
+/// allocator, whatever its size.
+fn alloc_node<T, A: Alloc>(alloc: &mut A) -> NonNull<T> {
+    let layout = Layout::new::<T>();
+    match unsafe { alloc.alloc(layout) } {
+        Ok(ptr) => ptr.cast(),
+        Err(_) => handle_alloc_error(layout),
+    }
+}
+
+/// Like `alloc_node`, but returns an error if the allocation fails.
+fn try_alloc_node<T, A: Alloc>(alloc: &mut A) -> Result<NonNull<T>, CollectionAllocErr> {
+    Ok(unsafe { alloc.alloc(Layout::new::<T>())? }.cast())
+}
+
+impl<K, V, A: Alloc + Clone> Drop for NodePool<K, V, A> {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(node) = self.leaf.take() {
+                self.alloc.dealloc(node.cast(), Layout::new::<LeafNode<K, V>>());
+            }
+            for node in self.internals.drain(..) {
+                self.alloc.dealloc(node.cast(), Layout::new::<InternalNode<K, V>>());
+            }
+        }
+    }
+}
+
 /// An owned pointer to a node. This basically is either `Box<LeafNode<K, V>>` or
 /// `Box<InternalNode<K, V>>`. However, it contains no information as to which of the two types
 /// of nodes is actually behind the box, and, partially due to this lack of information, has no
@@ -160,14 +265,13 @@ struct BoxedNode<K, V> {
 }
 
 impl<K, V> BoxedNode<K, V> {
-    fn from_leaf(node: Box<LeafNode<K, V>>) -> Self {
-        BoxedNode { ptr: Box::into_unique(node) }
+    // The node is freed with the allocator of the tree.
+    fn from_leaf(node: NonNull<LeafNode<K, V>>) -> Self {
+        BoxedNode { ptr: Unique::from(node) }
     }
 
-    fn from_internal(node: Box<InternalNode<K, V>>) -> Self {
-        unsafe {
-            BoxedNode { ptr: Unique::new_unchecked(Box::into_raw(node) as *mut LeafNode<K, V>) }
-        }
+    fn from_internal(node: NonNull<InternalNode<K, V>>) -> Self {
+        BoxedNode { ptr: Unique::from(node.cast::<LeafNode<K, V>>()) }
     }
 
     unsafe fn from_ptr(ptr: NonNull<LeafNode<K, V>>) -> Self {
@@ -205,13 +309,25 @@ impl<K, V> Root<K, V> {
         }
     }
 
-    pub fn new_leaf() -> Self {
+    pub fn new_leaf<A: Alloc>(mut alloc: A) -> Self {
+        let node = alloc_node(&mut alloc);
+        unsafe { ptr::write(node.as_ptr(), LeafNode::new()); }
         Root {
-            node: BoxedNode::from_leaf(Box::new(unsafe { LeafNode::new() })),
+            node: BoxedNode::from_leaf(node),
             height: 0
         }
     }
 
+    /// Like `new_leaf`, but returns an error if the allocation fails.
+    pub fn try_new_leaf<A: Alloc>(mut alloc: A) -> Result<Self, CollectionAllocErr> {
+        let node = try_alloc_node(&mut alloc)?;
+        unsafe { ptr::write(node.as_ptr(), LeafNode::new()); }
+        Ok(Root {
+            node: BoxedNode::from_leaf(node),
+            height: 0
+        })
+    }
+
     pub fn as_ref(&self)
             -> NodeRef<marker::Immut, K, V, marker::LeafOrInternal> {
         NodeRef {
@@ -244,11 +360,19 @@ impl<K, V> Root<K, V> {
 
     /// Adds a new internal node with a single edge, pointing to the previous root, and make that
     /// new node the root. This increases the height by 1 and is the opposite of `pop_level`.
-    pub fn push_level(&mut self)
+    pub fn push_level<A: Alloc + Clone>(&mut self, alloc: A)
+            -> NodeRef<marker::Mut, K, V, marker::Internal> {
+        self.push_level_with(&mut NodePool::new(alloc))
+    }
+
+    /// Like `push_level`, but takes the new node from `pool` if it has one.
+    pub fn push_level_with<A: Alloc + Clone>(&mut self, pool: &mut NodePool<K, V, A>)
             -> NodeRef<marker::Mut, K, V, marker::Internal> {
         debug_assert!(!self.is_shared_root());
-        let mut new_node = Box::new(unsafe { InternalNode::new() });
-        new_node.edges[0] = unsafe { BoxedNode::from_ptr(self.node.as_ptr()) };
+        let new_node = pool.take_internal();
+        unsafe {
+            (*new_node.as_ptr()).edges[0] = BoxedNode::from_ptr(self.node.as_ptr());
+        }
 
         self.node = BoxedNode::from_internal(new_node);
         self.height += 1;
@@ -271,7 +395,7 @@ impl<K, V> Root<K, V> {
     /// the tree consists only of a leaf node. As it is intended only to be called when the root
     /// has only one edge, no cleanup is done on any of the other children are elements of the root.
     /// This decreases the height by 1 and is the opposite of `push_level`.
-    pub fn pop_level(&mut self) {
+    pub fn pop_level<A: Alloc>(&mut self, mut alloc: A) {
         debug_assert!(self.height > 0);
 
         let top = self.node.ptr;
@@ -287,7 +411,7 @@ impl<K, V> Root<K, V> {
         self.as_mut().as_leaf_mut().parent = ptr::null();
 
         unsafe {
-            Global.dealloc(NonNull::from(top).cast(), Layout::new::<InternalNode<K, V>>());
+            alloc.dealloc(NonNull::from(top).cast(), Layout::new::<InternalNode<K, V>>());
         }
     }
 }
@@ -465,7 +589,7 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::Leaf> {
     /// Similar to `ascend`, gets a reference to a node's parent node, but also
     /// deallocate the current node in the process. This is unsafe because the
     /// current node will still be accessible despite being deallocated.
-    pub unsafe fn deallocate_and_ascend(self) -> Option<
+    pub unsafe fn deallocate_and_ascend<A: Alloc>(self, mut alloc: A) -> Option<
         Handle<
             NodeRef<
                 marker::Owned,
@@ -478,7 +602,7 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::Leaf> {
         debug_assert!(!self.is_shared_root());
         let node = self.node;
         let ret = self.ascend().ok();
-        Global.dealloc(node.cast(), Layout::new::<LeafNode<K, V>>());
+        alloc.dealloc(node.cast(), Layout::new::<LeafNode<K, V>>());
         ret
     }
 }
@@ -487,7 +611,7 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
     /// Similar to `ascend`, gets a reference to a node's parent node, but also
     /// deallocate the current node in the process. This is unsafe because the
     /// current node will still be accessible despite being deallocated.
-    pub unsafe fn deallocate_and_ascend(self) -> Option<
+    pub unsafe fn deallocate_and_ascend<A: Alloc>(self, mut alloc: A) -> Option<
         Handle<
             NodeRef<
                 marker::Owned,
@@ -499,7 +623,7 @@ impl<K, V> NodeRef<marker::Owned, K, V, marker::Internal> {
     > {
         let node = self.node;
         let ret = self.ascend().ok();
-        Global.dealloc(node.cast(), Layout::new::<InternalNode<K, V>>());
+        alloc.dealloc(node.cast(), Layout::new::<InternalNode<K, V>>());
         ret
     }
 }
@@ -959,8 +1083,8 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
     /// this edge. This method assumes that there is enough space in the node for the new
     /// pair to fit.
     ///
-    /// The returned pointer points to the inserted value.
-    fn insert_fit(&mut self, key: K, val: V) -> *mut V {
+    /// The returned pointers point to the inserted key and value.
+    fn insert_fit(&mut self, key: K, val: V) -> (*mut K, *mut V) {
         // Necessary for correctness, but in a private module
         debug_assert!(self.node.len() < CAPACITY);
         debug_assert!(!self.node.is_shared_root());
@@ -971,24 +1095,26 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
 
             self.node.as_leaf_mut().len += 1;
 
-            self.node.vals_mut().get_unchecked_mut(self.idx)
+            (self.node.keys_mut().get_unchecked_mut(self.idx),
+             self.node.vals_mut().get_unchecked_mut(self.idx))
         }
     }
 
     /// Inserts a new key/value pair between the key/value pairs to the right and left of
-    /// this edge. This method splits the node if there isn't enough room.
+    /// this edge. This method splits the node if there isn't enough room, taking the new node
+    /// from `pool` if it has one.
     ///
-    /// The returned pointer points to the inserted value.
-    pub fn insert(mut self, key: K, val: V)
-            -> (InsertResult<'a, K, V, marker::Leaf>, *mut V) {
+    /// The returned pointers point to the inserted key and value.
+    pub fn insert<A: Alloc + Clone>(mut self, key: K, val: V, pool: &mut NodePool<K, V, A>)
+            -> (InsertResult<'a, K, V, marker::Leaf>, *mut K, *mut V) {
 
         if self.node.len() < CAPACITY {
-            let ptr = self.insert_fit(key, val);
-            (InsertResult::Fit(Handle::new_kv(self.node, self.idx)), ptr)
+            let (key_ptr, val_ptr) = self.insert_fit(key, val);
+            (InsertResult::Fit(Handle::new_kv(self.node, self.idx)), key_ptr, val_ptr)
         } else {
             let middle = Handle::new_kv(self.node, B);
-            let (mut left, k, v, mut right) = middle.split();
-            let ptr = if self.idx <= B {
+            let (mut left, k, v, mut right) = middle.split(pool);
+            let (key_ptr, val_ptr) = if self.idx <= B {
                 unsafe {
                     Handle::new_edge(left.reborrow_mut(), self.idx).insert_fit(key, val)
                 }
@@ -1000,7 +1126,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
                     ).insert_fit(key, val)
                 }
             };
-            (InsertResult::Split(left, k, v, right), ptr)
+            (InsertResult::Split(left, k, v, right), key_ptr, val_ptr)
         }
     }
 }
@@ -1053,8 +1179,10 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
 
     /// Inserts a new key/value pair and an edge that will go to the right of that new pair
     /// between this edge and the key/value pair to the right of this edge. This method splits
-    /// the node if there isn't enough room.
-    pub fn insert(mut self, key: K, val: V, edge: Root<K, V>)
+    /// the node if there isn't enough room, taking the new node from `pool` if it has one.
+    pub fn insert<A: Alloc + Clone>(mut self,
+                                    key: K, val: V, edge: Root<K, V>,
+                                    pool: &mut NodePool<K, V, A>)
             -> InsertResult<'a, K, V, marker::Internal> {
 
         // Necessary for correctness, but this is an internal module
@@ -1065,7 +1193,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
             InsertResult::Fit(Handle::new_kv(self.node, self.idx))
         } else {
             let middle = Handle::new_kv(self.node, B);
-            let (mut left, k, v, mut right) = middle.split();
+            let (mut left, k, v, mut right) = middle.split(pool);
             if self.idx <= B {
                 unsafe {
                     Handle::new_edge(left.reborrow_mut(), self.idx).insert_fit(key, val, edge);
@@ -1137,13 +1265,14 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV>
     /// - The node is truncated to only contain the key/value pairs to the right of
     ///   this handle.
     /// - The key and value pointed to by this handle and extracted.
-    /// - All the key/value pairs to the right of this handle are put into a newly
-    ///   allocated node.
-    pub fn split(mut self)
+    /// - All the key/value pairs to the right of this handle are put into a new node,
+    ///   taken from `pool` if it has one.
+    pub fn split<A: Alloc + Clone>(mut self, pool: &mut NodePool<K, V, A>)
             -> (NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, K, V, Root<K, V>) {
         debug_assert!(!self.node.is_shared_root());
         unsafe {
-            let mut new_node = Box::new(LeafNode::new());
+            let new_ptr = pool.take_leaf();
+            let new_node = &mut *new_ptr.as_ptr();
 
             let k = ptr::read(self.node.keys().get_unchecked(self.idx));
             let v = ptr::read(self.node.vals().get_unchecked(self.idx));
@@ -1168,7 +1297,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV>
                 self.node,
                 k, v,
                 Root {
-                    node: BoxedNode::from_leaf(new_node),
+                    node: BoxedNode::from_leaf(new_ptr),
                     height: 0
                 }
             )
@@ -1196,11 +1325,12 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
     ///   right of this handle.
     /// - The key and value pointed to by this handle and extracted.
     /// - All the edges and key/value pairs to the right of this handle are put into
-    ///   a newly allocated node.
-    pub fn split(mut self)
+    ///   a new node, taken from `pool` if it has one.
+    pub fn split<A: Alloc + Clone>(mut self, pool: &mut NodePool<K, V, A>)
             -> (NodeRef<marker::Mut<'a>, K, V, marker::Internal>, K, V, Root<K, V>) {
         unsafe {
-            let mut new_node = Box::new(InternalNode::new());
+            let new_ptr = pool.take_internal();
+            let new_node = &mut *new_ptr.as_ptr();
 
             let k = ptr::read(self.node.keys().get_unchecked(self.idx));
             let v = ptr::read(self.node.vals().get_unchecked(self.idx));
@@ -1228,7 +1358,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
             new_node.data.len = new_len as u16;
 
             let mut new_root = Root {
-                node: BoxedNode::from_internal(new_node),
+                node: BoxedNode::from_internal(new_ptr),
                 height,
             };
 
@@ -1266,7 +1396,7 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
     /// child of the underlying node, returning an edge referencing that new child.
     ///
     /// Assumes that this edge `.can_merge()`.
-    pub fn merge(mut self)
+    pub fn merge<A: Alloc>(mut self, mut alloc: A)
             -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge> {
         let self1 = unsafe { ptr::read(&self) };
         let self2 = unsafe { ptr::read(&self) };
@@ -1320,12 +1450,12 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::
                     ).correct_parent_link();
                 }
 
-                Global.dealloc(
+                alloc.dealloc(
                     right_node.node.cast(),
                     Layout::new::<InternalNode<K, V>>(),
                 );
             } else {
-                Global.dealloc(
+                alloc.dealloc(
                     right_node.node.cast(),
                     Layout::new::<LeafNode<K, V>>(),
                 );
@@ -1519,6 +1649,21 @@ unsafe fn move_edges<K, V>(
     dest.correct_childrens_parent_links(dest_offset, dest_offset + count);
 }
 
+impl<BorrowType, K, V, NodeType, HandleType>
+        Handle<NodeRef<BorrowType, K, V, NodeType>, HandleType> {
+
+    /// Removes any static information about whether the underlying node is a `Leaf` or an
+    /// `Internal` node.
+    pub fn forget_node_type(self)
+            -> Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, HandleType> {
+        Handle {
+            node: self.node.forget_type(),
+            idx: self.idx,
+            _marker: PhantomData
+        }
+    }
+}
+
 impl<BorrowType, K, V, HandleType>
         Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, HandleType> {
 
@@ -20,6 +20,7 @@ use core::ops::{BitOr, BitAnd, BitXor, Sub, RangeBounds};
 
 use borrow::Borrow;
 use collections::btree_map::{self, BTreeMap, Keys};
+use collections::CollectionAllocErr;
 use super::Recover;
 
 // FIXME(conventions): implement bounded iterators
@@ -547,6 +548,31 @@ impl<T: Ord> BTreeSet<T> {
         self.map.insert(value, ()).is_none()
     }
 
+    /// Adds a value to the set, returning an error instead of aborting if a
+    /// node can't be allocated.
+    ///
+    /// On success, this behaves like [`insert`]. If an error is returned, the
+    /// set is unchanged and `value` is dropped.
+    ///
+    /// [`insert`]: #method.insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::BTreeSet;
+    ///
+    /// let mut set = BTreeSet::new();
+    ///
+    /// assert_eq!(set.try_insert(2), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(false));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CollectionAllocErr> {
+        Ok(self.map.try_insert(value, ())?.is_none())
+    }
+
     /// Adds a value to the set, replacing the existing value, if any, that is equal to the given
     /// one. Returns the replaced value.
     ///
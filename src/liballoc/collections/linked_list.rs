@@ -32,6 +32,7 @@ use core::ptr::NonNull;
 
 use boxed::Box;
 use super::SpecExtend;
+use super::CollectionAllocErr;
 
 /// A doubly-linked list with owned nodes.
 ///
@@ -603,6 +604,29 @@ impl<T> LinkedList<T> {
         self.push_front_node(box Node::new(elt));
     }
 
+    /// Adds an element first in the list, returning an error instead of
+    /// aborting if the node can't be allocated.
+    ///
+    /// If an error is returned, the list is unchanged and `elt` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut dl = LinkedList::new();
+    ///
+    /// dl.try_push_front(2).expect("out of memory");
+    /// dl.try_push_front(1).expect("out of memory");
+    /// assert_eq!(dl.front().unwrap(), &1);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push_front(&mut self, elt: T) -> Result<(), CollectionAllocErr> {
+        self.push_front_node(Box::try_new(Node::new(elt))?);
+        Ok(())
+    }
+
     /// Removes the first element and returns it, or `None` if the list is
     /// empty.
     ///
@@ -644,6 +668,28 @@ impl<T> LinkedList<T> {
         self.push_back_node(box Node::new(elt));
     }
 
+    /// Appends an element to the back of a list, returning an error instead of
+    /// aborting if the node can't be allocated.
+    ///
+    /// If an error is returned, the list is unchanged and `elt` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut d = LinkedList::new();
+    /// d.try_push_back(1).expect("out of memory");
+    /// d.try_push_back(3).expect("out of memory");
+    /// assert_eq!(3, *d.back().unwrap());
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push_back(&mut self, elt: T) -> Result<(), CollectionAllocErr> {
+        self.push_back_node(Box::try_new(Node::new(elt))?);
+        Ok(())
+    }
+
     /// Removes the last element from a list and returns it, or `None` if
     /// it is empty.
     ///
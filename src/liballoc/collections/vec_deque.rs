@@ -30,6 +30,7 @@ use core::slice;
 use core::hash::{Hash, Hasher};
 use core::cmp;
 
+use alloc::{Alloc, Global};
 use collections::CollectionAllocErr;
 use raw_vec::RawVec;
 use vec::Vec;
@@ -52,8 +53,14 @@ const MAXIMUM_ZST_CAPACITY: usize = 1 << (64 - 1); // Largest possible power of
 /// [`pop_front`]: #method.pop_front
 /// [`extend`]: #method.extend
 /// [`append`]: #method.append
+///
+/// Like [`Vec`], a `VecDeque` can take an optional second type parameter
+/// naming the allocator its buffer is obtained from. See [`new_in`].
+///
+/// [`Vec`]: ../../std/vec/struct.Vec.html
+/// [`new_in`]: #method.new_in
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct VecDeque<T> {
+pub struct VecDeque<T, A: Alloc = Global> {
     // tail and head are pointers into the buffer. Tail always points
     // to the first element that could be read, Head always points
     // to where data should be written.
@@ -61,18 +68,20 @@ pub struct VecDeque<T> {
     // is defined as the distance between the two.
     tail: usize,
     head: usize,
-    buf: RawVec<T>,
+    buf: RawVec<T, A>,
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Clone> Clone for VecDeque<T> {
-    fn clone(&self) -> VecDeque<T> {
-        self.iter().cloned().collect()
+impl<T: Clone, A: Alloc + Clone> Clone for VecDeque<T, A> {
+    fn clone(&self) -> VecDeque<T, A> {
+        let mut deq = VecDeque::with_capacity_in(self.len(), self.buf.alloc().clone());
+        deq.extend(self.iter().cloned());
+        deq
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for VecDeque<T> {
+unsafe impl<#[may_dangle] T, A: Alloc> Drop for VecDeque<T, A> {
     fn drop(&mut self) {
         let (front, back) = self.as_mut_slices();
         unsafe {
@@ -93,7 +102,7 @@ impl<T> Default for VecDeque<T> {
     }
 }
 
-impl<T> VecDeque<T> {
+impl<T, A: Alloc> VecDeque<T, A> {
     /// Marginally more convenient
     #[inline]
     fn ptr(&self) -> *mut T {
@@ -389,6 +398,67 @@ impl<T> VecDeque<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn with_capacity(n: usize) -> VecDeque<T> {
+        VecDeque::with_capacity_in(n, Global)
+    }
+
+    /// Creates an empty `VecDeque` with space for at least `n` elements,
+    /// returning an error instead of aborting if the allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let vector: VecDeque<u32> = VecDeque::try_with_capacity(10).expect("out of memory");
+    /// assert!(vector.capacity() >= 10);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity(n: usize) -> Result<VecDeque<T>, CollectionAllocErr> {
+        VecDeque::try_with_capacity_in(n, Global)
+    }
+}
+
+impl<T, A: Alloc> VecDeque<T, A> {
+    /// Creates an empty `VecDeque` which will allocate from `alloc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    /// use std::collections::VecDeque;
+    ///
+    /// let vector: VecDeque<u32, Global> = VecDeque::new_in(Global);
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(alloc: A) -> VecDeque<T, A> {
+        VecDeque::with_capacity_in(INITIAL_CAPACITY, alloc)
+    }
+
+    /// Creates an empty `VecDeque` with space for at least `n` elements,
+    /// which will allocate from `alloc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    /// use std::collections::VecDeque;
+    ///
+    /// let vector: VecDeque<u32, Global> = VecDeque::with_capacity_in(10, Global);
+    /// assert!(vector.capacity() >= 10);
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_in(n: usize, alloc: A) -> VecDeque<T, A> {
         // +1 since the ringbuffer always leaves one space empty
         let cap = cmp::max(n + 1, MINIMUM_CAPACITY + 1).next_power_of_two();
         assert!(cap > n, "capacity overflow");
@@ -396,10 +466,42 @@ impl<T> VecDeque<T> {
         VecDeque {
             tail: 0,
             head: 0,
-            buf: RawVec::with_capacity(cap),
+            buf: RawVec::with_capacity_in(cap, alloc),
         }
     }
 
+    /// Creates an empty `VecDeque` with space for at least `n` elements,
+    /// which will allocate from `alloc`, returning an error instead of aborting
+    /// if the allocation fails.
+    ///
+    /// See [`try_with_capacity`] for details.
+    ///
+    /// [`try_with_capacity`]: #method.try_with_capacity
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity_in(n: usize, alloc: A)
+                                -> Result<VecDeque<T, A>, CollectionAllocErr> {
+        // +1 since the ringbuffer always leaves one space empty
+        let cap = n.checked_add(1)
+            .and_then(|needed_cap| cmp::max(needed_cap, MINIMUM_CAPACITY + 1)
+                                       .checked_next_power_of_two())
+            .ok_or(CollectionAllocErr::CapacityOverflow)?;
+
+        let mut buf = RawVec::new_in(alloc);
+        buf.try_reserve_exact(0, cap)?;
+        Ok(VecDeque {
+            tail: 0,
+            head: 0,
+            buf,
+        })
+    }
+
+    /// Returns a reference to the allocator backing this `VecDeque`.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn alloc(&self) -> &A {
+        self.buf.alloc()
+    }
+
     /// Retrieves an element in the `VecDeque` by index.
     ///
     /// Element at index 0 is the front of the queue.
@@ -966,7 +1068,7 @@ impl<T> VecDeque<T> {
     /// ```
     #[inline]
     #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain<R>(&mut self, range: R) -> Drain<T>
+    pub fn drain<R>(&mut self, range: R) -> Drain<T, A>
         where R: RangeBounds<usize>
     {
         // Memory safety
@@ -1226,6 +1328,33 @@ impl<T> VecDeque<T> {
         }
     }
 
+    /// Prepends an element to the `VecDeque`, returning an error instead of
+    /// aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `VecDeque` is unchanged and `value` is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut d = VecDeque::new();
+    /// d.try_push_front(1).expect("out of memory");
+    /// d.try_push_front(2).expect("out of memory");
+    /// assert_eq!(d.front(), Some(&2));
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push_front(&mut self, value: T) -> Result<(), CollectionAllocErr> {
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        self.push_front(value);
+        Ok(())
+    }
+
     /// Appends an element to the back of the `VecDeque`.
     ///
     /// # Examples
@@ -1247,6 +1376,33 @@ impl<T> VecDeque<T> {
         unsafe { self.buffer_write(head, value) }
     }
 
+    /// Appends an element to the back of the `VecDeque`, returning an error
+    /// instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `VecDeque` is unchanged and `value` is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut buf = VecDeque::new();
+    /// buf.try_push_back(1).expect("out of memory");
+    /// buf.try_push_back(3).expect("out of memory");
+    /// assert_eq!(3, *buf.back().unwrap());
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push_back(&mut self, value: T) -> Result<(), CollectionAllocErr> {
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        self.push_back(value);
+        Ok(())
+    }
+
     /// Removes the last element from the `VecDeque` and returns it, or `None` if
     /// it is empty.
     ///
@@ -1569,6 +1725,37 @@ impl<T> VecDeque<T> {
         }
     }
 
+    /// Inserts an element at `index` within the `VecDeque`, returning an error
+    /// instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `VecDeque` is unchanged and `value` is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than `VecDeque`'s length
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut vec_deque: VecDeque<_> = vec!['a', 'b', 'c'].into();
+    /// vec_deque.try_insert(1, 'd').expect("out of memory");
+    /// assert_eq!(vec_deque, &['a', 'd', 'b', 'c']);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), CollectionAllocErr> {
+        assert!(index <= self.len(), "index out of bounds");
+        if self.is_full() {
+            self.try_reserve(1)?;
+        }
+        self.insert(index, value);
+        Ok(())
+    }
+
     /// Removes and returns the element at `index` from the `VecDeque`.
     /// Whichever end is closer to the removal point will be moved to make
     /// room, and all the affected elements will be moved to new positions.
@@ -1772,12 +1959,14 @@ impl<T> VecDeque<T> {
     /// ```
     #[inline]
     #[stable(feature = "split_off", since = "1.4.0")]
-    pub fn split_off(&mut self, at: usize) -> Self {
+    pub fn split_off(&mut self, at: usize) -> Self
+        where A: Clone
+    {
         let len = self.len();
         assert!(at <= len, "`at` out of bounds");
 
         let other_len = len - at;
-        let mut other = VecDeque::with_capacity(other_len);
+        let mut other = VecDeque::with_capacity_in(other_len, self.buf.alloc().clone());
 
         unsafe {
             let (first_half, second_half) = self.as_slices();
@@ -1886,7 +2075,7 @@ impl<T> VecDeque<T> {
     }
 }
 
-impl<T: Clone> VecDeque<T> {
+impl<T: Clone, A: Alloc> VecDeque<T, A> {
     /// Modifies the `VecDeque` in-place so that `len()` is equal to new_len,
     /// either by removing excess elements from the back or by appending clones of `value`
     /// to the back.
@@ -1918,6 +2107,29 @@ impl<T: Clone> VecDeque<T> {
             self.truncate(new_len);
         }
     }
+
+    /// Returns a copy of the `VecDeque`, returning an error instead of aborting
+    /// if the allocation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::collections::VecDeque;
+    ///
+    /// let buf: VecDeque<_> = vec![5, 10, 15].into();
+    /// let copy = buf.try_clone().expect("out of memory");
+    /// assert_eq!(buf, copy);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_clone(&self) -> Result<VecDeque<T, A>, CollectionAllocErr>
+        where A: Clone
+    {
+        let mut deq = VecDeque::try_with_capacity_in(self.len(), self.buf.alloc().clone())?;
+        deq.extend(self.iter().cloned());
+        Ok(deq)
+    }
 }
 
 /// Returns the index in the underlying buffer for a given logical element index.
@@ -2169,12 +2381,12 @@ impl<'a, T> FusedIterator for IterMut<'a, T> {}
 /// [`VecDeque`]: struct.VecDeque.html
 #[derive(Clone)]
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<T> {
-    inner: VecDeque<T>,
+pub struct IntoIter<T, A: Alloc = Global> {
+    inner: VecDeque<T, A>,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("IntoIter")
          .field(&self.inner)
@@ -2183,7 +2395,7 @@ impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Alloc> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
@@ -2199,7 +2411,7 @@ impl<T> Iterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Alloc> DoubleEndedIterator for IntoIter<T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.inner.pop_back()
@@ -2207,14 +2419,14 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ExactSizeIterator for IntoIter<T> {
+impl<T, A: Alloc> ExactSizeIterator for IntoIter<T, A> {
     fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
 }
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<T> FusedIterator for IntoIter<T> {}
+impl<T, A: Alloc> FusedIterator for IntoIter<T, A> {}
 
 /// A draining iterator over the elements of a `VecDeque`.
 ///
@@ -2224,15 +2436,15 @@ impl<T> FusedIterator for IntoIter<T> {}
 /// [`drain`]: struct.VecDeque.html#method.drain
 /// [`VecDeque`]: struct.VecDeque.html
 #[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a, T: 'a> {
+pub struct Drain<'a, T: 'a, A: 'a + Alloc = Global> {
     after_tail: usize,
     after_head: usize,
     iter: Iter<'a, T>,
-    deque: NonNull<VecDeque<T>>,
+    deque: NonNull<VecDeque<T, A>>,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
+impl<'a, T: 'a + fmt::Debug, A: Alloc> fmt::Debug for Drain<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("Drain")
          .field(&self.after_tail)
@@ -2243,12 +2455,12 @@ impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+unsafe impl<'a, T: Sync, A: Alloc + Sync> Sync for Drain<'a, T, A> {}
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Send, A: Alloc + Send> Send for Drain<'a, T, A> {}
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> Drop for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> Drop for Drain<'a, T, A> {
     fn drop(&mut self) {
         self.for_each(drop);
 
@@ -2295,7 +2507,7 @@ impl<'a, T: 'a> Drop for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> Iterator for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> Iterator for Drain<'a, T, A> {
     type Item = T;
 
     #[inline]
@@ -2310,7 +2522,7 @@ impl<'a, T: 'a> Iterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> DoubleEndedIterator for Drain<'a, T> {
+impl<'a, T: 'a, A: Alloc> DoubleEndedIterator for Drain<'a, T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back().map(|elt| unsafe { ptr::read(elt) })
@@ -2318,14 +2530,14 @@ impl<'a, T: 'a> DoubleEndedIterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T: 'a> ExactSizeIterator for Drain<'a, T> {}
+impl<'a, T: 'a, A: Alloc> ExactSizeIterator for Drain<'a, T, A> {}
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<'a, T: 'a> FusedIterator for Drain<'a, T> {}
+impl<'a, T: 'a, A: Alloc> FusedIterator for Drain<'a, T, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: PartialEq> PartialEq for VecDeque<A> {
-    fn eq(&self, other: &VecDeque<A>) -> bool {
+impl<T: PartialEq, A: Alloc> PartialEq for VecDeque<T, A> {
+    fn eq(&self, other: &VecDeque<T, A>) -> bool {
         if self.len() != other.len() {
             return false;
         }
@@ -2363,15 +2575,15 @@ impl<A: PartialEq> PartialEq for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Eq> Eq for VecDeque<A> {}
+impl<T: Eq, A: Alloc> Eq for VecDeque<T, A> {}
 
 macro_rules! __impl_slice_eq1 {
-    ($Lhs: ty, $Rhs: ty) => {
-        __impl_slice_eq1! { $Lhs, $Rhs, Sized }
+    ([$($vars:tt)*] $Lhs: ty, $Rhs: ty) => {
+        __impl_slice_eq1! { [$($vars)*] $Lhs, $Rhs, Sized }
     };
-    ($Lhs: ty, $Rhs: ty, $Bound: ident) => {
+    ([$($vars:tt)*] $Lhs: ty, $Rhs: ty, $Bound: ident) => {
         #[stable(feature = "vec-deque-partial-eq-slice", since = "1.17.0")]
-        impl<'a, 'b, A: $Bound, B> PartialEq<$Rhs> for $Lhs where A: PartialEq<B> {
+        impl<'a, 'b, T: $Bound, U, $($vars)*> PartialEq<$Rhs> for $Lhs where T: PartialEq<U> {
             fn eq(&self, other: &$Rhs) -> bool {
                 if self.len() != other.len() {
                     return false;
@@ -2384,16 +2596,16 @@ macro_rules! __impl_slice_eq1 {
     }
 }
 
-__impl_slice_eq1! { VecDeque<A>, Vec<B> }
-__impl_slice_eq1! { VecDeque<A>, &'b [B] }
-__impl_slice_eq1! { VecDeque<A>, &'b mut [B] }
+__impl_slice_eq1! { [A1: Alloc, A2: Alloc] VecDeque<T, A1>, Vec<U, A2> }
+__impl_slice_eq1! { [A: Alloc] VecDeque<T, A>, &'b [U] }
+__impl_slice_eq1! { [A: Alloc] VecDeque<T, A>, &'b mut [U] }
 
 macro_rules! array_impls {
     ($($N: expr)+) => {
         $(
-            __impl_slice_eq1! { VecDeque<A>, [B; $N] }
-            __impl_slice_eq1! { VecDeque<A>, &'b [B; $N] }
-            __impl_slice_eq1! { VecDeque<A>, &'b mut [B; $N] }
+            __impl_slice_eq1! { [A: Alloc] VecDeque<T, A>, [U; $N] }
+            __impl_slice_eq1! { [A: Alloc] VecDeque<T, A>, &'b [U; $N] }
+            __impl_slice_eq1! { [A: Alloc] VecDeque<T, A>, &'b mut [U; $N] }
         )+
     }
 }
@@ -2406,22 +2618,22 @@ array_impls! {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: PartialOrd> PartialOrd for VecDeque<A> {
-    fn partial_cmp(&self, other: &VecDeque<A>) -> Option<Ordering> {
+impl<T: PartialOrd, A: Alloc> PartialOrd for VecDeque<T, A> {
+    fn partial_cmp(&self, other: &VecDeque<T, A>) -> Option<Ordering> {
         self.iter().partial_cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Ord> Ord for VecDeque<A> {
+impl<T: Ord, A: Alloc> Ord for VecDeque<T, A> {
     #[inline]
-    fn cmp(&self, other: &VecDeque<A>) -> Ordering {
+    fn cmp(&self, other: &VecDeque<T, A>) -> Ordering {
         self.iter().cmp(other.iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A: Hash> Hash for VecDeque<A> {
+impl<T: Hash, A: Alloc> Hash for VecDeque<T, A> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.len().hash(state);
         let (a, b) = self.as_slices();
@@ -2431,19 +2643,19 @@ impl<A: Hash> Hash for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> Index<usize> for VecDeque<A> {
-    type Output = A;
+impl<T, A: Alloc> Index<usize> for VecDeque<T, A> {
+    type Output = T;
 
     #[inline]
-    fn index(&self, index: usize) -> &A {
+    fn index(&self, index: usize) -> &T {
         self.get(index).expect("Out of bounds access")
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> IndexMut<usize> for VecDeque<A> {
+impl<T, A: Alloc> IndexMut<usize> for VecDeque<T, A> {
     #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut A {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.get_mut(index).expect("Out of bounds access")
     }
 }
@@ -2460,19 +2672,19 @@ impl<A> FromIterator<A> for VecDeque<A> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> IntoIterator for VecDeque<T> {
+impl<T, A: Alloc> IntoIterator for VecDeque<T, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     /// Consumes the `VecDeque` into a front-to-back iterator yielding elements by
     /// value.
-    fn into_iter(self) -> IntoIter<T> {
+    fn into_iter(self) -> IntoIter<T, A> {
         IntoIter { inner: self }
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a VecDeque<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a VecDeque<T, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -2482,7 +2694,7 @@ impl<'a, T> IntoIterator for &'a VecDeque<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a mut VecDeque<T, A> {
     type Item = &'a mut T;
     type IntoIter = IterMut<'a, T>;
 
@@ -2492,8 +2704,8 @@ impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<A> Extend<A> for VecDeque<A> {
-    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
+impl<T, A: Alloc> Extend<T> for VecDeque<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for elt in iter {
             self.push_back(elt);
         }
@@ -2501,25 +2713,26 @@ impl<A> Extend<A> for VecDeque<A> {
 }
 
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, T: 'a + Copy> Extend<&'a T> for VecDeque<T> {
+impl<'a, T: 'a + Copy, A: Alloc> Extend<&'a T> for VecDeque<T, A> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         self.extend(iter.into_iter().cloned());
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: fmt::Debug> fmt::Debug for VecDeque<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for VecDeque<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_list().entries(self).finish()
     }
 }
 
 #[stable(feature = "vecdeque_vec_conversions", since = "1.10.0")]
-impl<T> From<Vec<T>> for VecDeque<T> {
-    fn from(mut other: Vec<T>) -> Self {
+impl<T, A: Alloc> From<Vec<T, A>> for VecDeque<T, A> {
+    fn from(mut other: Vec<T, A>) -> Self {
         unsafe {
             let other_buf = other.as_mut_ptr();
-            let mut buf = RawVec::from_raw_parts(other_buf, other.capacity());
+            let alloc = ptr::read(other.alloc());
+            let mut buf = RawVec::from_raw_parts_in(other_buf, other.capacity(), alloc);
             let len = other.len();
             mem::forget(other);
 
@@ -2541,8 +2754,8 @@ impl<T> From<Vec<T>> for VecDeque<T> {
 }
 
 #[stable(feature = "vecdeque_vec_conversions", since = "1.10.0")]
-impl<T> From<VecDeque<T>> for Vec<T> {
-    fn from(other: VecDeque<T>) -> Self {
+impl<T, A: Alloc> From<VecDeque<T, A>> for Vec<T, A> {
+    fn from(other: VecDeque<T, A>) -> Self {
         unsafe {
             let buf = other.buf.ptr();
             let len = other.len();
@@ -2604,7 +2817,8 @@ impl<T> From<VecDeque<T>> for Vec<T> {
                 }
 
             }
-            let out = Vec::from_raw_parts(buf, len, cap);
+            let alloc = ptr::read(other.buf.alloc());
+            let out = Vec::from_raw_parts_in(buf, len, cap, alloc);
             mem::forget(other);
             out
         }
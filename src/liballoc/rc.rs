@@ -261,6 +261,7 @@ use core::convert::From;
 use core::usize;
 
 use alloc::{Global, Alloc, Layout, box_free, handle_alloc_error};
+use collections::CollectionAllocErr;
 use string::String;
 use vec::Vec;
 
@@ -321,6 +322,31 @@ impl<T> Rc<T> {
         }
     }
 
+    /// Constructs a new `Rc<T>`, returning an error instead of aborting if the
+    /// allocation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// use std::rc::Rc;
+    ///
+    /// let five = Rc::try_new(5).expect("out of memory");
+    /// assert_eq!(*five, 5);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_new(value: T) -> Result<Rc<T>, CollectionAllocErr> {
+        Ok(Rc {
+            ptr: Box::into_raw_non_null(Box::try_new(RcBox {
+                strong: Cell::new(1),
+                weak: Cell::new(1),
+                value,
+            })?),
+            phantom: PhantomData,
+        })
+    }
+
     /// Returns the contained value, if the `Rc` has exactly one strong reference.
     ///
     /// Otherwise, an [`Err`][result] is returned with the same `Rc` that was
@@ -690,7 +716,7 @@ impl<T: ?Sized> Rc<T> {
                 value_size);
 
             // Free the allocation without dropping its contents
-            box_free(box_unique);
+            box_free(box_unique, Global);
 
             Rc { ptr: NonNull::new_unchecked(ptr), phantom: PhantomData }
         }
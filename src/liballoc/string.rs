@@ -57,6 +57,7 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use core::char::{decode_utf16, REPLACEMENT_CHARACTER};
+use core::cmp::Ordering;
 use core::fmt;
 use core::hash;
 use core::iter::{FromIterator, FusedIterator};
@@ -66,6 +67,7 @@ use core::ptr;
 use core::str::pattern::Pattern;
 use core::str::lossy;
 
+use alloc::{Alloc, Global};
 use collections::CollectionAllocErr;
 use borrow::{Cow, ToOwned};
 use boxed::Box;
@@ -289,10 +291,9 @@ use vec::Vec;
 /// [`&str`]: ../../std/primitive.str.html
 /// [`Deref`]: ../../std/ops/trait.Deref.html
 /// [`as_str()`]: struct.String.html#method.as_str
-#[derive(PartialOrd, Eq, Ord)]
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct String {
-    vec: Vec<u8>,
+pub struct String<A: Alloc = Global> {
+    vec: Vec<u8, A>,
 }
 
 /// A possible error value when converting a `String` from a UTF-8 byte vector.
@@ -332,8 +333,10 @@ pub struct String {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 #[derive(Debug)]
-pub struct FromUtf8Error {
-    bytes: Vec<u8>,
+pub struct FromUtf8Error<
+    #[unstable(feature = "allocator_api", issue = "32838")] A: Alloc = Global,
+> {
+    bytes: Vec<u8, A>,
     error: Utf8Error,
 }
 
@@ -428,88 +431,35 @@ impl String {
         String { vec: Vec::with_capacity(capacity) }
     }
 
-    // HACK(japaric): with cfg(test) the inherent `[T]::to_vec` method, which is
-    // required for this method definition, is not available. Since we don't
-    // require this method for testing purposes, I'll just stub it
-    // NB see the slice::hack module in slice.rs for more information
-    #[inline]
-    #[cfg(test)]
-    pub fn from_str(_: &str) -> String {
-        panic!("not available with cfg(test)");
-    }
-
-    /// Converts a vector of bytes to a `String`.
-    ///
-    /// A string slice ([`&str`]) is made of bytes ([`u8`]), and a vector of bytes
-    /// ([`Vec<u8>`]) is made of bytes, so this function converts between the
-    /// two. Not all byte slices are valid `String`s, however: `String`
-    /// requires that it is valid UTF-8. `from_utf8()` checks to ensure that
-    /// the bytes are valid UTF-8, and then does the conversion.
-    ///
-    /// If you are sure that the byte slice is valid UTF-8, and you don't want
-    /// to incur the overhead of the validity check, there is an unsafe version
-    /// of this function, [`from_utf8_unchecked`], which has the same behavior
-    /// but skips the check.
-    ///
-    /// This method will take care to not copy the vector, for efficiency's
-    /// sake.
-    ///
-    /// If you need a [`&str`] instead of a `String`, consider
-    /// [`str::from_utf8`].
-    ///
-    /// The inverse of this method is [`as_bytes`].
+    /// Creates a new empty `String` with a particular capacity, returning an
+    /// error instead of aborting if the allocation fails.
     ///
     /// # Errors
     ///
-    /// Returns [`Err`] if the slice is not UTF-8 with a description as to why the
-    /// provided bytes are not UTF-8. The vector you moved in is also included.
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
     ///
     /// # Examples
     ///
-    /// Basic usage:
-    ///
-    /// ```
-    /// // some bytes, in a vector
-    /// let sparkle_heart = vec![240, 159, 146, 150];
-    ///
-    /// // We know these bytes are valid, so we'll use `unwrap()`.
-    /// let sparkle_heart = String::from_utf8(sparkle_heart).unwrap();
-    ///
-    /// assert_eq!("💖", sparkle_heart);
-    /// ```
-    ///
-    /// Incorrect bytes:
-    ///
     /// ```
-    /// // some invalid bytes, in a vector
-    /// let sparkle_heart = vec![0, 159, 146, 150];
+    /// #![feature(try_reserve)]
     ///
-    /// assert!(String::from_utf8(sparkle_heart).is_err());
+    /// let s = String::try_with_capacity(10).expect("out of memory");
+    /// assert_eq!(s.capacity(), 10);
     /// ```
-    ///
-    /// See the docs for [`FromUtf8Error`] for more details on what you can do
-    /// with this error.
-    ///
-    /// [`from_utf8_unchecked`]: struct.String.html#method.from_utf8_unchecked
-    /// [`&str`]: ../../std/primitive.str.html
-    /// [`u8`]: ../../std/primitive.u8.html
-    /// [`Vec<u8>`]: ../../std/vec/struct.Vec.html
-    /// [`str::from_utf8`]: ../../std/str/fn.from_utf8.html
-    /// [`as_bytes`]: struct.String.html#method.as_bytes
-    /// [`FromUtf8Error`]: struct.FromUtf8Error.html
-    /// [`Err`]: ../../stdresult/enum.Result.html#variant.Err
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity(capacity: usize) -> Result<String, CollectionAllocErr> {
+        Ok(String { vec: Vec::try_with_capacity(capacity)? })
+    }
+
+    // HACK(japaric): with cfg(test) the inherent `[T]::to_vec` method, which is
+    // required for this method definition, is not available. Since we don't
+    // require this method for testing purposes, I'll just stub it
+    // NB see the slice::hack module in slice.rs for more information
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn from_utf8(vec: Vec<u8>) -> Result<String, FromUtf8Error> {
-        match str::from_utf8(&vec) {
-            Ok(..) => Ok(String { vec: vec }),
-            Err(e) => {
-                Err(FromUtf8Error {
-                    bytes: vec,
-                    error: e,
-                })
-            }
-        }
+    #[cfg(test)]
+    pub fn from_str(_: &str) -> String {
+        panic!("not available with cfg(test)");
     }
 
     /// Converts a slice of bytes to a string, including invalid characters.
@@ -696,6 +646,186 @@ impl String {
         String { vec: Vec::from_raw_parts(buf, length, capacity) }
     }
 
+    /// Converts this `String` into a [`Box`]`<`[`str`]`>`.
+    ///
+    /// This will drop any excess capacity.
+    ///
+    /// [`Box`]: ../../std/boxed/struct.Box.html
+    /// [`str`]: ../../std/primitive.str.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// let s = String::from("hello");
+    ///
+    /// let b = s.into_boxed_str();
+    /// ```
+    #[stable(feature = "box_str", since = "1.4.0")]
+    #[inline]
+    pub fn into_boxed_str(self) -> Box<str> {
+        let slice = self.vec.into_boxed_slice();
+        unsafe { from_boxed_utf8_unchecked(slice) }
+    }
+}
+
+impl<A: Alloc> String<A> {
+    /// Converts a vector of bytes to a `String`.
+    ///
+    /// A string slice ([`&str`]) is made of bytes ([`u8`]), and a vector of bytes
+    /// ([`Vec<u8>`]) is made of bytes, so this function converts between the
+    /// two. Not all byte slices are valid `String`s, however: `String`
+    /// requires that it is valid UTF-8. `from_utf8()` checks to ensure that
+    /// the bytes are valid UTF-8, and then does the conversion.
+    ///
+    /// If you are sure that the byte slice is valid UTF-8, and you don't want
+    /// to incur the overhead of the validity check, there is an unsafe version
+    /// of this function, [`from_utf8_unchecked`], which has the same behavior
+    /// but skips the check.
+    ///
+    /// This method will take care to not copy the vector, for efficiency's
+    /// sake.
+    ///
+    /// If you need a [`&str`] instead of a `String`, consider
+    /// [`str::from_utf8`].
+    ///
+    /// The inverse of this method is [`as_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the slice is not UTF-8 with a description as to why the
+    /// provided bytes are not UTF-8. The vector you moved in is also included.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// // some bytes, in a vector
+    /// let sparkle_heart = vec![240, 159, 146, 150];
+    ///
+    /// // We know these bytes are valid, so we'll use `unwrap()`.
+    /// let sparkle_heart = String::from_utf8(sparkle_heart).unwrap();
+    ///
+    /// assert_eq!("💖", sparkle_heart);
+    /// ```
+    ///
+    /// Incorrect bytes:
+    ///
+    /// ```
+    /// // some invalid bytes, in a vector
+    /// let sparkle_heart = vec![0, 159, 146, 150];
+    ///
+    /// assert!(String::from_utf8(sparkle_heart).is_err());
+    /// ```
+    ///
+    /// See the docs for [`FromUtf8Error`] for more details on what you can do
+    /// with this error.
+    ///
+    /// [`from_utf8_unchecked`]: struct.String.html#method.from_utf8_unchecked
+    /// [`&str`]: ../../std/primitive.str.html
+    /// [`u8`]: ../../std/primitive.u8.html
+    /// [`Vec<u8>`]: ../../std/vec/struct.Vec.html
+    /// [`str::from_utf8`]: ../../std/str/fn.from_utf8.html
+    /// [`as_bytes`]: struct.String.html#method.as_bytes
+    /// [`FromUtf8Error`]: struct.FromUtf8Error.html
+    /// [`Err`]: ../../stdresult/enum.Result.html#variant.Err
+    #[inline]
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn from_utf8(vec: Vec<u8, A>) -> Result<String<A>, FromUtf8Error<A>> {
+        match str::from_utf8(&vec) {
+            Ok(..) => Ok(String { vec: vec }),
+            Err(e) => {
+                Err(FromUtf8Error {
+                    bytes: vec,
+                    error: e,
+                })
+            }
+        }
+    }
+
+    /// Creates a new empty `String<A>` which will allocate from `alloc`.
+    ///
+    /// Like [`new`], this will not allocate any initial buffer.
+    ///
+    /// [`new`]: #method.new
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let mut s: String<Global> = String::new_in(Global);
+    /// s.push_str("hello");
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(alloc: A) -> String<A> {
+        String { vec: Vec::new_in(alloc) }
+    }
+
+    /// Creates a new empty `String<A>` with a particular capacity, which will
+    /// allocate from `alloc`.
+    ///
+    /// See [`with_capacity`] for details.
+    ///
+    /// [`with_capacity`]: #method.with_capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let s: String<Global> = String::with_capacity_in(10, Global);
+    /// assert_eq!(s.len(), 0);
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> String<A> {
+        String { vec: Vec::with_capacity_in(capacity, alloc) }
+    }
+
+    /// Creates a string from the items of an iterator, which will allocate
+    /// from `alloc`.
+    ///
+    /// This is the counterpart of [`FromIterator`], which only builds strings
+    /// using the global allocator. It accepts the same items.
+    ///
+    /// [`FromIterator`]: ../../std/iter/trait.FromIterator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let s = String::from_iter_in(vec!["ab", "cd"], Global);
+    /// assert_eq!(s, "abcd");
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn from_iter_in<T, I>(iter: I, alloc: A) -> String<A>
+        where I: IntoIterator<Item = T>,
+              String<A>: Extend<T>,
+    {
+        let mut buf = String::new_in(alloc);
+        buf.extend(iter);
+        buf
+    }
+
+    /// Returns a reference to the allocator backing this string.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn alloc(&self) -> &A {
+        self.vec.alloc()
+    }
+
     /// Converts a vector of bytes to a `String` without checking that the
     /// string contains valid UTF-8.
     ///
@@ -726,7 +856,7 @@ impl String {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> String {
+    pub unsafe fn from_utf8_unchecked(bytes: Vec<u8, A>) -> String<A> {
         String { vec: bytes }
     }
 
@@ -746,7 +876,7 @@ impl String {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn into_bytes(self) -> Vec<u8> {
+    pub fn into_bytes(self) -> Vec<u8, A> {
         self.vec
     }
 
@@ -806,6 +936,25 @@ impl String {
         self.vec.extend_from_slice(string.as_bytes())
     }
 
+    /// Appends a given string slice onto the end of this `String`, returning an
+    /// error instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `String` is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut s = String::from("foo");
+    /// s.try_push_str("bar").expect("out of memory");
+    /// assert_eq!("foobar", s);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), CollectionAllocErr> {
+        self.vec.try_extend_from_slice(string.as_bytes())
+    }
+
     /// Returns this `String`'s capacity, in bytes.
     ///
     /// # Examples
@@ -1069,6 +1218,27 @@ impl String {
         }
     }
 
+    /// Appends the given [`char`] to the end of this `String`, returning an error
+    /// instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `String` is unchanged.
+    ///
+    /// [`char`]: ../../std/primitive.char.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut s = String::from("abc");
+    /// s.try_push('1').expect("out of memory");
+    /// assert_eq!("abc1", s);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push(&mut self, ch: char) -> Result<(), CollectionAllocErr> {
+        self.try_push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
     /// Returns a byte slice of this `String`'s contents.
     ///
     /// The inverse of this method is [`from_utf8`].
@@ -1282,6 +1452,32 @@ impl String {
         }
     }
 
+    /// Inserts a character into this `String` at a byte position, returning an
+    /// error instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `String` is unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not
+    /// lie on a [`char`] boundary.
+    ///
+    /// [`char`]: ../../std/primitive.char.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut s = String::from("ac");
+    /// s.try_insert(1, 'b').expect("out of memory");
+    /// assert_eq!("abc", s);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert(&mut self, idx: usize, ch: char) -> Result<(), CollectionAllocErr> {
+        self.try_insert_str(idx, ch.encode_utf8(&mut [0; 4]))
+    }
+
     unsafe fn insert_bytes(&mut self, idx: usize, bytes: &[u8]) {
         let len = self.len();
         let amt = bytes.len();
@@ -1329,6 +1525,55 @@ impl String {
         }
     }
 
+    /// Inserts a string slice into this `String` at a byte position, returning
+    /// an error instead of aborting if the buffer can't be grown.
+    ///
+    /// If an error is returned, the `String` is unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not
+    /// lie on a [`char`] boundary.
+    ///
+    /// [`char`]: ../../std/primitive.char.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut s = String::from("bar");
+    /// s.try_insert_str(0, "foo").expect("out of memory");
+    /// assert_eq!("foobar", s);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert_str(&mut self, idx: usize, string: &str)
+                          -> Result<(), CollectionAllocErr> {
+        assert!(self.is_char_boundary(idx));
+        self.vec.try_reserve(string.len())?;
+
+        unsafe {
+            self.insert_bytes(idx, string.as_bytes());
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the `String`, returning an error instead of aborting if
+    /// the allocation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let s = String::from("foo");
+    /// assert_eq!(s.try_clone().expect("out of memory"), "foo");
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_clone(&self) -> Result<Self, CollectionAllocErr> where A: Clone {
+        Ok(String { vec: self.vec.try_clone()? })
+    }
+
     /// Returns a mutable reference to the contents of this `String`.
     ///
     /// # Safety
@@ -1355,7 +1600,7 @@ impl String {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8, A> {
         &mut self.vec
     }
 
@@ -1422,7 +1667,9 @@ impl String {
     /// ```
     #[inline]
     #[stable(feature = "string_split_off", since = "1.16.0")]
-    pub fn split_off(&mut self, at: usize) -> String {
+    pub fn split_off(&mut self, at: usize) -> String<A>
+        where A: Clone
+    {
         assert!(self.is_char_boundary(at));
         let other = self.vec.split_off(at);
         unsafe { String::from_utf8_unchecked(other) }
@@ -1483,7 +1730,7 @@ impl String {
     /// assert_eq!(s, "");
     /// ```
     #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain<R>(&mut self, range: R) -> Drain
+    pub fn drain<R>(&mut self, range: R) -> Drain<A>
         where R: RangeBounds<usize>
     {
         // Memory safety
@@ -1566,32 +1813,9 @@ impl String {
             self.as_mut_vec()
         }.splice(range, replace_with.bytes());
     }
-
-    /// Converts this `String` into a [`Box`]`<`[`str`]`>`.
-    ///
-    /// This will drop any excess capacity.
-    ///
-    /// [`Box`]: ../../std/boxed/struct.Box.html
-    /// [`str`]: ../../std/primitive.str.html
-    ///
-    /// # Examples
-    ///
-    /// Basic usage:
-    ///
-    /// ```
-    /// let s = String::from("hello");
-    ///
-    /// let b = s.into_boxed_str();
-    /// ```
-    #[stable(feature = "box_str", since = "1.4.0")]
-    #[inline]
-    pub fn into_boxed_str(self) -> Box<str> {
-        let slice = self.vec.into_boxed_slice();
-        unsafe { from_boxed_utf8_unchecked(slice) }
-    }
 }
 
-impl FromUtf8Error {
+impl<A: Alloc> FromUtf8Error<A> {
     /// Returns a slice of [`u8`]s bytes that were attempted to convert to a `String`.
     ///
     /// # Examples
@@ -1630,7 +1854,7 @@ impl FromUtf8Error {
     /// assert_eq!(vec![0, 159], value.unwrap_err().into_bytes());
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn into_bytes(self) -> Vec<u8> {
+    pub fn into_bytes(self) -> Vec<u8, A> {
         self.bytes
     }
 
@@ -1666,7 +1890,7 @@ impl FromUtf8Error {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl fmt::Display for FromUtf8Error {
+impl<A: Alloc> fmt::Display for FromUtf8Error<A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(&self.error, f)
     }
@@ -1680,7 +1904,7 @@ impl fmt::Display for FromUtf16Error {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl Clone for String {
+impl<A: Alloc + Clone> Clone for String<A> {
     fn clone(&self) -> Self {
         String { vec: self.vec.clone() }
     }
@@ -1736,7 +1960,7 @@ impl<'a> FromIterator<Cow<'a, str>> for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl Extend<char> for String {
+impl<A: Alloc> Extend<char> for String<A> {
     fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
         let iterator = iter.into_iter();
         let (lower_bound, _) = iterator.size_hint();
@@ -1748,14 +1972,14 @@ impl Extend<char> for String {
 }
 
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a> Extend<&'a char> for String {
+impl<'a, A: Alloc> Extend<&'a char> for String<A> {
     fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
         self.extend(iter.into_iter().cloned());
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a> Extend<&'a str> for String {
+impl<'a, A: Alloc> Extend<&'a str> for String<A> {
     fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
         for s in iter {
             self.push_str(s)
@@ -1764,7 +1988,7 @@ impl<'a> Extend<&'a str> for String {
 }
 
 #[stable(feature = "extend_string", since = "1.4.0")]
-impl Extend<String> for String {
+impl<A: Alloc> Extend<String> for String<A> {
     fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
         for s in iter {
             self.push_str(&s)
@@ -1773,7 +1997,7 @@ impl Extend<String> for String {
 }
 
 #[stable(feature = "herd_cows", since = "1.19.0")]
-impl<'a> Extend<Cow<'a, str>> for String {
+impl<'a, A: Alloc> Extend<Cow<'a, str>> for String<A> {
     fn extend<I: IntoIterator<Item = Cow<'a, str>>>(&mut self, iter: I) {
         for s in iter {
             self.push_str(&s)
@@ -1785,7 +2009,7 @@ impl<'a> Extend<Cow<'a, str>> for String {
 #[unstable(feature = "pattern",
            reason = "API not fully fleshed out and ready to be stabilized",
            issue = "27721")]
-impl<'a, 'b> Pattern<'a> for &'b String {
+impl<'a, 'b, A: Alloc> Pattern<'a> for &'b String<A> {
     type Searcher = <&'b str as Pattern<'a>>::Searcher;
 
     fn into_searcher(self, haystack: &'a str) -> <&'b str as Pattern<'a>>::Searcher {
@@ -1804,21 +2028,40 @@ impl<'a, 'b> Pattern<'a> for &'b String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl PartialEq for String {
+impl<A: Alloc> PartialEq for String<A> {
     #[inline]
-    fn eq(&self, other: &String) -> bool {
+    fn eq(&self, other: &String<A>) -> bool {
         PartialEq::eq(&self[..], &other[..])
     }
     #[inline]
-    fn ne(&self, other: &String) -> bool {
+    fn ne(&self, other: &String<A>) -> bool {
         PartialEq::ne(&self[..], &other[..])
     }
 }
 
+#[stable(feature = "rust1", since = "1.0.0")]
+impl<A: Alloc> Eq for String<A> {}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl<A: Alloc> PartialOrd for String<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &String<A>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&self[..], &other[..])
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl<A: Alloc> Ord for String<A> {
+    #[inline]
+    fn cmp(&self, other: &String<A>) -> Ordering {
+        Ord::cmp(&self[..], &other[..])
+    }
+}
+
 macro_rules! impl_eq {
-    ($lhs:ty, $rhs: ty) => {
+    ([$($vars:tt)*] $lhs:ty, $rhs: ty) => {
         #[stable(feature = "rust1", since = "1.0.0")]
-        impl<'a, 'b> PartialEq<$rhs> for $lhs {
+        impl<'a, 'b, $($vars)*> PartialEq<$rhs> for $lhs {
             #[inline]
             fn eq(&self, other: &$rhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
             #[inline]
@@ -1826,7 +2069,7 @@ macro_rules! impl_eq {
         }
 
         #[stable(feature = "rust1", since = "1.0.0")]
-        impl<'a, 'b> PartialEq<$lhs> for $rhs {
+        impl<'a, 'b, $($vars)*> PartialEq<$lhs> for $rhs {
             #[inline]
             fn eq(&self, other: &$lhs) -> bool { PartialEq::eq(&self[..], &other[..]) }
             #[inline]
@@ -1836,11 +2079,11 @@ macro_rules! impl_eq {
     }
 }
 
-impl_eq! { String, str }
-impl_eq! { String, &'a str }
-impl_eq! { Cow<'a, str>, str }
-impl_eq! { Cow<'a, str>, &'b str }
-impl_eq! { Cow<'a, str>, String }
+impl_eq! { [A: Alloc] String<A>, str }
+impl_eq! { [A: Alloc] String<A>, &'a str }
+impl_eq! { [] Cow<'a, str>, str }
+impl_eq! { [] Cow<'a, str>, &'b str }
+impl_eq! { [A: Alloc] Cow<'a, str>, String<A> }
 
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Default for String {
@@ -1852,7 +2095,7 @@ impl Default for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl fmt::Display for String {
+impl<A: Alloc> fmt::Display for String<A> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(&**self, f)
@@ -1860,7 +2103,7 @@ impl fmt::Display for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl fmt::Debug for String {
+impl<A: Alloc> fmt::Debug for String<A> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(&**self, f)
@@ -1868,7 +2111,7 @@ impl fmt::Debug for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl hash::Hash for String {
+impl<A: Alloc> hash::Hash for String<A> {
     #[inline]
     fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
         (**self).hash(hasher)
@@ -1913,11 +2156,11 @@ impl hash::Hash for String {
 /// let c = a.to_string() + b;
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a> Add<&'a str> for String {
-    type Output = String;
+impl<'a, A: Alloc> Add<&'a str> for String<A> {
+    type Output = String<A>;
 
     #[inline]
-    fn add(mut self, other: &str) -> String {
+    fn add(mut self, other: &str) -> String<A> {
         self.push_str(other);
         self
     }
@@ -1929,7 +2172,7 @@ impl<'a> Add<&'a str> for String {
 ///
 /// [`push_str`]: struct.String.html#method.push_str
 #[stable(feature = "stringaddassign", since = "1.12.0")]
-impl<'a> AddAssign<&'a str> for String {
+impl<'a, A: Alloc> AddAssign<&'a str> for String<A> {
     #[inline]
     fn add_assign(&mut self, other: &str) {
         self.push_str(other);
@@ -1937,7 +2180,7 @@ impl<'a> AddAssign<&'a str> for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl ops::Index<ops::Range<usize>> for String {
+impl<A: Alloc> ops::Index<ops::Range<usize>> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1946,7 +2189,7 @@ impl ops::Index<ops::Range<usize>> for String {
     }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
-impl ops::Index<ops::RangeTo<usize>> for String {
+impl<A: Alloc> ops::Index<ops::RangeTo<usize>> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1955,7 +2198,7 @@ impl ops::Index<ops::RangeTo<usize>> for String {
     }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
-impl ops::Index<ops::RangeFrom<usize>> for String {
+impl<A: Alloc> ops::Index<ops::RangeFrom<usize>> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1964,7 +2207,7 @@ impl ops::Index<ops::RangeFrom<usize>> for String {
     }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
-impl ops::Index<ops::RangeFull> for String {
+impl<A: Alloc> ops::Index<ops::RangeFull> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1973,7 +2216,7 @@ impl ops::Index<ops::RangeFull> for String {
     }
 }
 #[stable(feature = "inclusive_range", since = "1.26.0")]
-impl ops::Index<ops::RangeInclusive<usize>> for String {
+impl<A: Alloc> ops::Index<ops::RangeInclusive<usize>> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1982,7 +2225,7 @@ impl ops::Index<ops::RangeInclusive<usize>> for String {
     }
 }
 #[stable(feature = "inclusive_range", since = "1.26.0")]
-impl ops::Index<ops::RangeToInclusive<usize>> for String {
+impl<A: Alloc> ops::Index<ops::RangeToInclusive<usize>> for String<A> {
     type Output = str;
 
     #[inline]
@@ -1992,42 +2235,42 @@ impl ops::Index<ops::RangeToInclusive<usize>> for String {
 }
 
 #[stable(feature = "derefmut_for_string", since = "1.3.0")]
-impl ops::IndexMut<ops::Range<usize>> for String {
+impl<A: Alloc> ops::IndexMut<ops::Range<usize>> for String<A> {
     #[inline]
     fn index_mut(&mut self, index: ops::Range<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 #[stable(feature = "derefmut_for_string", since = "1.3.0")]
-impl ops::IndexMut<ops::RangeTo<usize>> for String {
+impl<A: Alloc> ops::IndexMut<ops::RangeTo<usize>> for String<A> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeTo<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 #[stable(feature = "derefmut_for_string", since = "1.3.0")]
-impl ops::IndexMut<ops::RangeFrom<usize>> for String {
+impl<A: Alloc> ops::IndexMut<ops::RangeFrom<usize>> for String<A> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeFrom<usize>) -> &mut str {
         &mut self[..][index]
     }
 }
 #[stable(feature = "derefmut_for_string", since = "1.3.0")]
-impl ops::IndexMut<ops::RangeFull> for String {
+impl<A: Alloc> ops::IndexMut<ops::RangeFull> for String<A> {
     #[inline]
     fn index_mut(&mut self, _index: ops::RangeFull) -> &mut str {
         unsafe { str::from_utf8_unchecked_mut(&mut *self.vec) }
     }
 }
 #[stable(feature = "inclusive_range", since = "1.26.0")]
-impl ops::IndexMut<ops::RangeInclusive<usize>> for String {
+impl<A: Alloc> ops::IndexMut<ops::RangeInclusive<usize>> for String<A> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeInclusive<usize>) -> &mut str {
         IndexMut::index_mut(&mut **self, index)
     }
 }
 #[stable(feature = "inclusive_range", since = "1.26.0")]
-impl ops::IndexMut<ops::RangeToInclusive<usize>> for String {
+impl<A: Alloc> ops::IndexMut<ops::RangeToInclusive<usize>> for String<A> {
     #[inline]
     fn index_mut(&mut self, index: ops::RangeToInclusive<usize>) -> &mut str {
         IndexMut::index_mut(&mut **self, index)
@@ -2035,7 +2278,7 @@ impl ops::IndexMut<ops::RangeToInclusive<usize>> for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl ops::Deref for String {
+impl<A: Alloc> ops::Deref for String<A> {
     type Target = str;
 
     #[inline]
@@ -2045,7 +2288,7 @@ impl ops::Deref for String {
 }
 
 #[stable(feature = "derefmut_for_string", since = "1.3.0")]
-impl ops::DerefMut for String {
+impl<A: Alloc> ops::DerefMut for String<A> {
     #[inline]
     fn deref_mut(&mut self) -> &mut str {
         unsafe { str::from_utf8_unchecked_mut(&mut *self.vec) }
@@ -2179,7 +2422,7 @@ impl ToString for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl AsRef<str> for String {
+impl<A: Alloc> AsRef<str> for String<A> {
     #[inline]
     fn as_ref(&self) -> &str {
         self
@@ -2187,7 +2430,7 @@ impl AsRef<str> for String {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl AsRef<[u8]> for String {
+impl<A: Alloc> AsRef<[u8]> for String<A> {
     #[inline]
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
@@ -2270,14 +2513,14 @@ impl<'a> FromIterator<String> for Cow<'a, str> {
 }
 
 #[stable(feature = "from_string_for_vec_u8", since = "1.14.0")]
-impl From<String> for Vec<u8> {
-    fn from(string: String) -> Vec<u8> {
+impl<A: Alloc> From<String<A>> for Vec<u8, A> {
+    fn from(string: String<A>) -> Vec<u8, A> {
         string.into_bytes()
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl fmt::Write for String {
+impl<A: Alloc> fmt::Write for String<A> {
     #[inline]
     fn write_str(&mut self, s: &str) -> fmt::Result {
         self.push_str(s);
@@ -2299,9 +2542,9 @@ impl fmt::Write for String {
 /// [`drain`]: struct.String.html#method.drain
 /// [`String`]: struct.String.html
 #[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a> {
+pub struct Drain<'a, A: 'a + Alloc = Global> {
     /// Will be used as &'a mut String in the destructor
-    string: *mut String,
+    string: *mut String<A>,
     /// Start of part to remove
     start: usize,
     /// End of part to remove
@@ -2311,19 +2554,19 @@ pub struct Drain<'a> {
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<'a> fmt::Debug for Drain<'a> {
+impl<'a, A: Alloc> fmt::Debug for Drain<'a, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.pad("Drain { .. }")
     }
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a> Sync for Drain<'a> {}
+unsafe impl<'a, A: Alloc + Sync> Sync for Drain<'a, A> {}
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a> Send for Drain<'a> {}
+unsafe impl<'a, A: Alloc + Send> Send for Drain<'a, A> {}
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a> Drop for Drain<'a> {
+impl<'a, A: Alloc> Drop for Drain<'a, A> {
     fn drop(&mut self) {
         unsafe {
             // Use Vec::drain. "Reaffirm" the bounds checks to avoid
@@ -2337,7 +2580,7 @@ impl<'a> Drop for Drain<'a> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a> Iterator for Drain<'a> {
+impl<'a, A: Alloc> Iterator for Drain<'a, A> {
     type Item = char;
 
     #[inline]
@@ -2351,7 +2594,7 @@ impl<'a> Iterator for Drain<'a> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a> DoubleEndedIterator for Drain<'a> {
+impl<'a, A: Alloc> DoubleEndedIterator for Drain<'a, A> {
     #[inline]
     fn next_back(&mut self) -> Option<char> {
         self.iter.next_back()
@@ -2359,4 +2602,4 @@ impl<'a> DoubleEndedIterator for Drain<'a> {
 }
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<'a> FusedIterator for Drain<'a> {}
+impl<'a, A: Alloc> FusedIterator for Drain<'a, A> {}
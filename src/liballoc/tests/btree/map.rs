@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::collections::btree_map::Entry::{Occupied, Vacant};
 use std::ops::Bound::{self, Excluded, Included, Unbounded};
@@ -15,6 +16,7 @@ use std::rc::Rc;
 
 use std::iter::FromIterator;
 use super::DeterministicRng;
+use Counting;
 
 #[test]
 fn test_basic_large() {
@@ -685,3 +687,195 @@ fn test_split_off_large_random_sorted() {
     assert!(map.into_iter().eq(data.clone().into_iter().filter(|x| x.0 < key)));
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
+
+#[test]
+fn test_cursor() {
+    let map: BTreeMap<_, _> = (0..100).map(|i| (i * 2, i)).collect();
+
+    let mut cur = map.lower_bound(Included(&10));
+    assert_eq!(cur.key_value(), Some((&10, &5)));
+    assert_eq!(cur.peek_prev(), Some((&8, &4)));
+    assert_eq!(cur.peek_next(), Some((&12, &6)));
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&8));
+
+    assert_eq!(map.lower_bound(Excluded(&10)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Included(&11)).key(), Some(&12));
+    assert_eq!(map.lower_bound(Unbounded).key(), Some(&0));
+    assert_eq!(map.upper_bound(Included(&10)).key(), Some(&10));
+    assert_eq!(map.upper_bound(Excluded(&10)).key(), Some(&8));
+    assert_eq!(map.upper_bound(Included(&11)).key(), Some(&10));
+    assert_eq!(map.upper_bound(Unbounded).key(), Some(&198));
+
+    // Walking off either end lands on the ghost element, and then wraps around.
+    let mut cur = map.upper_bound(Unbounded);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_next(), Some((&0, &0)));
+    assert_eq!(cur.peek_prev(), Some((&198, &99)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&0));
+    cur.move_prev();
+    assert_eq!(cur.key(), None);
+    assert_eq!(map.lower_bound(Excluded(&198)).key(), None);
+    assert_eq!(map.upper_bound(Excluded(&0)).key(), None);
+
+    // A full walk in either direction visits every element in order.
+    let mut cur = map.lower_bound(Unbounded);
+    let mut forward = vec![];
+    while let Some(&k) = cur.key() {
+        forward.push(k);
+        cur.move_next();
+    }
+    assert!(forward.into_iter().eq(map.keys().cloned()));
+    let mut cur = map.upper_bound(Unbounded);
+    let mut backward = vec![];
+    while let Some(&k) = cur.key() {
+        backward.push(k);
+        cur.move_prev();
+    }
+    assert!(backward.into_iter().eq(map.keys().rev().cloned()));
+
+    let empty: BTreeMap<i32, i32> = BTreeMap::new();
+    let mut cur = empty.lower_bound(Unbounded);
+    assert_eq!(cur.key(), None);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut map: BTreeMap<_, _> = (0..100).map(|i| (i * 2, i)).collect();
+
+    {
+        let mut cur = map.lower_bound_mut(Included(&10));
+        *cur.value_mut().unwrap() += 100;
+        cur.insert_after(11, 0);
+        assert_eq!(cur.key(), Some(&10));
+        assert_eq!(cur.peek_next(), Some((&11, &0)));
+        cur.insert_before(9, 0);
+        assert_eq!(cur.key(), Some(&10));
+        assert_eq!(cur.peek_prev(), Some((&9, &0)));
+        assert_eq!(cur.remove_current(), Some((10, 105)));
+        assert_eq!(cur.key(), Some(&11));
+    }
+    assert_eq!(map.len(), 101);
+    assert_eq!(map.get(&10), None);
+    assert_eq!(map.get(&9), Some(&0));
+    assert_eq!(map.get(&11), Some(&0));
+
+    {
+        let mut cur = map.upper_bound_mut(Unbounded);
+        cur.move_next();
+        assert_eq!(cur.key(), None);
+        cur.insert_after(-1, 0);
+        cur.insert_before(200, 0);
+        assert_eq!(cur.key(), None);
+        assert_eq!(cur.remove_current(), None);
+    }
+    assert_eq!(map.len(), 103);
+    assert_eq!(map.keys().next(), Some(&-1));
+    assert_eq!(map.keys().next_back(), Some(&200));
+}
+
+#[test]
+fn test_cursor_mut_insert_remove_many() {
+    let mut map = BTreeMap::new();
+    {
+        // Build a multi-level tree purely through the cursor.
+        let mut cur = map.lower_bound_mut(Unbounded);
+        for i in 0..1000 {
+            cur.insert_before(i, i);
+        }
+        assert_eq!(cur.key(), None);
+    }
+    assert_eq!(map.len(), 1000);
+    assert!(map.iter().map(|(&k, &v)| (k, v)).eq((0..1000).map(|i| (i, i))));
+
+    {
+        // Remove every other element, which exercises merging and stealing.
+        let mut cur = map.lower_bound_mut(Unbounded);
+        while cur.key().is_some() {
+            cur.remove_current();
+            cur.move_next();
+        }
+    }
+    assert_eq!(map.len(), 500);
+    assert!(map.keys().cloned().eq((0..1000).filter(|i| i % 2 == 1)));
+
+    {
+        let mut cur = map.lower_bound_mut(Unbounded);
+        while cur.remove_current().is_some() {}
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "key must be ordered below the next element")]
+fn test_cursor_mut_insert_after_out_of_order() {
+    let mut map: BTreeMap<_, _> = (0..10).map(|i| (i * 2, i)).collect();
+    let mut cur = map.lower_bound_mut(Included(&4));
+    cur.insert_after(7, 0);
+}
+
+#[test]
+fn test_try_insert() {
+    let size = 10000;
+    let mut map = BTreeMap::new();
+    let mut expected = BTreeMap::new();
+
+    // Insert in a scrambled order, so that splits happen all over the tree.
+    for i in 0..size {
+        let k = (i * 7919) % size;
+        assert_eq!(map.try_insert(k, i).unwrap(), None);
+        expected.insert(k, i);
+        assert_eq!(map.len(), i + 1);
+    }
+    assert!(map.iter().eq(expected.iter()));
+
+    for i in 0..size {
+        assert_eq!(map.try_insert(i, i * 10).unwrap(), expected.insert(i, i * 10));
+    }
+    assert_eq!(map.len(), size);
+    assert!(map.iter().eq(expected.iter()));
+}
+
+#[test]
+fn test_alloc_in() {
+    let live = Cell::new(0);
+    {
+        let mut map = BTreeMap::new_in(Counting(&live));
+        assert_eq!(live.get(), 0);
+
+        // Insert in a scrambled order, so that splits happen all over the tree.
+        let size = 1000;
+        for i in 0..size {
+            let k = (i * 7919) % size;
+            assert_eq!(map.insert(k, i), None);
+        }
+        assert!(live.get() > 1);
+        map.try_insert(size, size).unwrap();
+        *map.entry(0).or_insert(0) += 1;
+
+        let clone = map.clone();
+        assert!(map.iter().eq(clone.iter()));
+
+        let mut right = map.split_off(&(size / 2));
+        assert_eq!(map.len() + right.len(), size + 1);
+        for i in 0..size / 4 {
+            assert!(map.remove(&i).is_some());
+        }
+        right.append(&mut map);
+        assert_eq!(right.len(), size + 1 - size / 4);
+
+        let mut cur = right.lower_bound_mut(Unbounded);
+        cur.insert_before(0, 0);
+        assert_eq!(cur.remove_current(), Some((250, 750)));
+
+        // Leave part of the tree for the iterator to free.
+        let mut iter = clone.into_iter();
+        assert_eq!(iter.next(), Some((0, 1)));
+        assert_eq!(iter.next_back(), Some((size, size)));
+    }
+    assert_eq!(live.get(), 0);
+}
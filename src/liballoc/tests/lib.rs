@@ -12,6 +12,7 @@
 #![feature(alloc_system)]
 #![feature(attr_literals)]
 #![feature(box_syntax)]
+#![feature(btree_cursors)]
 #![feature(const_fn)]
 #![feature(drain_filter)]
 #![feature(exact_size_is_empty)]
@@ -30,8 +31,11 @@ extern crate alloc_system;
 extern crate core;
 extern crate rand;
 
+use std::alloc::{Alloc, AllocErr, Global, Layout};
+use std::cell::Cell;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
+use std::ptr::NonNull;
 
 mod arc;
 mod binary_heap;
@@ -47,6 +51,36 @@ mod string;
 mod vec_deque;
 mod vec;
 
+/// Forwards to `Global`, keeping count of the allocations still live.
+#[derive(Clone)]
+struct Counting<'a>(&'a Cell<isize>);
+
+unsafe impl<'a> Alloc for Counting<'a> {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        self.0.set(self.0.get() + 1);
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.set(self.0.get() - 1);
+        Global.dealloc(ptr, layout)
+    }
+}
+
+/// Forwards to `Global`, but is a type of its own.
+#[derive(Clone, Copy, Default)]
+struct Forwarding;
+
+unsafe impl Alloc for Forwarding {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        Global.dealloc(ptr, layout)
+    }
+}
+
 fn hash<T: Hash>(t: &T) -> u64 {
     let mut s = DefaultHasher::new();
     t.hash(&mut s);
@@ -12,6 +12,8 @@ use std::borrow::Cow;
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::str::from_utf8;
 
+use rand::{thread_rng, Rng};
+
 #[test]
 fn test_le() {
     assert!("" <= "");
@@ -877,6 +879,106 @@ fn from_utf8_error() {
     test!(b"A\xC3\xA9 \xF1\x80\x80 ", 4, Some(3));
 }
 
+/// A straightforward transcription of the UTF-8 grammar from RFC 3629, used to check
+/// `from_utf8` against. Returns `(valid_up_to, error_len)` on failure.
+fn reference_utf8_validation(v: &[u8]) -> Result<(), (usize, Option<usize>)> {
+    let mut i = 0;
+    while i < v.len() {
+        let (len, second_min, second_max) = match v[i] {
+            0x00..=0x7F => {
+                i += 1;
+                continue;
+            }
+            0xC2..=0xDF => (2, 0x80, 0xBF),
+            0xE0 => (3, 0xA0, 0xBF),
+            0xE1..=0xEC | 0xEE..=0xEF => (3, 0x80, 0xBF),
+            0xED => (3, 0x80, 0x9F),
+            0xF0 => (4, 0x90, 0xBF),
+            0xF1..=0xF3 => (4, 0x80, 0xBF),
+            0xF4 => (4, 0x80, 0x8F),
+            _ => return Err((i, Some(1))),
+        };
+        for j in 1..len {
+            let (min, max) = if j == 1 { (second_min, second_max) } else { (0x80, 0xBF) };
+            match v.get(i + j) {
+                None => return Err((i, None)),
+                Some(&b) if b < min || b > max => return Err((i, Some(j))),
+                Some(_) => {}
+            }
+        }
+        i += len;
+    }
+    Ok(())
+}
+
+fn check_utf8_validation(v: &[u8]) {
+    let expected = reference_utf8_validation(v);
+    let actual = from_utf8(v).map(|_| ()).map_err(|e| (e.valid_up_to(), e.error_len()));
+    assert_eq!(actual, expected, "mismatch validating {:?}", v);
+    let actual = String::from_utf8(v.to_vec()).map(|_| ())
+        .map_err(|e| (e.utf8_error().valid_up_to(), e.utf8_error().error_len()));
+    assert_eq!(actual, expected, "mismatch validating {:?}", v);
+}
+
+#[test]
+fn from_utf8_matches_reference() {
+    // Byte sequences right at the edges of what is valid, plus every way of getting a
+    // sequence wrong.
+    let snippets: &[&[u8]] = &[
+        b"a", b"\x7F", b"\x80", b"\xBF", b"\xC0\x80", b"\xC1\xBF", b"\xC2", b"\xC2\x80",
+        b"\xDF\xBF", b"\xDF\xC0", b"\xE0\x9F\x80", b"\xE0\xA0\x80", b"\xE0\xA0",
+        b"\xE1\x80\x80", b"\xEC\xBF\xBF", b"\xED\x9F\xBF", b"\xED\xA0\x80",
+        b"\xEE\x80\x80", b"\xEF\xBF\xBF", b"\xEF\xBF", b"\xF0\x8F\xBF\xBF",
+        b"\xF0\x90\x80\x80", b"\xF0\x90\x80", b"\xF1\x80\x80\x80", b"\xF3\xBF\xBF\xBF",
+        b"\xF4\x8F\xBF\xBF", b"\xF4\x90\x80\x80", b"\xF5\x80\x80\x80", b"\xF8\x88\x80\x80",
+        b"\xFF", b"\xC2\x80\x80", b"\xE1\x80\x80\x80", b"\xF1\x80\x80\x80\x80",
+    ];
+    // Fillers with different mixes of character widths, so that the snippets land on
+    // every offset relative to the vector blocks and to the characters around them.
+    let fillers = ["a", "\u{e9}", "\u{4e2d}", "\u{1f600}", "a\u{e9}\u{4e2d}\u{1f600}"];
+
+    for filler in &fillers {
+        let filler: String = filler.chars().cycle().take(100).collect();
+        for snippet in snippets {
+            for offset in 0..100 {
+                // Fill up to `offset` with whole characters, padding with ASCII.
+                let mut data: Vec<u8> = filler.char_indices()
+                    .take_while(|&(i, c)| i + c.len_utf8() <= offset)
+                    .flat_map(|(_, c)| c.to_string().into_bytes())
+                    .collect();
+                while data.len() < offset {
+                    data.push(b'a');
+                }
+                data.extend_from_slice(snippet);
+                data.extend_from_slice(filler.as_bytes());
+                check_utf8_validation(&data);
+                // And with the snippet cut off at the end of the input.
+                data.truncate(offset + snippet.len());
+                check_utf8_validation(&data);
+            }
+        }
+    }
+}
+
+#[test]
+fn from_utf8_matches_reference_random() {
+    let mut rng = thread_rng();
+    let chars = ["a", "~", "\u{80}", "\u{7ff}", "\u{800}", "\u{ffff}", "\u{10000}", "\u{10ffff}"];
+    for _ in 0..2000 {
+        let mut data = Vec::new();
+        while data.len() < 300 {
+            data.extend_from_slice(rng.choose(&chars).unwrap().as_bytes());
+        }
+        check_utf8_validation(&data);
+        // Corrupt a few bytes.
+        for _ in 0..rng.gen_range(1, 4) {
+            let i = rng.gen_range(0, data.len());
+            data[i] = rng.gen();
+        }
+        check_utf8_validation(&data);
+    }
+}
+
 #[test]
 fn test_as_bytes() {
     // no null
@@ -10,9 +10,12 @@
 
 use std::borrow::Cow;
 use std::collections::CollectionAllocErr::*;
+use std::iter::FromIterator;
 use std::mem::size_of;
 use std::{usize, isize};
 
+use Forwarding;
+
 pub trait IntoCow<'a, B: ?Sized> where B: ToOwned {
     fn into_cow(self) -> Cow<'a, B>;
 }
@@ -425,6 +428,31 @@ fn test_from_iterator() {
     assert_eq!(s, d);
 }
 
+#[test]
+fn test_from_iterator_in() {
+    let s = String::from_iter_in("ศไทย中华".chars(), Forwarding);
+    assert_eq!(s, "ศไทย中华");
+
+    let t = String::from_iter_in(vec!["ab", "cd"], Forwarding);
+    assert_eq!(t, "abcd");
+
+    // Without an allocator, the global one is inferred.
+    let u = String::from_iter(vec!["ab", "cd"]);
+    assert_eq!(u, t);
+}
+
+#[test]
+fn test_from_utf8_in() {
+    let bytes = Vec::from_iter_in(b"hello".iter().cloned(), Forwarding);
+    assert_eq!(String::from_utf8(bytes).unwrap(), "hello");
+
+    let bytes = Vec::from_iter_in(vec![0, 159], Forwarding);
+    let err = String::from_utf8(bytes).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 1);
+    let bytes: Vec<u8, Forwarding> = err.into_bytes();
+    assert_eq!(bytes, [0, 159]);
+}
+
 #[test]
 fn test_drain() {
     let mut s = String::from("αβγ");
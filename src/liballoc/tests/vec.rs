@@ -8,12 +8,18 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::alloc::{self, Alloc, Layout};
 use std::borrow::Cow;
+use std::cell::Cell;
+use std::iter::FromIterator;
 use std::mem::size_of;
+use std::ptr::NonNull;
 use std::{usize, isize};
 use std::vec::{Drain, IntoIter};
 use std::collections::CollectionAllocErr::*;
 
+use {Counting, Forwarding};
+
 struct DropCounter<'a> {
     count: &'a mut u32,
 }
@@ -1154,3 +1160,105 @@ fn test_try_reserve_exact() {
     }
 
 }
+
+#[test]
+fn test_from_iter_in() {
+    let v = Vec::from_iter_in(0..10, Forwarding);
+    assert_eq!(v, (0..10).collect::<Vec<_>>());
+
+    let w = Vec::from_iter_in(v.iter().cloned().filter(|x| x % 2 == 0), Forwarding);
+    assert_eq!(w, [0, 2, 4, 6, 8]);
+
+    // Without an allocator, the global one is inferred.
+    let x = Vec::from_iter(w);
+    assert_eq!(x, [0, 2, 4, 6, 8]);
+}
+
+#[test]
+fn test_into_boxed_slice_in() {
+    let mut v = Vec::with_capacity_in(10, Forwarding);
+    v.extend_from_slice(&[1, 2, 3]);
+    let b: Box<[i32], Forwarding> = v.into_boxed_slice();
+    assert_eq!(*b, [1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "`Box` only supports zero-sized allocators")]
+fn test_into_boxed_slice_in_not_zero_sized() {
+    let live = Cell::new(0);
+    let mut v = Vec::with_capacity_in(10, Counting(&live));
+    v.push(1);
+    let _ = v.into_boxed_slice();
+}
+
+#[test]
+fn test_alloc_in() {
+    let live = Cell::new(0);
+    {
+        let mut v = Vec::new_in(Counting(&live));
+        assert_eq!(live.get(), 0);
+        v.extend(0..100);
+        v.push(100);
+        assert_eq!(live.get(), 1);
+
+        let w = v.clone();
+        assert_eq!(live.get(), 2);
+        assert_eq!(v, w);
+
+        let tail = v.split_off(50);
+        assert_eq!(live.get(), 3);
+        assert_eq!(tail.len(), 51);
+
+        v.try_reserve(1000).unwrap();
+        assert!(v.capacity() >= 1050);
+        v.drain(..10);
+        assert_eq!(v.len(), 40);
+
+        let sum: i32 = w.into_iter().sum();
+        assert_eq!(sum, 5050);
+        assert_eq!(live.get(), 2);
+    }
+    assert_eq!(live.get(), 0);
+}
+
+/// Fails every allocation.
+#[derive(Clone)]
+struct Failing;
+
+unsafe impl Alloc for Failing {
+    unsafe fn alloc(&mut self, _: Layout) -> Result<NonNull<u8>, alloc::AllocErr> {
+        Err(alloc::AllocErr)
+    }
+
+    unsafe fn dealloc(&mut self, _: NonNull<u8>, _: Layout) {
+        panic!("nothing was allocated")
+    }
+}
+
+#[test]
+fn test_try_methods() {
+    let mut v = Vec::try_with_capacity(10).unwrap();
+    v.try_push(1).unwrap();
+    v.try_extend(2..5).unwrap();
+    v.try_extend_from_slice(&[5, 6]).unwrap();
+    v.try_insert(0, 0).unwrap();
+    assert_eq!(v, [0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(v.try_clone().unwrap(), v);
+    assert_eq!(Vec::try_from_iter(0..7).unwrap(), v);
+
+    if let Err(CapacityOverflow) = Vec::<u32>::try_with_capacity(usize::MAX) {
+    } else { panic!("usize::MAX u32s should trigger an overflow!") }
+}
+
+#[test]
+fn test_try_methods_failing_alloc() {
+    if let Err(AllocErr) = Vec::<u8, _>::try_with_capacity_in(10, Failing) {
+    } else { panic!("allocation should fail!") }
+
+    let mut v = Vec::new_in(Failing);
+    if let Err(AllocErr) = v.try_push(1) {
+    } else { panic!("allocation should fail!") }
+    if let Err(AllocErr) = v.try_extend_from_slice(&[1, 2, 3]) {
+    } else { panic!("allocation should fail!") }
+    assert!(v.is_empty());
+}
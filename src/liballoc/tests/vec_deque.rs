@@ -1208,3 +1208,21 @@ fn test_try_reserve_exact() {
     }
 
 }
+
+#[test]
+fn test_try_methods() {
+    let mut d = VecDeque::try_with_capacity(1).unwrap();
+    for i in 0..10 {
+        d.try_push_back(i).unwrap();
+        d.try_push_front(-i).unwrap();
+    }
+    d.try_insert(10, 100).unwrap();
+    assert_eq!(d.len(), 21);
+    assert_eq!(d[10], 100);
+    assert_eq!(d.front(), Some(&-9));
+    assert_eq!(d.back(), Some(&9));
+    assert_eq!(d.try_clone().unwrap(), d);
+
+    if let Err(CapacityOverflow) = VecDeque::<u8>::try_with_capacity(usize::MAX) {
+    } else { panic!("usize::MAX should trigger an overflow!") }
+}
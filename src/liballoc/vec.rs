@@ -72,7 +72,7 @@ use core::hash::{self, Hash};
 use core::intrinsics::{arith_offset, assume};
 use core::iter::{FromIterator, FusedIterator, TrustedLen};
 use core::marker::PhantomData;
-use core::mem;
+use core::mem::{self, ManuallyDrop};
 use core::ops::Bound::{Excluded, Included, Unbounded};
 use core::ops::{Index, IndexMut, RangeBounds};
 use core::ops;
@@ -80,10 +80,11 @@ use core::ptr;
 use core::ptr::NonNull;
 use core::slice;
 
+use alloc::{Alloc, Global};
 use collections::CollectionAllocErr;
 use borrow::ToOwned;
 use borrow::Cow;
-use boxed::Box;
+use boxed::{Box, assert_zst_alloc};
 use raw_vec::RawVec;
 
 /// A contiguous growable array type, written `Vec<T>` but pronounced 'vector'.
@@ -282,6 +283,18 @@ use raw_vec::RawVec;
 /// `Vec` does not currently guarantee the order in which elements are dropped.
 /// The order has changed in the past and may change again.
 ///
+/// # Allocators
+///
+/// The optional second type parameter is the [`Alloc`] implementation that
+/// the buffer is obtained from, [`Global`] by default. A vector using some
+/// other allocator is created with [`Vec::new_in`] or [`Vec::with_capacity_in`],
+/// and otherwise has the same API as `Vec<T>`. Conversions to and from
+/// `Box<[T]>` are only available with the default allocator.
+///
+/// [`Alloc`]: ../../std/alloc/trait.Alloc.html
+/// [`Global`]: ../../std/alloc/struct.Global.html
+/// [`Vec::new_in`]: ../../std/vec/struct.Vec.html#method.new_in
+/// [`Vec::with_capacity_in`]: ../../std/vec/struct.Vec.html#method.with_capacity_in
 /// [`vec!`]: ../../std/macro.vec.html
 /// [`Index`]: ../../std/ops/trait.Index.html
 /// [`String`]: ../../std/string/struct.String.html
@@ -297,8 +310,8 @@ use raw_vec::RawVec;
 /// [`reserve`]: ../../std/vec/struct.Vec.html#method.reserve
 /// [owned slice]: ../../std/boxed/struct.Box.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct Vec<T> {
-    buf: RawVec<T>,
+pub struct Vec<T, A: Alloc = Global> {
+    buf: RawVec<T, A>,
     len: usize,
 }
 
@@ -364,6 +377,51 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Constructs a new, empty `Vec<T>` with the specified capacity, returning an
+    /// error instead of aborting if the allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let vec: Vec<i32> = Vec::try_with_capacity(10).expect("out of memory");
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity(capacity: usize) -> Result<Vec<T>, CollectionAllocErr> {
+        Vec::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Creates a `Vec<T>` from an iterator, returning an error instead of aborting
+    /// if an allocation fails.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let vec = Vec::try_from_iter((1..4).map(|x| x * 2)).expect("out of memory");
+    /// assert_eq!(vec, [2, 4, 6]);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I)
+                                                    -> Result<Vec<T>, CollectionAllocErr> {
+        let mut vec = Vec::new();
+        vec.try_extend(iter)?;
+        Ok(vec)
+    }
+
     /// Creates a `Vec<T>` directly from the raw components of another vector.
     ///
     /// # Safety
@@ -426,6 +484,127 @@ impl<T> Vec<T> {
             len: length,
         }
     }
+}
+
+impl<T, A: Alloc> Vec<T, A> {
+    /// Constructs a new, empty `Vec<T, A>` which will allocate from `alloc`.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let mut vec: Vec<i32, Global> = Vec::new_in(Global);
+    /// vec.push(1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn new_in(alloc: A) -> Vec<T, A> {
+        Vec {
+            buf: RawVec::new_in(alloc),
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty `Vec<T, A>` with the specified capacity, which
+    /// will allocate from `alloc`.
+    ///
+    /// See [`with_capacity`] for details.
+    ///
+    /// [`with_capacity`]: #method.with_capacity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let vec: Vec<i32, Global> = Vec::with_capacity_in(10, Global);
+    /// assert_eq!(vec.len(), 0);
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Vec<T, A> {
+        Vec {
+            buf: RawVec::with_capacity_in(capacity, alloc),
+            len: 0,
+        }
+    }
+
+    /// Creates a vector from the elements of an iterator, which will allocate
+    /// from `alloc`.
+    ///
+    /// This is the counterpart of [`FromIterator`], which only builds vectors
+    /// using the global allocator.
+    ///
+    /// [`FromIterator`]: ../../std/iter/trait.FromIterator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    ///
+    /// use std::alloc::Global;
+    ///
+    /// let vec = Vec::from_iter_in(1..4, Global);
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, alloc: A) -> Vec<T, A> {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut vector = Vec::with_capacity_in(lower, alloc);
+        vector.extend(iter);
+        vector
+    }
+
+    /// Constructs a new, empty `Vec<T, A>` with the specified capacity, which
+    /// will allocate from `alloc`, returning an error instead of aborting if the
+    /// allocation fails.
+    ///
+    /// See [`try_with_capacity`] for details.
+    ///
+    /// [`try_with_capacity`]: #method.try_with_capacity
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A)
+                                -> Result<Vec<T, A>, CollectionAllocErr> {
+        let mut vec = Vec::new_in(alloc);
+        vec.try_reserve_exact(capacity)?;
+        Ok(vec)
+    }
+
+    /// Creates a `Vec<T, A>` directly from the raw components of another
+    /// vector and the allocator its buffer was obtained from.
+    ///
+    /// # Safety
+    ///
+    /// The same invariants as for [`from_raw_parts`] must hold, except that
+    /// `ptr` needs to have been allocated by `alloc` rather than the global
+    /// allocator.
+    ///
+    /// [`from_raw_parts`]: #method.from_raw_parts
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub unsafe fn from_raw_parts_in(ptr: *mut T, length: usize, capacity: usize,
+                                    alloc: A) -> Vec<T, A> {
+        Vec {
+            buf: RawVec::from_raw_parts_in(ptr, capacity, alloc),
+            len: length,
+        }
+    }
+
+    /// Returns a reference to the allocator backing this vector.
+    #[inline]
+    #[unstable(feature = "allocator_api", issue = "32838")]
+    pub fn alloc(&self) -> &A {
+        self.buf.alloc()
+    }
+
 
     /// Returns the number of elements the vector can hold without
     /// reallocating.
@@ -566,6 +745,45 @@ impl<T> Vec<T> {
         self.buf.try_reserve_exact(self.len, additional)
     }
 
+    /// Converts the vector into [`Box<[T], A>`][owned slice].
+    ///
+    /// Note that this will drop any excess capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `A` is not zero-sized, which `Box` doesn't support yet.
+    ///
+    /// [owned slice]: ../../std/boxed/struct.Box.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let v = vec![1, 2, 3];
+    ///
+    /// let slice = v.into_boxed_slice();
+    /// ```
+    ///
+    /// Any excess capacity is removed:
+    ///
+    /// ```
+    /// let mut vec = Vec::with_capacity(10);
+    /// vec.extend([1, 2, 3].iter().cloned());
+    ///
+    /// assert_eq!(vec.capacity(), 10);
+    /// let slice = vec.into_boxed_slice();
+    /// assert_eq!(slice.into_vec().capacity(), 3);
+    /// ```
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn into_boxed_slice(mut self) -> Box<[T], A> {
+        assert_zst_alloc::<A>();
+        unsafe {
+            self.shrink_to_fit();
+            let buf = ptr::read(&self.buf);
+            mem::forget(self);
+            buf.into_box()
+        }
+    }
+
     /// Shrinks the capacity of the vector as much as possible.
     ///
     /// It will drop down as close as possible to the length but the allocator
@@ -612,40 +830,6 @@ impl<T> Vec<T> {
         self.buf.shrink_to_fit(cmp::max(self.len, min_capacity));
     }
 
-    /// Converts the vector into [`Box<[T]>`][owned slice].
-    ///
-    /// Note that this will drop any excess capacity.
-    ///
-    /// [owned slice]: ../../std/boxed/struct.Box.html
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// let v = vec![1, 2, 3];
-    ///
-    /// let slice = v.into_boxed_slice();
-    /// ```
-    ///
-    /// Any excess capacity is removed:
-    ///
-    /// ```
-    /// let mut vec = Vec::with_capacity(10);
-    /// vec.extend([1, 2, 3].iter().cloned());
-    ///
-    /// assert_eq!(vec.capacity(), 10);
-    /// let slice = vec.into_boxed_slice();
-    /// assert_eq!(slice.into_vec().capacity(), 3);
-    /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn into_boxed_slice(mut self) -> Box<[T]> {
-        unsafe {
-            self.shrink_to_fit();
-            let buf = ptr::read(&self.buf);
-            mem::forget(self);
-            buf.into_box()
-        }
-    }
-
     /// Shortens the vector, keeping the first `len` elements and dropping
     /// the rest.
     ///
@@ -868,6 +1052,35 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right, returning an error instead of aborting if
+    /// the vector's buffer can't be grown.
+    ///
+    /// If an error is returned, the vector is unchanged and `element` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut vec = vec![1, 2, 3];
+    /// vec.try_insert(1, 4).expect("out of memory");
+    /// assert_eq!(vec, [1, 4, 2, 3]);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), CollectionAllocErr> {
+        assert!(index <= self.len());
+        if self.len == self.buf.cap() {
+            self.try_reserve(1)?;
+        }
+        self.insert(index, element);
+        Ok(())
+    }
+
     /// Removes and returns the element at position `index` within the vector,
     /// shifting all elements after it to the left.
     ///
@@ -1078,6 +1291,56 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Appends an element to the back of a collection, returning an error instead
+    /// of aborting if the vector's buffer can't be grown.
+    ///
+    /// If an error is returned, the vector is unchanged and `value` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut vec = vec![1, 2];
+    /// vec.try_push(3).expect("out of memory");
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_push(&mut self, value: T) -> Result<(), CollectionAllocErr> {
+        if self.len == self.buf.cap() {
+            self.try_reserve(1)?;
+        }
+        self.push(value);
+        Ok(())
+    }
+
+    /// Appends all elements of an iterator to the vector, returning an error
+    /// instead of aborting if the vector's buffer can't be grown.
+    ///
+    /// If an error is returned, the elements appended before the failure are
+    /// kept, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut vec = vec![1];
+    /// vec.try_extend(2..5).expect("out of memory");
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_extend<I: IntoIterator<Item = T>>(&mut self, iter: I)
+                                                 -> Result<(), CollectionAllocErr> {
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0)?;
+        for element in iter {
+            self.try_push(element)?;
+        }
+        Ok(())
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`] if it
     /// is empty.
     ///
@@ -1164,7 +1427,7 @@ impl<T> Vec<T> {
     /// assert_eq!(v, &[]);
     /// ```
     #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain<R>(&mut self, range: R) -> Drain<T>
+    pub fn drain<R>(&mut self, range: R) -> Drain<T, A>
         where R: RangeBounds<usize>
     {
         // Memory safety
@@ -1279,11 +1542,13 @@ impl<T> Vec<T> {
     /// ```
     #[inline]
     #[stable(feature = "split_off", since = "1.4.0")]
-    pub fn split_off(&mut self, at: usize) -> Self {
+    pub fn split_off(&mut self, at: usize) -> Self
+        where A: Clone
+    {
         assert!(at <= self.len(), "`at` out of bounds");
 
         let other_len = self.len - at;
-        let mut other = Vec::with_capacity(other_len);
+        let mut other = Vec::with_capacity_in(other_len, self.buf.alloc().clone());
 
         // Unsafely `set_len` and copy items to `other`.
         unsafe {
@@ -1341,7 +1606,7 @@ impl<T> Vec<T> {
     }
 }
 
-impl<T: Clone> Vec<T> {
+impl<T: Clone, A: Alloc> Vec<T, A> {
     /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
     ///
     /// If `new_len` is greater than `len`, the `Vec` is extended by the
@@ -1401,9 +1666,48 @@ impl<T: Clone> Vec<T> {
     pub fn extend_from_slice(&mut self, other: &[T]) {
         self.spec_extend(other.iter())
     }
+
+    /// Clones and appends all elements in a slice to the `Vec`, returning an
+    /// error instead of aborting if the vector's buffer can't be grown.
+    ///
+    /// If an error is returned, the vector is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let mut vec = vec![1];
+    /// vec.try_extend_from_slice(&[2, 3, 4]).expect("out of memory");
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), CollectionAllocErr> {
+        self.try_reserve(other.len())?;
+        self.extend_from_slice(other);
+        Ok(())
+    }
+
+    /// Returns a copy of the vector, returning an error instead of aborting if
+    /// the allocation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    ///
+    /// let vec = vec![1, 2, 3];
+    /// assert_eq!(vec.try_clone().expect("out of memory"), vec);
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_clone(&self) -> Result<Self, CollectionAllocErr> where A: Clone {
+        let mut vec = Vec::try_with_capacity_in(self.len(), self.buf.alloc().clone())?;
+        vec.extend_from_slice(self);
+        Ok(vec)
+    }
 }
 
-impl<T: Default> Vec<T> {
+impl<T: Default, A: Alloc> Vec<T, A> {
     /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
     ///
     /// If `new_len` is greater than `len`, the `Vec` is extended by the
@@ -1467,7 +1771,7 @@ impl<T, F: FnMut() -> T> ExtendWith<T> for ExtendFunc<F> {
     fn last(mut self) -> T { (self.0)() }
 }
 
-impl<T> Vec<T> {
+impl<T, A: Alloc> Vec<T, A> {
     /// Extend the vector by `n` values, using the given generator.
     fn extend_with<E: ExtendWith<T>>(&mut self, n: usize, mut value: E) {
         self.reserve(n);
@@ -1532,7 +1836,7 @@ impl<'a> Drop for SetLenOnDrop<'a> {
     }
 }
 
-impl<T: PartialEq> Vec<T> {
+impl<T: PartialEq, A: Alloc> Vec<T, A> {
     /// Removes consecutive repeated elements in the vector.
     ///
     /// If the vector is sorted, this removes all duplicates.
@@ -1681,28 +1985,31 @@ unsafe impl<T: ?Sized> IsZero for *mut T {
 ////////////////////////////////////////////////////////////////////////////////
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Clone> Clone for Vec<T> {
-    #[cfg(not(test))]
-    fn clone(&self) -> Vec<T> {
-        <[T]>::to_vec(&**self)
+impl<T: Clone, A: Alloc + Clone> Clone for Vec<T, A> {
+    fn clone(&self) -> Vec<T, A> {
+        let mut v = Vec::with_capacity_in(self.len(), self.buf.alloc().clone());
+        v.extend_from_slice(self);
+        v
     }
 
-    // HACK(japaric): with cfg(test) the inherent `[T]::to_vec` method, which is
-    // required for this method definition, is not available. Instead use the
-    // `slice::to_vec`  function which is only available with cfg(test)
-    // NB see the slice::hack module in slice.rs for more information
-    #[cfg(test)]
-    fn clone(&self) -> Vec<T> {
-        ::slice::to_vec(&**self)
-    }
+    fn clone_from(&mut self, other: &Vec<T, A>) {
+        // This is `<[T]>::clone_into`, which only knows how to fill a
+        // `Vec<T>`.
+        // drop anything that will not be overwritten
+        self.truncate(other.len());
+        let len = self.len();
+
+        // reuse the contained values' allocations/resources.
+        self.clone_from_slice(&other[..len]);
 
-    fn clone_from(&mut self, other: &Vec<T>) {
-        other.as_slice().clone_into(self);
+        // self.len <= other.len due to the truncate above, so the
+        // slice here is always in-bounds.
+        self.extend_from_slice(&other[len..]);
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Hash> Hash for Vec<T> {
+impl<T: Hash, A: Alloc> Hash for Vec<T, A> {
     #[inline]
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         Hash::hash(&**self, state)
@@ -1714,7 +2021,7 @@ impl<T: Hash> Hash for Vec<T> {
     message="vector indices are of type `usize` or ranges of `usize`",
     label="vector indices are of type `usize` or ranges of `usize`",
 )]
-impl<T, I> Index<I> for Vec<T>
+impl<T, I, A: Alloc> Index<I> for Vec<T, A>
 where
     I: ::core::slice::SliceIndex<[T]>,
 {
@@ -1731,7 +2038,7 @@ where
     message="vector indices are of type `usize` or ranges of `usize`",
     label="vector indices are of type `usize` or ranges of `usize`",
 )]
-impl<T, I> IndexMut<I> for Vec<T>
+impl<T, I, A: Alloc> IndexMut<I> for Vec<T, A>
 where
     I: ::core::slice::SliceIndex<[T]>,
 {
@@ -1742,7 +2049,7 @@ where
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ops::Deref for Vec<T> {
+impl<T, A: Alloc> ops::Deref for Vec<T, A> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -1755,7 +2062,7 @@ impl<T> ops::Deref for Vec<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ops::DerefMut for Vec<T> {
+impl<T, A: Alloc> ops::DerefMut for Vec<T, A> {
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe {
             let ptr = self.buf.ptr();
@@ -1769,14 +2076,14 @@ impl<T> ops::DerefMut for Vec<T> {
 impl<T> FromIterator<T> for Vec<T> {
     #[inline]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Vec<T> {
-        <Self as SpecExtend<T, I::IntoIter>>::from_iter(iter.into_iter())
+        <Self as SpecFromIter<T, I::IntoIter>>::from_iter(iter.into_iter())
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> IntoIterator for Vec<T> {
+impl<T, A: Alloc> IntoIterator for Vec<T, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     /// Creates a consuming iterator, that is, one that moves each value out of
     /// the vector (from start to end). The vector cannot be used after calling
@@ -1792,7 +2099,7 @@ impl<T> IntoIterator for Vec<T> {
     /// }
     /// ```
     #[inline]
-    fn into_iter(mut self) -> IntoIter<T> {
+    fn into_iter(mut self) -> IntoIter<T, A> {
         unsafe {
             let begin = self.as_mut_ptr();
             assume(!begin.is_null());
@@ -1801,12 +2108,14 @@ impl<T> IntoIterator for Vec<T> {
             } else {
                 begin.offset(self.len() as isize) as *const T
             };
-            let cap = self.buf.cap();
+            let buf = ptr::read(&self.buf);
             mem::forget(self);
+            let (_, cap, alloc) = buf.into_raw_parts_in();
             IntoIter {
                 buf: NonNull::new_unchecked(begin),
                 phantom: PhantomData,
                 cap,
+                alloc: ManuallyDrop::new(alloc),
                 ptr: begin,
                 end,
             }
@@ -1815,7 +2124,7 @@ impl<T> IntoIterator for Vec<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a Vec<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a Vec<T, A> {
     type Item = &'a T;
     type IntoIter = slice::Iter<'a, T>;
 
@@ -1825,7 +2134,7 @@ impl<'a, T> IntoIterator for &'a Vec<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a mut Vec<T> {
+impl<'a, T, A: Alloc> IntoIterator for &'a mut Vec<T, A> {
     type Item = &'a mut T;
     type IntoIter = slice::IterMut<'a, T>;
 
@@ -1835,20 +2144,24 @@ impl<'a, T> IntoIterator for &'a mut Vec<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Extend<T> for Vec<T> {
+impl<T, A: Alloc> Extend<T> for Vec<T, A> {
     #[inline]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         <Self as SpecExtend<T, I::IntoIter>>::spec_extend(self, iter.into_iter())
     }
 }
 
-// Specialization trait used for Vec::from_iter and Vec::extend
-trait SpecExtend<T, I> {
+// Specialization trait used for Vec::from_iter
+trait SpecFromIter<T, I> {
     fn from_iter(iter: I) -> Self;
+}
+
+// Specialization trait used for Vec::extend
+trait SpecExtend<T, I> {
     fn spec_extend(&mut self, iter: I);
 }
 
-impl<T, I> SpecExtend<T, I> for Vec<T>
+impl<T, I> SpecFromIter<T, I> for Vec<T>
     where I: Iterator<Item=T>,
 {
     default fn from_iter(mut iterator: I) -> Self {
@@ -1872,13 +2185,17 @@ impl<T, I> SpecExtend<T, I> for Vec<T>
         <Vec<T> as SpecExtend<T, I>>::spec_extend(&mut vector, iterator);
         vector
     }
+}
 
+impl<T, I, A: Alloc> SpecExtend<T, I> for Vec<T, A>
+    where I: Iterator<Item=T>,
+{
     default fn spec_extend(&mut self, iter: I) {
         self.extend_desugared(iter)
     }
 }
 
-impl<T, I> SpecExtend<T, I> for Vec<T>
+impl<T, I> SpecFromIter<T, I> for Vec<T>
     where I: TrustedLen<Item=T>,
 {
     default fn from_iter(iterator: I) -> Self {
@@ -1886,7 +2203,11 @@ impl<T, I> SpecExtend<T, I> for Vec<T>
         vector.spec_extend(iterator);
         vector
     }
+}
 
+impl<T, I, A: Alloc> SpecExtend<T, I> for Vec<T, A>
+    where I: TrustedLen<Item=T>,
+{
     default fn spec_extend(&mut self, iterator: I) {
         // This is the case for a TrustedLen iterator.
         let (low, high) = iterator.size_hint();
@@ -1913,7 +2234,7 @@ impl<T, I> SpecExtend<T, I> for Vec<T>
     }
 }
 
-impl<T> SpecExtend<T, IntoIter<T>> for Vec<T> {
+impl<T> SpecFromIter<T, IntoIter<T>> for Vec<T> {
     fn from_iter(iterator: IntoIter<T>) -> Self {
         // A common case is passing a vector into a function which immediately
         // re-collects into a vector. We can short circuit this if the IntoIter
@@ -1932,8 +2253,10 @@ impl<T> SpecExtend<T, IntoIter<T>> for Vec<T> {
             vector
         }
     }
+}
 
-    fn spec_extend(&mut self, mut iterator: IntoIter<T>) {
+impl<T, A: Alloc, B: Alloc> SpecExtend<T, IntoIter<T, B>> for Vec<T, A> {
+    fn spec_extend(&mut self, mut iterator: IntoIter<T, B>) {
         unsafe {
             self.append_elements(iterator.as_slice() as _);
         }
@@ -1941,20 +2264,25 @@ impl<T> SpecExtend<T, IntoIter<T>> for Vec<T> {
     }
 }
 
-impl<'a, T: 'a, I> SpecExtend<&'a T, I> for Vec<T>
+impl<'a, T: 'a, I> SpecFromIter<&'a T, I> for Vec<T>
     where I: Iterator<Item=&'a T>,
           T: Clone,
 {
     default fn from_iter(iterator: I) -> Self {
-        SpecExtend::from_iter(iterator.cloned())
+        SpecFromIter::from_iter(iterator.cloned())
     }
+}
 
+impl<'a, T: 'a, I, A: Alloc> SpecExtend<&'a T, I> for Vec<T, A>
+    where I: Iterator<Item=&'a T>,
+          T: Clone,
+{
     default fn spec_extend(&mut self, iterator: I) {
         self.spec_extend(iterator.cloned())
     }
 }
 
-impl<'a, T: 'a> SpecExtend<&'a T, slice::Iter<'a, T>> for Vec<T>
+impl<'a, T: 'a, A: Alloc> SpecExtend<&'a T, slice::Iter<'a, T>> for Vec<T, A>
     where T: Copy,
 {
     fn spec_extend(&mut self, iterator: slice::Iter<'a, T>) {
@@ -1968,7 +2296,7 @@ impl<'a, T: 'a> SpecExtend<&'a T, slice::Iter<'a, T>> for Vec<T>
     }
 }
 
-impl<T> Vec<T> {
+impl<T, A: Alloc> Vec<T, A> {
     fn extend_desugared<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
         // This is the case for a general iterator.
         //
@@ -2028,7 +2356,7 @@ impl<T> Vec<T> {
     /// ```
     #[inline]
     #[stable(feature = "vec_splice", since = "1.21.0")]
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<I::IntoIter>
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<I::IntoIter, A>
         where R: RangeBounds<usize>, I: IntoIterator<Item=T>
     {
         Splice {
@@ -2083,7 +2411,7 @@ impl<T> Vec<T> {
     /// assert_eq!(odds, vec![1, 3, 5, 9, 11, 13, 15]);
     /// ```
     #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<T, F>
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<T, F, A>
         where F: FnMut(&mut T) -> bool,
     {
         let old_len = self.len();
@@ -2108,19 +2436,19 @@ impl<T> Vec<T> {
 ///
 /// [`copy_from_slice`]: ../../std/primitive.slice.html#method.copy_from_slice
 #[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, T: 'a + Copy> Extend<&'a T> for Vec<T> {
+impl<'a, T: 'a + Copy, A: Alloc> Extend<&'a T> for Vec<T, A> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
         self.spec_extend(iter.into_iter())
     }
 }
 
 macro_rules! __impl_slice_eq1 {
-    ($Lhs: ty, $Rhs: ty) => {
-        __impl_slice_eq1! { $Lhs, $Rhs, Sized }
+    ([$($vars:tt)*] $Lhs: ty, $Rhs: ty) => {
+        __impl_slice_eq1! { [$($vars)*] $Lhs, $Rhs, Sized }
     };
-    ($Lhs: ty, $Rhs: ty, $Bound: ident) => {
+    ([$($vars:tt)*] $Lhs: ty, $Rhs: ty, $Bound: ident) => {
         #[stable(feature = "rust1", since = "1.0.0")]
-        impl<'a, 'b, A: $Bound, B> PartialEq<$Rhs> for $Lhs where A: PartialEq<B> {
+        impl<'a, 'b, T: $Bound, U, $($vars)*> PartialEq<$Rhs> for $Lhs where T: PartialEq<U> {
             #[inline]
             fn eq(&self, other: &$Rhs) -> bool { self[..] == other[..] }
             #[inline]
@@ -2129,23 +2457,23 @@ macro_rules! __impl_slice_eq1 {
     }
 }
 
-__impl_slice_eq1! { Vec<A>, Vec<B> }
-__impl_slice_eq1! { Vec<A>, &'b [B] }
-__impl_slice_eq1! { Vec<A>, &'b mut [B] }
-__impl_slice_eq1! { Cow<'a, [A]>, &'b [B], Clone }
-__impl_slice_eq1! { Cow<'a, [A]>, &'b mut [B], Clone }
-__impl_slice_eq1! { Cow<'a, [A]>, Vec<B>, Clone }
+__impl_slice_eq1! { [A1: Alloc, A2: Alloc] Vec<T, A1>, Vec<U, A2> }
+__impl_slice_eq1! { [A: Alloc] Vec<T, A>, &'b [U] }
+__impl_slice_eq1! { [A: Alloc] Vec<T, A>, &'b mut [U] }
+__impl_slice_eq1! { [] Cow<'a, [T]>, &'b [U], Clone }
+__impl_slice_eq1! { [] Cow<'a, [T]>, &'b mut [U], Clone }
+__impl_slice_eq1! { [A: Alloc] Cow<'a, [T]>, Vec<U, A>, Clone }
 
 macro_rules! array_impls {
     ($($N: expr)+) => {
         $(
             // NOTE: some less important impls are omitted to reduce code bloat
-            __impl_slice_eq1! { Vec<A>, [B; $N] }
-            __impl_slice_eq1! { Vec<A>, &'b [B; $N] }
-            // __impl_slice_eq1! { Vec<A>, &'b mut [B; $N] }
-            // __impl_slice_eq1! { Cow<'a, [A]>, [B; $N], Clone }
-            // __impl_slice_eq1! { Cow<'a, [A]>, &'b [B; $N], Clone }
-            // __impl_slice_eq1! { Cow<'a, [A]>, &'b mut [B; $N], Clone }
+            __impl_slice_eq1! { [A: Alloc] Vec<T, A>, [U; $N] }
+            __impl_slice_eq1! { [A: Alloc] Vec<T, A>, &'b [U; $N] }
+            // __impl_slice_eq1! { [A: Alloc] Vec<T, A>, &'b mut [U; $N] }
+            // __impl_slice_eq1! { [] Cow<'a, [T]>, [U; $N], Clone }
+            // __impl_slice_eq1! { [] Cow<'a, [T]>, &'b [U; $N], Clone }
+            // __impl_slice_eq1! { [] Cow<'a, [T]>, &'b mut [U; $N], Clone }
         )+
     }
 }
@@ -2159,27 +2487,27 @@ array_impls! {
 
 /// Implements comparison of vectors, lexicographically.
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: PartialOrd> PartialOrd for Vec<T> {
+impl<T: PartialOrd, A: Alloc> PartialOrd for Vec<T, A> {
     #[inline]
-    fn partial_cmp(&self, other: &Vec<T>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &Vec<T, A>) -> Option<Ordering> {
         PartialOrd::partial_cmp(&**self, &**other)
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Eq> Eq for Vec<T> {}
+impl<T: Eq, A: Alloc> Eq for Vec<T, A> {}
 
 /// Implements ordering of vectors, lexicographically.
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Ord> Ord for Vec<T> {
+impl<T: Ord, A: Alloc> Ord for Vec<T, A> {
     #[inline]
-    fn cmp(&self, other: &Vec<T>) -> Ordering {
+    fn cmp(&self, other: &Vec<T, A>) -> Ordering {
         Ord::cmp(&**self, &**other)
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for Vec<T> {
+unsafe impl<#[may_dangle] T, A: Alloc> Drop for Vec<T, A> {
     fn drop(&mut self) {
         unsafe {
             // use drop for [T]
@@ -2198,35 +2526,35 @@ impl<T> Default for Vec<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T: fmt::Debug> fmt::Debug for Vec<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for Vec<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(&**self, f)
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> AsRef<Vec<T>> for Vec<T> {
-    fn as_ref(&self) -> &Vec<T> {
+impl<T, A: Alloc> AsRef<Vec<T, A>> for Vec<T, A> {
+    fn as_ref(&self) -> &Vec<T, A> {
         self
     }
 }
 
 #[stable(feature = "vec_as_mut", since = "1.5.0")]
-impl<T> AsMut<Vec<T>> for Vec<T> {
-    fn as_mut(&mut self) -> &mut Vec<T> {
+impl<T, A: Alloc> AsMut<Vec<T, A>> for Vec<T, A> {
+    fn as_mut(&mut self) -> &mut Vec<T, A> {
         self
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> AsRef<[T]> for Vec<T> {
+impl<T, A: Alloc> AsRef<[T]> for Vec<T, A> {
     fn as_ref(&self) -> &[T] {
         self
     }
 }
 
 #[stable(feature = "vec_as_mut", since = "1.5.0")]
-impl<T> AsMut<[T]> for Vec<T> {
+impl<T, A: Alloc> AsMut<[T]> for Vec<T, A> {
     fn as_mut(&mut self) -> &mut [T] {
         self
     }
@@ -2332,16 +2660,18 @@ impl<'a, T> FromIterator<T> for Cow<'a, [T]> where T: Clone {
 /// [`Vec`]: struct.Vec.html
 /// [`IntoIterator`]: ../../std/iter/trait.IntoIterator.html
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<T> {
+pub struct IntoIter<T, A: Alloc = Global> {
     buf: NonNull<T>,
     phantom: PhantomData<T>,
     cap: usize,
+    // Taken out in `drop` to free the buffer.
+    alloc: ManuallyDrop<A>,
     ptr: *const T,
     end: *const T,
 }
 
 #[stable(feature = "vec_intoiter_debug", since = "1.13.0")]
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Alloc> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("IntoIter")
             .field(&self.as_slice())
@@ -2349,7 +2679,7 @@ impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
     }
 }
 
-impl<T> IntoIter<T> {
+impl<T, A: Alloc> IntoIter<T, A> {
     /// Returns the remaining items of this iterator as a slice.
     ///
     /// # Examples
@@ -2390,12 +2720,12 @@ impl<T> IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<T: Send> Send for IntoIter<T> {}
+unsafe impl<T: Send, A: Alloc + Send> Send for IntoIter<T, A> {}
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<T: Sync> Sync for IntoIter<T> {}
+unsafe impl<T: Sync, A: Alloc + Sync> Sync for IntoIter<T, A> {}
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Alloc> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
@@ -2440,7 +2770,7 @@ impl<T> Iterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Alloc> DoubleEndedIterator for IntoIter<T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         unsafe {
@@ -2465,33 +2795,38 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ExactSizeIterator for IntoIter<T> {
+impl<T, A: Alloc> ExactSizeIterator for IntoIter<T, A> {
     fn is_empty(&self) -> bool {
         self.ptr == self.end
     }
 }
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<T> FusedIterator for IntoIter<T> {}
+impl<T, A: Alloc> FusedIterator for IntoIter<T, A> {}
 
 #[unstable(feature = "trusted_len", issue = "37572")]
-unsafe impl<T> TrustedLen for IntoIter<T> {}
+unsafe impl<T, A: Alloc> TrustedLen for IntoIter<T, A> {}
 
 #[stable(feature = "vec_into_iter_clone", since = "1.8.0")]
-impl<T: Clone> Clone for IntoIter<T> {
-    fn clone(&self) -> IntoIter<T> {
-        self.as_slice().to_owned().into_iter()
+impl<T: Clone, A: Alloc + Clone> Clone for IntoIter<T, A> {
+    fn clone(&self) -> IntoIter<T, A> {
+        let mut v = Vec::with_capacity_in(self.len(), (*self.alloc).clone());
+        v.extend_from_slice(self.as_slice());
+        v.into_iter()
     }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for IntoIter<T> {
+unsafe impl<#[may_dangle] T, A: Alloc> Drop for IntoIter<T, A> {
     fn drop(&mut self) {
         // destroy the remaining elements
         for _x in self.by_ref() {}
 
         // RawVec handles deallocation
-        let _ = unsafe { RawVec::from_raw_parts(self.buf.as_ptr(), self.cap) };
+        let _ = unsafe {
+            let alloc = ptr::read(&*self.alloc);
+            RawVec::from_raw_parts_in(self.buf.as_ptr(), self.cap, alloc)
+        };
     }
 }
 
@@ -2502,18 +2837,18 @@ unsafe impl<#[may_dangle] T> Drop for IntoIter<T> {
 /// [`drain`]: struct.Vec.html#method.drain
 /// [`Vec`]: struct.Vec.html
 #[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a, T: 'a> {
+pub struct Drain<'a, T: 'a, A: 'a + Alloc = Global> {
     /// Index of tail to preserve
     tail_start: usize,
     /// Length of tail
     tail_len: usize,
     /// Current remaining range to remove
     iter: slice::Iter<'a, T>,
-    vec: NonNull<Vec<T>>,
+    vec: NonNull<Vec<T, A>>,
 }
 
 #[stable(feature = "collection_debug", since = "1.17.0")]
-impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
+impl<'a, T: 'a + fmt::Debug, A: Alloc> fmt::Debug for Drain<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("Drain")
          .field(&self.iter.as_slice())
@@ -2522,12 +2857,12 @@ impl<'a, T: 'a + fmt::Debug> fmt::Debug for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+unsafe impl<'a, T: Sync, A: Alloc + Sync> Sync for Drain<'a, T, A> {}
 #[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Send, A: Alloc + Send> Send for Drain<'a, T, A> {}
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T> Iterator for Drain<'a, T> {
+impl<'a, T, A: Alloc> Iterator for Drain<'a, T, A> {
     type Item = T;
 
     #[inline]
@@ -2541,7 +2876,7 @@ impl<'a, T> Iterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+impl<'a, T, A: Alloc> DoubleEndedIterator for Drain<'a, T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back().map(|elt| unsafe { ptr::read(elt as *const _) })
@@ -2549,7 +2884,7 @@ impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
 }
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T> Drop for Drain<'a, T> {
+impl<'a, T, A: Alloc> Drop for Drain<'a, T, A> {
     fn drop(&mut self) {
         // exhaust self first
         self.for_each(drop);
@@ -2573,14 +2908,14 @@ impl<'a, T> Drop for Drain<'a, T> {
 
 
 #[stable(feature = "drain", since = "1.6.0")]
-impl<'a, T> ExactSizeIterator for Drain<'a, T> {
+impl<'a, T, A: Alloc> ExactSizeIterator for Drain<'a, T, A> {
     fn is_empty(&self) -> bool {
         self.iter.is_empty()
     }
 }
 
 #[stable(feature = "fused", since = "1.26.0")]
-impl<'a, T> FusedIterator for Drain<'a, T> {}
+impl<'a, T, A: Alloc> FusedIterator for Drain<'a, T, A> {}
 
 /// A splicing iterator for `Vec`.
 ///
@@ -2591,13 +2926,13 @@ impl<'a, T> FusedIterator for Drain<'a, T> {}
 /// [`Vec`]: struct.Vec.html
 #[derive(Debug)]
 #[stable(feature = "vec_splice", since = "1.21.0")]
-pub struct Splice<'a, I: Iterator + 'a> {
-    drain: Drain<'a, I::Item>,
+pub struct Splice<'a, I: Iterator + 'a, A: 'a + Alloc = Global> {
+    drain: Drain<'a, I::Item, A>,
     replace_with: I,
 }
 
 #[stable(feature = "vec_splice", since = "1.21.0")]
-impl<'a, I: Iterator> Iterator for Splice<'a, I> {
+impl<'a, I: Iterator, A: Alloc> Iterator for Splice<'a, I, A> {
     type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -2610,18 +2945,18 @@ impl<'a, I: Iterator> Iterator for Splice<'a, I> {
 }
 
 #[stable(feature = "vec_splice", since = "1.21.0")]
-impl<'a, I: Iterator> DoubleEndedIterator for Splice<'a, I> {
+impl<'a, I: Iterator, A: Alloc> DoubleEndedIterator for Splice<'a, I, A> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.drain.next_back()
     }
 }
 
 #[stable(feature = "vec_splice", since = "1.21.0")]
-impl<'a, I: Iterator> ExactSizeIterator for Splice<'a, I> {}
+impl<'a, I: Iterator, A: Alloc> ExactSizeIterator for Splice<'a, I, A> {}
 
 
 #[stable(feature = "vec_splice", since = "1.21.0")]
-impl<'a, I: Iterator> Drop for Splice<'a, I> {
+impl<'a, I: Iterator, A: Alloc> Drop for Splice<'a, I, A> {
     fn drop(&mut self) {
         self.drain.by_ref().for_each(drop);
 
@@ -2662,7 +2997,7 @@ impl<'a, I: Iterator> Drop for Splice<'a, I> {
 }
 
 /// Private helper methods for `Splice::drop`
-impl<'a, T> Drain<'a, T> {
+impl<'a, T, A: Alloc> Drain<'a, T, A> {
     /// The range from `self.vec.len` to `self.tail_start` contains elements
     /// that have been moved out.
     /// Fill that range as much as possible with new elements from the `replace_with` iterator.
@@ -2703,10 +3038,10 @@ impl<'a, T> Drain<'a, T> {
 /// An iterator produced by calling `drain_filter` on Vec.
 #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
 #[derive(Debug)]
-pub struct DrainFilter<'a, T: 'a, F>
+pub struct DrainFilter<'a, T: 'a, F, A: 'a + Alloc = Global>
     where F: FnMut(&mut T) -> bool,
 {
-    vec: &'a mut Vec<T>,
+    vec: &'a mut Vec<T, A>,
     idx: usize,
     del: usize,
     old_len: usize,
@@ -2714,7 +3049,7 @@ pub struct DrainFilter<'a, T: 'a, F>
 }
 
 #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<'a, T, F> Iterator for DrainFilter<'a, T, F>
+impl<'a, T, F, A: Alloc> Iterator for DrainFilter<'a, T, F, A>
     where F: FnMut(&mut T) -> bool,
 {
     type Item = T;
@@ -2748,7 +3083,7 @@ impl<'a, T, F> Iterator for DrainFilter<'a, T, F>
 }
 
 #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<'a, T, F> Drop for DrainFilter<'a, T, F>
+impl<'a, T, F, A: Alloc> Drop for DrainFilter<'a, T, F, A>
     where F: FnMut(&mut T) -> bool,
 {
     fn drop(&mut self) {
@@ -1383,6 +1383,184 @@ impl<'a, T: ?Sized + fmt::Display> fmt::Display for RefMut<'a, T> {
     }
 }
 
+/// A cell which can be written to only once.
+///
+/// Unlike [`RefCell`], a `OnceCell` only provides shared `&T` references to its value once it has
+/// been initialized, and unlike [`Cell`] it does not require copying or replacing the value to
+/// access it. This makes it a good fit for caching a value that is computed on first use.
+///
+/// `OnceCell<T>` is not thread safe; see `std::sync::OnceCell` for a variant which may be shared
+/// between threads.
+///
+/// [`RefCell`]: struct.RefCell.html
+/// [`Cell`]: struct.Cell.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(once_cell)]
+///
+/// use std::cell::OnceCell;
+///
+/// let cell = OnceCell::new();
+/// assert!(cell.get().is_none());
+///
+/// let value: &String = cell.get_or_init(|| "Hello, World!".to_string());
+/// assert_eq!(value, "Hello, World!");
+/// assert!(cell.get().is_some());
+/// ```
+#[unstable(feature = "once_cell", issue = "0")]
+pub struct OnceCell<T> {
+    // Invariant: written to at most once.
+    inner: UnsafeCell<Option<T>>,
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> !Sync for OnceCell<T> {}
+
+impl<T> OnceCell<T> {
+    /// Creates a new, uninitialized cell.
+    #[unstable(feature = "once_cell", issue = "0")]
+    #[inline]
+    pub const fn new() -> OnceCell<T> {
+        OnceCell { inner: UnsafeCell::new(None) }
+    }
+
+    /// Returns a reference to the underlying value, or `None` if the cell is still empty.
+    #[unstable(feature = "once_cell", issue = "0")]
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        // Safe because a reference into the contents is only ever handed out once the cell has
+        // been written to, after which it is never written to again.
+        unsafe { &*self.inner.get() }.as_ref()
+    }
+
+    /// Returns a mutable reference to the underlying value, or `None` if the cell is still empty.
+    ///
+    /// This call borrows the cell mutably, so no other references to its contents can exist.
+    #[unstable(feature = "once_cell", issue = "0")]
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        unsafe { &mut *self.inner.get() }.as_mut()
+    }
+
+    /// Sets the contents of the cell to `value`.
+    ///
+    /// Returns `Err(value)` if the cell was already initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::cell::OnceCell;
+    ///
+    /// let cell = OnceCell::new();
+    /// assert!(cell.get().is_none());
+    ///
+    /// assert_eq!(cell.set(92), Ok(()));
+    /// assert_eq!(cell.set(62), Err(62));
+    ///
+    /// assert_eq!(cell.get(), Some(&92));
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.get().is_some() {
+            return Err(value);
+        }
+        // Safe because we have just checked that there are no outstanding references to the
+        // contents: references can only be handed out once the cell is initialized.
+        let slot = unsafe { &mut *self.inner.get() };
+        *slot = Some(value);
+        Ok(())
+    }
+
+    /// Gets the contents of the cell, initializing it with `f` if the cell was empty.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics, the panic is propagated to the caller and the cell remains uninitialized.
+    ///
+    /// It is an error to reentrantly initialize the cell from `f`. Doing so results in a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::cell::OnceCell;
+    ///
+    /// let cell = OnceCell::new();
+    /// let value = cell.get_or_init(|| 92);
+    /// assert_eq!(value, &92);
+    /// let value = cell.get_or_init(|| unreachable!());
+    /// assert_eq!(value, &92);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get_or_init<F>(&self, f: F) -> &T where F: FnOnce() -> T {
+        if let Some(val) = self.get() {
+            return val;
+        }
+        let val = f();
+        assert!(self.set(val).is_ok(), "reentrant init");
+        self.get().unwrap()
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    ///
+    /// Returns `None` if the cell was empty.
+    #[unstable(feature = "once_cell", issue = "0")]
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.inner.into_inner()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> Default for OnceCell<T> {
+    fn default() -> OnceCell<T> {
+        OnceCell::new()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Debug> Debug for OnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(v) => f.debug_tuple("OnceCell").field(v).finish(),
+            None => f.write_str("OnceCell(Uninit)"),
+        }
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Clone> Clone for OnceCell<T> {
+    fn clone(&self) -> OnceCell<T> {
+        let res = OnceCell::new();
+        if let Some(value) = self.get() {
+            let _ = res.set(value.clone());
+        }
+        res
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: PartialEq> PartialEq for OnceCell<T> {
+    fn eq(&self, other: &OnceCell<T>) -> bool {
+        self.get() == other.get()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Eq> Eq for OnceCell<T> {}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> From<T> for OnceCell<T> {
+    fn from(value: T) -> OnceCell<T> {
+        OnceCell { inner: UnsafeCell::new(Some(value)) }
+    }
+}
+
 /// The core primitive for interior mutability in Rust.
 ///
 /// `UnsafeCell<T>` is a type that wraps some `T` and indicates unsafe interior operations on the
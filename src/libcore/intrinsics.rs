@@ -1376,4 +1376,14 @@ extern "rust-intrinsic" {
     /// Emits a `!nontemporal` store according to LLVM (see their docs).
     /// Probably will never become stable.
     pub fn nontemporal_store<T>(ptr: *mut T, val: T);
+
+    /// Increments counter `index` of the `num_counters` coverage counters of the
+    /// calling function. The source code the counter covers is the span of the
+    /// `index` argument.
+    ///
+    /// Calls to this intrinsic are inserted by the compiler when compiling with
+    /// `-Z instrument-coverage`, it is not meant to be called directly.
+    #[cfg(not(stage0))]
+    #[lang = "count_code_region"]
+    pub fn count_code_region(num_counters: u32, index: u32);
 }
@@ -30,6 +30,8 @@ pub mod pattern;
 #[allow(missing_docs)]
 pub mod lossy;
 
+mod validations;
+
 /// A trait to abstract the idea of creating a new instance of a type from a
 /// string.
 ///
@@ -1427,7 +1429,9 @@ fn contains_nonascii(x: usize) -> bool {
 /// invalid sequence.
 #[inline]
 fn run_utf8_validation(v: &[u8]) -> Result<(), Utf8Error> {
-    let mut index = 0;
+    // Skip over whatever the vectorized validator could prove valid; the loop below
+    // validates the rest and computes the exact error position.
+    let mut index = validations::valid_prefix_len(v);
     let len = v.len();
 
     let usize_bytes = mem::size_of::<usize>();
@@ -0,0 +1,76 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Vectorized UTF-8 validation.
+//!
+//! The vector validator only answers the question "how much of this input is certainly
+//! valid?". `run_utf8_validation` skips over that prefix and hands the rest to the scalar
+//! validator, so the `valid_up_to` and `error_len` values reported through `Utf8Error` are
+//! always computed by the same code, no matter which instruction set did the heavy lifting.
+//!
+//! Only SSE2 is used, to skip over runs of ASCII. On targets without it the prefix is
+//! always empty, leaving everything to the portable word-at-a-time validator.
+
+/// Returns the length of a prefix of `v` that is valid UTF-8 and ends on a character
+/// boundary. The rest of `v` has not been validated.
+#[inline]
+pub(super) fn valid_prefix_len(v: &[u8]) -> usize {
+    imp::valid_prefix_len(v)
+}
+
+#[cfg(not(all(not(stage0), any(target_arch = "x86", target_arch = "x86_64"))))]
+mod imp {
+    #[inline]
+    pub fn valid_prefix_len(_v: &[u8]) -> usize {
+        0
+    }
+}
+
+#[cfg(all(not(stage0), any(target_arch = "x86", target_arch = "x86_64")))]
+mod imp {
+    #[cfg(target_arch = "x86")]
+    use arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use arch::x86_64::*;
+
+    /// Inputs shorter than this are left to the scalar validator entirely, as setting up
+    /// the vector loop costs more than it saves.
+    const MIN_LEN: usize = 64;
+
+    // SSE2 is part of the x86_64 baseline, so that target always takes this path. Picking
+    // a wider validator would mean probing the CPU at runtime, which is std's business,
+    // not something libcore can do portably.
+    #[inline]
+    pub fn valid_prefix_len(v: &[u8]) -> usize {
+        if v.len() < MIN_LEN {
+            return 0;
+        }
+        if cfg!(target_feature = "sse2") {
+            unsafe { valid_prefix_len_sse2(v) }
+        } else {
+            0
+        }
+    }
+
+    /// Skips over ASCII, 16 bytes at a time.
+    #[target_feature(enable = "sse2")]
+    unsafe fn valid_prefix_len_sse2(v: &[u8]) -> usize {
+        let mut index = 0;
+        while index + 16 <= v.len() {
+            let input = _mm_loadu_si128(v.as_ptr().offset(index as isize) as *const __m128i);
+            if _mm_movemask_epi8(input) != 0 {
+                break;
+            }
+            index += 16;
+        }
+        // Everything before `index` is ASCII, so it is a character boundary.
+        index
+    }
+}
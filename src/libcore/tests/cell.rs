@@ -11,6 +11,8 @@
 use core::cell::*;
 use core::default::Default;
 use std::mem::drop;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
 
 #[test]
 fn smoketest_cell() {
@@ -373,3 +375,58 @@ fn refcell_replace_borrows() {
     let _b = x.borrow();
     x.replace(1);
 }
+
+#[test]
+fn once_cell() {
+    let c = OnceCell::new();
+    assert!(c.get().is_none());
+    c.get_or_init(|| 92);
+    assert_eq!(c.get(), Some(&92));
+
+    c.get_or_init(|| panic!("Kabom!"));
+    assert_eq!(c.get(), Some(&92));
+}
+
+#[test]
+fn once_cell_set() {
+    let c = OnceCell::new();
+    assert_eq!(c.set(1), Ok(()));
+    assert_eq!(c.set(2), Err(2));
+    assert_eq!(c.into_inner(), Some(1));
+}
+
+#[test]
+fn once_cell_get_mut() {
+    let mut c = OnceCell::new();
+    assert!(c.get_mut().is_none());
+    c.set(90).unwrap();
+    *c.get_mut().unwrap() += 2;
+    assert_eq!(c.get_mut(), Some(&mut 92));
+}
+
+#[test]
+fn once_cell_drop() {
+    static DROP_CNT: AtomicUsize = AtomicUsize::new(0);
+    struct Dropper;
+    impl Drop for Dropper {
+        fn drop(&mut self) {
+            DROP_CNT.fetch_add(1, SeqCst);
+        }
+    }
+
+    let x = OnceCell::new();
+    x.get_or_init(|| Dropper);
+    assert_eq!(DROP_CNT.load(SeqCst), 0);
+    drop(x);
+    assert_eq!(DROP_CNT.load(SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "reentrant init")]
+fn once_cell_reentrant_init() {
+    let x: OnceCell<Box<i32>> = OnceCell::new();
+    x.get_or_init(|| {
+        let r = x.get_or_init(|| Box::new(92));
+        Box::new(**r)
+    });
+}
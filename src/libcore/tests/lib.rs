@@ -46,6 +46,7 @@
 #![feature(inner_deref)]
 #![feature(slice_internals)]
 #![feature(option_replace)]
+#![feature(once_cell)]
 
 extern crate core;
 extern crate test;
@@ -526,6 +526,7 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             ReadUndefBytes |
             DeadLocal |
             StackFrameLimitReached |
+            StepLimitReached |
             OutOfTls |
             TlsOutOfBounds |
             CalledClosureAsFunction |
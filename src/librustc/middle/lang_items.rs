@@ -105,18 +105,12 @@ struct LanguageItemCollector<'a, 'tcx: 'a> {
 
 impl<'a, 'v, 'tcx> ItemLikeVisitor<'v> for LanguageItemCollector<'a, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item) {
-        if let Some((value, span)) = extract(&item.attrs) {
-            let item_index = self.item_refs.get(&*value.as_str()).cloned();
+        self.collect_attrs(&item.attrs, item.id);
 
-            if let Some(item_index) = item_index {
-                let def_id = self.tcx.hir.local_def_id(item.id);
-                self.collect_item(item_index, def_id);
-            } else {
-                let mut err = struct_span_err!(self.tcx.sess, span, E0522,
-                                               "definition of an unknown language item: `{}`",
-                                               value);
-                err.span_label(span, format!("definition of unknown language item `{}`", value));
-                err.emit();
+        // Intrinsics can be lang items as well.
+        if let hir::ItemKind::ForeignMod(ref foreign_mod) = item.node {
+            for foreign_item in &foreign_mod.items {
+                self.collect_attrs(&foreign_item.attrs, foreign_item.id);
             }
         }
     }
@@ -143,6 +137,23 @@ impl<'a, 'tcx> LanguageItemCollector<'a, 'tcx> {
         }
     }
 
+    fn collect_attrs(&mut self, attrs: &[ast::Attribute], id: ast::NodeId) {
+        if let Some((value, span)) = extract(attrs) {
+            let item_index = self.item_refs.get(&*value.as_str()).cloned();
+
+            if let Some(item_index) = item_index {
+                let def_id = self.tcx.hir.local_def_id(id);
+                self.collect_item(item_index, def_id);
+            } else {
+                let mut err = struct_span_err!(self.tcx.sess, span, E0522,
+                                               "definition of an unknown language item: `{}`",
+                                               value);
+                err.span_label(span, format!("definition of unknown language item `{}`", value));
+                err.emit();
+            }
+        }
+    }
+
     fn collect_item(&mut self, item_index: usize, item_def_id: DefId) {
         // Check for duplicates.
         match self.items.items[item_index] {
@@ -360,6 +371,8 @@ language_item_table! {
     AlignOffsetLangItem,             "align_offset",            align_offset_fn;
 
     TerminationTraitLangItem,        "termination",             termination;
+
+    CountCodeRegionFnLangItem,       "count_code_region",       count_code_region_fn;
 }
 
 impl<'a, 'tcx, 'gcx> TyCtxt<'a, 'tcx, 'gcx> {
@@ -20,11 +20,16 @@ use syntax::ast;
 
 use rustc_data_structures::sync::Once;
 
+/// The number of steps a constant evaluation may take without a `#![const_eval_limit]`.
+pub const DEFAULT_CONST_EVAL_LIMIT: usize = 10_000_000;
+
 pub fn update_limits(sess: &Session, krate: &ast::Crate) {
     update_limit(sess, krate, &sess.recursion_limit, "recursion_limit",
                  "recursion limit", 64);
     update_limit(sess, krate, &sess.type_length_limit, "type_length_limit",
                  "type length limit", 1048576);
+    update_limit(sess, krate, &sess.const_eval_limit, "const_eval_limit",
+                 "const eval limit", DEFAULT_CONST_EVAL_LIMIT);
 }
 
 fn update_limit(sess: &Session, krate: &ast::Crate, limit: &Once<usize>,
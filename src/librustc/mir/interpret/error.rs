@@ -102,8 +102,23 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
             struct_error(tcx, message)
         };
         err.span_label(self.span, self.error.to_string());
-        for FrameInfo { span, location, .. } in &self.stacktrace {
-            err.span_label(*span, format!("inside call to `{}`", location));
+        // One note per frame, innermost first, with runs of the same call (as in a
+        // runaway recursion) collapsed into one.
+        let mut frames = self.stacktrace.iter().peekable();
+        while let Some(frame) = frames.next() {
+            let mut times = 1;
+            while frames.peek().map_or(false, |next| {
+                next.span == frame.span && next.location == frame.location
+            }) {
+                frames.next();
+                times += 1;
+            }
+            let msg = if times == 1 {
+                format!("inside call to `{}`", frame.location)
+            } else {
+                format!("inside call to `{}` (repeated {} times)", frame.location, times)
+            };
+            err.span_note(frame.span, &msg);
         }
         Some(err)
     }
@@ -204,6 +219,7 @@ pub enum EvalErrorKind<'tcx, O> {
     Intrinsic(String),
     InvalidChar(u128),
     StackFrameLimitReached,
+    StepLimitReached,
     OutOfTls,
     TlsOutOfBounds,
     AbiViolation(String),
@@ -331,6 +347,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "tried to interpret an invalid 32-bit value as a char",
             StackFrameLimitReached =>
                 "reached the configured maximum number of stack frames",
+            StepLimitReached =>
+                "reached the configured maximum number of evaluation steps",
             OutOfTls =>
                 "reached the maximum number of representable TLS keys",
             TlsOutOfBounds =>
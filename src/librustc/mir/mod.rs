@@ -2383,7 +2383,11 @@ pub struct UnsafetyCheckResult {
 /// The layout of generator state
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct GeneratorLayout<'tcx> {
+    /// The locals saved in the generator, in the order of their fields.
     pub fields: Vec<LocalDecl<'tcx>>,
+    /// Pairs of indices into `fields`, lowest first, of the locals which may have live storage
+    /// at the same time. Any two locals not listed here can share space in the generator.
+    pub storage_conflicts: Vec<(usize, usize)>,
 }
 
 #[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
@@ -2521,7 +2525,7 @@ BraceStructTypeFoldableImpl! {
 
 BraceStructTypeFoldableImpl! {
     impl<'tcx> TypeFoldable<'tcx> for GeneratorLayout<'tcx> {
-        fields
+        fields, storage_conflicts
     }
 }
 
@@ -112,6 +112,15 @@ impl CrossLangLto {
     }
 }
 
+/// How symbol names are mangled, see `rustc_codegen_utils::symbol_names`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SymbolManglingVersion {
+    /// `_ZN...17h<hash>E`, which looks like C++ but can't be demangled back into types.
+    Legacy,
+    /// `_R...`, which encodes generic arguments and can be demangled in full.
+    V0,
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfoLevel {
     NoDebugInfo,
@@ -819,12 +828,14 @@ macro_rules! options {
         pub const parse_cross_lang_lto: Option<&'static str> =
             Some("either a boolean (`yes`, `no`, `on`, `off`, etc), `no-link`, \
                   or the path to the linker plugin");
+        pub const parse_symbol_mangling_version: Option<&'static str> =
+            Some("either `legacy` or `v0`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, SomePasses, AllPasses, Sanitizer, Lto,
-                    CrossLangLto};
+                    CrossLangLto, SymbolManglingVersion};
         use rustc_target::spec::{LinkerFlavor, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
 
@@ -1046,6 +1057,16 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_symbol_mangling_version(slot: &mut SymbolManglingVersion,
+                                         v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("legacy") => SymbolManglingVersion::Legacy,
+                Some("v0") => SymbolManglingVersion::V0,
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -1269,6 +1290,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "in addition to `.mir` files, create graphviz `.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
           "if set, exclude the pass number when dumping MIR (used in tests)"),
+    validate_mir: bool = (false, parse_bool, [UNTRACKED],
+          "check the MIR after every MIR pass, and ICE naming the pass that broke it"),
     mir_emit_validate: usize = (0, parse_uint, [TRACKED],
           "emit Validate MIR statements, interpreted e.g. by miri (0: do not emit; 1: if function \
            contains unsafe block, only validate arguments; 2: always emit full validation)"),
@@ -1303,6 +1326,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
          location if it's empty."),
     pgo_use: String = (String::new(), parse_string, [TRACKED],
         "Use PGO profile data from the given profile file."),
+    instrument_coverage: bool = (false, parse_bool, [TRACKED],
+        "instrument the generated code with coverage counters for each MIR basic block and \
+         emit a coverage map, to measure line and region coverage with LLVM's tools"),
     disable_instrumentation_preinliner: bool =
         (false, parse_bool, [TRACKED], "Disable the instrumentation pre-inliner, \
         useful for profiling / PGO."),
@@ -1361,6 +1387,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "enable the experimental Chalk-based trait solving engine"),
     cross_lang_lto: CrossLangLto = (CrossLangLto::Disabled, parse_cross_lang_lto, [TRACKED],
           "generate build artifacts that are compatible with linker-based LTO."),
+    symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
+          parse_symbol_mangling_version, [TRACKED],
+          "which mangling to use for symbol names: `legacy` (the default) or `v0`"),
     no_parallel_llvm: bool = (false, parse_bool, [UNTRACKED],
           "don't run LLVM in parallel (while keeping codegen-units and ThinLTO)"),
     no_leak_check: bool = (false, parse_bool, [UNTRACKED],
@@ -1884,6 +1913,13 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    if debugging_opts.instrument_coverage && debugging_opts.pgo_gen.is_some() {
+        early_error(
+            error_format,
+            "options `-Z instrument-coverage` and `-Z pgo-gen` are exclusive",
+        );
+    }
+
     let mut output_types = BTreeMap::new();
     if !debugging_opts.parse_only {
         for list in matches.opt_strs("emit") {
@@ -2392,7 +2428,7 @@ mod dep_tracking {
     use std::path::PathBuf;
     use std::collections::hash_map::DefaultHasher;
     use super::{CrateType, DebugInfoLevel, ErrorOutputType, Lto, OptLevel, OutputTypes,
-                Passes, Sanitizer, CrossLangLto};
+                Passes, Sanitizer, CrossLangLto, SymbolManglingVersion};
     use syntax::feature_gate::UnstableFeatures;
     use rustc_target::spec::{PanicStrategy, RelroLevel, TargetTriple};
     use syntax::edition::Edition;
@@ -2457,6 +2493,7 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(CrossLangLto);
+    impl_dep_tracking_hash_via_hash!(SymbolManglingVersion);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
@@ -2521,7 +2558,7 @@ mod tests {
     use lint;
     use middle::cstore;
     use session::config::{build_configuration, build_session_options_and_crate_config};
-    use session::config::{Lto, CrossLangLto};
+    use session::config::{Lto, CrossLangLto, SymbolManglingVersion};
     use session::build_session;
     use std::collections::{BTreeMap, BTreeSet};
     use std::iter::FromIterator;
@@ -3025,6 +3062,10 @@ mod tests {
         opts.debugging_opts.pgo_use = String::from("abc");
         assert_ne!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.instrument_coverage = true;
+        assert_ne!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.cg.metadata = vec![String::from("A"), String::from("B")];
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -3124,6 +3165,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.validate_mir = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3181,6 +3224,10 @@ mod tests {
         opts = reference.clone();
         opts.debugging_opts.cross_lang_lto = CrossLangLto::LinkerPluginAuto;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.symbol_mangling_version = SymbolManglingVersion::V0;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 
     #[test]
@@ -113,6 +113,9 @@ pub struct Session {
     /// The maximum number of stackframes allowed in const eval
     pub const_eval_stack_frame_limit: usize,
 
+    /// The maximum number of steps a single const eval may take, or 0 for no limit.
+    pub const_eval_limit: Once<usize>,
+
     /// The metadata::creader module may inject an allocator/panic_runtime
     /// dependency if it didn't already find one, and this tracks what was
     /// injected.
@@ -1118,6 +1121,7 @@ pub fn build_session_(
         recursion_limit: Once::new(),
         type_length_limit: Once::new(),
         const_eval_stack_frame_limit: 100,
+        const_eval_limit: Once::new(),
         next_node_id: OneThread::new(Cell::new(NodeId::new(1))),
         injected_allocator: Once::new(),
         allocator_kind: Once::new(),
@@ -466,6 +466,84 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
         let univariant = |fields: &[TyLayout], repr: &ReprOptions, kind| {
             Ok(tcx.intern_layout(univariant_uninterned(fields, repr, kind)?))
         };
+
+        // Generators start with their upvars and state, laid out like a struct, followed by the
+        // locals saved across suspension points. Saved locals which don't conflict, as their
+        // storage is never live at the same time, can be given overlapping offsets.
+        let generator = |prefix: &[TyLayout], saved: &[TyLayout], conflicts: &[(usize, usize)]| {
+            let prefix = univariant_uninterned(prefix,
+                                               &ReprOptions::default(),
+                                               StructKind::AlwaysSized)?;
+            let mut offsets = match prefix.fields {
+                FieldPlacement::Arbitrary { ref offsets, .. } => offsets.clone(),
+                _ => bug!(),
+            };
+
+            let mut conflicting = vec![vec![]; saved.len()];
+            for &(a, b) in conflicts {
+                conflicting[a].push(b);
+                conflicting[b].push(a);
+            }
+
+            // Place the most aligned locals first, to waste as little space as possible.
+            let mut order: Vec<usize> = (0..saved.len()).collect();
+            order.sort_by_key(|&i| cmp::Reverse(saved[i].align.abi()));
+
+            let mut align = prefix.align;
+            let mut size = prefix.size;
+            let mut saved_offsets = vec![None; saved.len()];
+            for i in order {
+                let field = &saved[i];
+                // Start right after the prefix, and move past every local placed so far that
+                // this one both conflicts and overlaps with.
+                let mut offset = prefix.size.abi_align(field.align);
+                loop {
+                    let overlapping = conflicting[i].iter()
+                        .filter_map(|&j| saved_offsets[j].map(|start| (start, saved[j].size)))
+                        .find(|&(start, len)| offset < start + len && start < offset + field.size);
+                    match overlapping {
+                        Some((start, len)) => offset = (start + len).abi_align(field.align),
+                        None => break,
+                    }
+                }
+                let end = offset.checked_add(field.size, dl)
+                    .ok_or(LayoutError::SizeOverflow(ty))?;
+                saved_offsets[i] = Some(offset);
+                size = cmp::max(size, end);
+                align = align.max(field.align);
+            }
+            offsets.extend(saved_offsets.into_iter().map(|offset| offset.unwrap()));
+
+            size = size.abi_align(align);
+            if size.bytes() >= dl.obj_size_bound() {
+                return Err(LayoutError::SizeOverflow(ty));
+            }
+
+            // Fields are in memory order by offset, which keeps the prefix first.
+            let mut inverse_memory_index: Vec<u32> = (0..offsets.len() as u32).collect();
+            inverse_memory_index.sort_by_key(|&i| (offsets[i as usize], i));
+            let mut memory_index = vec![0; offsets.len()];
+            for (memory_position, &i) in inverse_memory_index.iter().enumerate() {
+                memory_index[i as usize] = memory_position as u32;
+            }
+
+            let abi = if prefix.abi == Abi::Uninhabited {
+                Abi::Uninhabited
+            } else {
+                Abi::Aggregate { sized: true }
+            };
+
+            Ok(tcx.intern_layout(LayoutDetails {
+                variants: Variants::Single { index: 0 },
+                fields: FieldPlacement::Arbitrary {
+                    offsets,
+                    memory_index
+                },
+                abi,
+                align,
+                size
+            }))
+        };
         assert!(!ty.has_infer_types());
 
         Ok(match ty.sty {
@@ -606,10 +684,18 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
 
             // Tuples, generators and closures.
             ty::TyGenerator(def_id, ref substs, _) => {
-                let tys = substs.field_tys(def_id, tcx);
-                univariant(&tys.map(|ty| self.layout_of(ty)).collect::<Result<Vec<_>, _>>()?,
-                    &ReprOptions::default(),
-                    StructKind::AlwaysSized)?
+                let prefix = substs.pre_transforms_tys(def_id, tcx)
+                    .map(|ty| self.layout_of(ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let saved = substs.state_tys(def_id, tcx)
+                    .map(|ty| self.layout_of(ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if saved.is_empty() {
+                    univariant(&prefix, &ReprOptions::default(), StructKind::AlwaysSized)?
+                } else {
+                    let conflicts = &tcx.generator_layout(def_id).storage_conflicts;
+                    generator(&prefix, &saved, conflicts)?
+                }
             }
 
             ty::TyClosure(def_id, ref substs) => {
@@ -883,6 +969,122 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
                     }
                 }
 
+                // Niche-filling with several data-carrying variants: the largest variant is
+                // left untagged, and a niche in one of its fields tells the others apart. The
+                // fields of each other variant have to fit entirely before or after the niche.
+                // This is only used if it ends up smaller than the tagged layout.
+                let mut niche_filling_layout = None;
+                if !def.repr.inhibit_enum_layout_opt() && no_explicit_discriminants {
+                    let variant_size = |fields: &[TyLayout]| {
+                        univariant_uninterned(fields, &def.repr, StructKind::AlwaysSized)
+                            .map(|st| st.size)
+                    };
+                    let mut largest = None;
+                    for (v, fields) in variants.iter().enumerate() {
+                        if absent(fields) {
+                            continue;
+                        }
+                        let size = variant_size(fields)?;
+                        if largest.map_or(true, |(_, largest_size)| size > largest_size) {
+                            largest = Some((v, size));
+                        }
+                    }
+
+                    let mut niche_variants = usize::max_value()..=0;
+                    if let Some((i, _)) = largest {
+                        for (v, fields) in variants.iter().enumerate() {
+                            if v != i && !absent(fields) {
+                                niche_variants = *niche_variants.start().min(&v)..=v;
+                            }
+                        }
+                    }
+
+                    if let (Some((i, _)), true) =
+                        (largest, niche_variants.start() <= niche_variants.end())
+                    {
+                        let count = (niche_variants.end() - niche_variants.start() + 1) as u128;
+                        for (field_index, &field) in variants[i].iter().enumerate() {
+                            let niche = match self.find_niche(field)? {
+                                Some(niche) => niche,
+                                _ => continue,
+                            };
+                            let (niche_start, niche_scalar) = match niche.reserve(self, count) {
+                                Some(pair) => pair,
+                                None => continue,
+                            };
+
+                            let mut align = dl.aggregate_align;
+                            let mut st = variants.iter().enumerate().map(|(j, v)| {
+                                let mut st = univariant_uninterned(v,
+                                    &def.repr, StructKind::AlwaysSized)?;
+                                st.variants = Variants::Single { index: j };
+
+                                align = align.max(st.align);
+
+                                Ok(st)
+                            }).collect::<Result<Vec<_>, _>>()?;
+
+                            let offset = st[i].fields.offset(field_index) + niche.offset;
+                            let niche_end = offset + niche_scalar.value.size(dl);
+                            let size = st[i].size.abi_align(align);
+
+                            let all_fit = st.iter_mut().enumerate().all(|(j, variant)| {
+                                if j == i || variant.size <= offset {
+                                    return true;
+                                }
+                                // Move the variant past the niche, if it still fits there.
+                                let shift = niche_end.abi_align(variant.align);
+                                if shift + variant.size > size {
+                                    return false;
+                                }
+                                match variant.fields {
+                                    FieldPlacement::Arbitrary { ref mut offsets, .. } => {
+                                        for field_offset in offsets {
+                                            *field_offset = shift + *field_offset;
+                                        }
+                                    }
+                                    _ => bug!(),
+                                }
+                                variant.size = shift + variant.size;
+                                // A scalar ABI would need the variant to start at offset 0.
+                                if variant.abi != Abi::Uninhabited {
+                                    variant.abi = Abi::Aggregate { sized: true };
+                                }
+                                true
+                            });
+                            if !all_fit {
+                                continue;
+                            }
+
+                            // Other variants may have data outside of the niche, so this is
+                            // never a scalar (pair).
+                            let abi = if st.iter().all(|v| v.abi == Abi::Uninhabited) {
+                                Abi::Uninhabited
+                            } else {
+                                Abi::Aggregate { sized: true }
+                            };
+
+                            niche_filling_layout = Some(LayoutDetails {
+                                variants: Variants::NicheFilling {
+                                    dataful_variant: i,
+                                    niche_variants,
+                                    niche: niche_scalar,
+                                    niche_start,
+                                    variants: st,
+                                },
+                                fields: FieldPlacement::Arbitrary {
+                                    offsets: vec![offset],
+                                    memory_index: vec![0]
+                                },
+                                abi,
+                                size,
+                                align,
+                            });
+                            break;
+                        }
+                    }
+                }
+
                 let (mut min, mut max) = (i128::max_value(), i128::min_value());
                 let discr_type = def.repr.discr_type();
                 let bits = Integer::from_attr(tcx, discr_type).size().bits();
@@ -1087,7 +1289,7 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
                     abi = Abi::Uninhabited;
                 }
 
-                tcx.intern_layout(LayoutDetails {
+                let tagged_layout = LayoutDetails {
                     variants: Variants::Tagged {
                         tag,
                         variants: layout_variants,
@@ -1099,7 +1301,14 @@ impl<'a, 'tcx> LayoutCx<'tcx, TyCtxt<'a, 'tcx, 'tcx>> {
                     abi,
                     align,
                     size
-                })
+                };
+
+                match niche_filling_layout {
+                    Some(niche_filling) if niche_filling.size < tagged_layout.size => {
+                        tcx.intern_layout(niche_filling)
+                    }
+                    _ => tcx.intern_layout(tagged_layout),
+                }
             }
 
             // Types with no meaningful known layout.
@@ -522,6 +522,7 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             Intrinsic(ref s) => Intrinsic(s.clone()),
             InvalidChar(c) => InvalidChar(c),
             StackFrameLimitReached => StackFrameLimitReached,
+            StepLimitReached => StepLimitReached,
             OutOfTls => OutOfTls,
             TlsOutOfBounds => TlsOutOfBounds,
             AbiViolation(ref s) => AbiViolation(s.clone()),
@@ -1152,7 +1152,8 @@ fn link_args(cmd: &mut dyn Linker,
         cmd.build_static_executable();
     }
 
-    if sess.opts.debugging_opts.pgo_gen.is_some() {
+    if sess.opts.debugging_opts.pgo_gen.is_some() ||
+       sess.opts.debugging_opts.instrument_coverage {
         cmd.pgo_gen();
     }
 
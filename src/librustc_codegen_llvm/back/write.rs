@@ -928,6 +928,11 @@ pub fn start_async_codegen(tcx: TyCtxt,
         modules_config.passes.push("insert-gcov-profiling".to_owned())
     }
 
+    if sess.opts.debugging_opts.instrument_coverage {
+        // Lowers the `llvm.instrprof.increment` calls of the coverage counters.
+        modules_config.passes.push("instrprof".to_owned())
+    }
+
     modules_config.pgo_gen = sess.opts.debugging_opts.pgo_gen.clone();
     modules_config.pgo_use = sess.opts.debugging_opts.pgo_use.clone();
 
@@ -13,6 +13,7 @@ use llvm;
 use rustc::dep_graph::DepGraphSafe;
 use rustc::hir;
 use rustc::hir::def_id::DefId;
+use coverageinfo;
 use debuginfo;
 use callee;
 use base;
@@ -95,6 +96,7 @@ pub struct CodegenCx<'a, 'tcx: 'a> {
     pub isize_ty: &'a Type,
 
     pub dbg_cx: Option<debuginfo::CrateDebugContext<'a, 'tcx>>,
+    pub coverage_cx: Option<coverageinfo::CrateCoverageContext<'a, 'tcx>>,
 
     eh_personality: Cell<Option<&'a Value>>,
     eh_unwind_resume: Cell<Option<&'a Value>>,
@@ -280,6 +282,12 @@ impl<'a, 'tcx> CodegenCx<'a, 'tcx> {
             None
         };
 
+        let coverage_cx = if tcx.sess.opts.debugging_opts.instrument_coverage {
+            Some(coverageinfo::CrateCoverageContext::new())
+        } else {
+            None
+        };
+
         let isize_ty = Type::ix_llcx(llcx, tcx.data_layout.pointer_size.bits());
 
         CodegenCx {
@@ -304,6 +312,7 @@ impl<'a, 'tcx> CodegenCx<'a, 'tcx> {
             pointee_infos: RefCell::new(FxHashMap()),
             isize_ty,
             dbg_cx,
+            coverage_cx,
             eh_personality: Cell::new(None),
             eh_unwind_resume: Cell::new(None),
             rust_try_fn: Cell::new(None),
@@ -770,6 +779,10 @@ fn declare_intrinsic(cx: &CodegenCx<'ll, '_>, key: &str) -> Option<&'ll Value> {
     ifn!("llvm.assume", fn(i1) -> void);
     ifn!("llvm.prefetch", fn(i8p, t_i32, t_i32, t_i32) -> void);
 
+    if cx.sess().opts.debugging_opts.instrument_coverage {
+        ifn!("llvm.instrprof.increment", fn(i8p, t_i64, t_i32, t_i32) -> void);
+    }
+
     if cx.sess().opts.debuginfo != NoDebugInfo {
         ifn!("llvm.dbg.declare", fn(Type::metadata(cx), Type::metadata(cx)) -> void);
         ifn!("llvm.dbg.value", fn(Type::metadata(cx), t_i64, Type::metadata(cx)) -> void);
@@ -0,0 +1,281 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Codegen for `-Z instrument-coverage`.
+//!
+//! The MIR pass `InstrumentCoverage` calls the `count_code_region` intrinsic at
+//! the start of every basic block. Each call becomes an `llvm.instrprof.increment`
+//! of the counter of that block, and its source region, the span of the counter
+//! index, is recorded here. Once the codegen unit is complete, `finalize` writes
+//! the regions of all its functions into the `__llvm_coverage_mapping` global,
+//! in the format LLVM's `llvm-cov` tool reads together with the counters of a
+//! profiled run.
+
+use llvm::{self, CounterMappingRegion};
+use builder::Builder;
+use common::{self, CodegenCx, C_bytes, C_struct, C_u32, C_u64};
+use consts;
+use monomorphize::Instance;
+use type_::Type;
+use value::Value;
+
+use rustc::mir;
+use rustc::ty::{self, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use syntax_pos::{FileName, Span};
+
+use libc::c_uint;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::hash::Hash;
+
+/// The coverage counters and regions of the functions in a codegen unit.
+pub struct CrateCoverageContext<'ll, 'tcx> {
+    function_coverage: RefCell<FxHashMap<Instance<'tcx>, FunctionCoverage>>,
+    pgo_func_name_vars: RefCell<FxHashMap<Instance<'tcx>, &'ll Value>>,
+}
+
+impl<'ll, 'tcx> CrateCoverageContext<'ll, 'tcx> {
+    pub fn new() -> Self {
+        CrateCoverageContext {
+            function_coverage: RefCell::new(FxHashMap()),
+            pgo_func_name_vars: RefCell::new(FxHashMap()),
+        }
+    }
+}
+
+struct FunctionCoverage {
+    source_hash: u64,
+    /// The counter index and the source region of each counter.
+    regions: Vec<(u32, Span)>,
+}
+
+/// Returns the source region of a counter, given the index argument of its
+/// call to `count_code_region`.
+pub fn counter_region(index: &mir::Operand) -> Span {
+    match *index {
+        mir::Operand::Constant(ref constant) => constant.span,
+        _ => bug!("count_code_region: the counter index is not a constant"),
+    }
+}
+
+/// Codegens a call to `count_code_region`. All arguments are constants
+/// inserted by `InstrumentCoverage`.
+///
+/// The MIR may come from a crate that was compiled with `-Z instrument-coverage`
+/// while this one is not, in which case the counters are left out.
+pub fn count_code_region(bx: &Builder<'a, 'll, 'tcx>,
+                         instance: Instance<'tcx>,
+                         mir: &mir::Mir<'tcx>,
+                         llfn: &'ll Value,
+                         args: &[&'ll Value],
+                         region: Span) {
+    let cx = bx.cx;
+    let coverage_cx = match cx.coverage_cx {
+        Some(ref coverage_cx) => coverage_cx,
+        None => return,
+    };
+
+    let arg = |i: usize| {
+        common::const_to_opt_u128(args[i], false)
+            .unwrap_or_else(|| bug!("count_code_region: argument {} is not a constant", i))
+    };
+    let num_counters = arg(0) as u32;
+    let index = arg(1) as u32;
+
+    let source_hash = {
+        let mut function_coverage = coverage_cx.function_coverage.borrow_mut();
+        let coverage = function_coverage.entry(instance).or_insert_with(|| {
+            FunctionCoverage {
+                source_hash: function_source_hash(cx.tcx, mir),
+                regions: vec![],
+            }
+        });
+        coverage.regions.push((index, region));
+        coverage.source_hash
+    };
+
+    let mut pgo_func_name_vars = coverage_cx.pgo_func_name_vars.borrow_mut();
+    let name_var = *pgo_func_name_vars.entry(instance).or_insert_with(|| {
+        let name = CString::new(&*cx.tcx.symbol_name(instance).as_str()).unwrap();
+        unsafe { llvm::LLVMRustCoverageCreatePGOFuncNameVar(llfn, name.as_ptr()) }
+    });
+
+    let llfn = cx.get_intrinsic("llvm.instrprof.increment");
+    bx.call(llfn, &[
+        consts::ptrcast(name_var, Type::i8p(cx)),
+        C_u64(cx, source_hash),
+        C_u32(cx, num_counters),
+        C_u32(cx, index),
+    ], None);
+}
+
+/// Writes the coverage map of the codegen unit.
+pub fn finalize(cx: &CodegenCx) {
+    let coverage_cx = match cx.coverage_cx {
+        Some(ref coverage_cx) => coverage_cx,
+        None => return,
+    };
+    let function_coverage = coverage_cx.function_coverage.borrow();
+    if function_coverage.is_empty() {
+        return;
+    }
+
+    // Sort the functions by their names for a deterministic coverage map.
+    let mut functions: Vec<_> = function_coverage.iter().map(|(&instance, coverage)| {
+        (cx.tcx.symbol_name(instance).as_str().to_string(), coverage)
+    }).collect();
+    functions.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let codemap = cx.sess().codemap();
+    let mut filenames: Vec<CString> = vec![];
+    let mut filename_indices = FxHashMap();
+    let mut function_records = vec![];
+    let mut mappings = vec![];
+
+    for (name, coverage) in functions {
+        // The regions refer to the global file table through a table of their own.
+        let mut virtual_file_mapping: Vec<c_uint> = vec![];
+        let mut regions = vec![];
+
+        for &(counter_id, region) in &coverage.regions {
+            let start = codemap.lookup_char_pos(region.lo());
+            let end = codemap.lookup_char_pos(region.hi());
+            if start.file.name != end.file.name {
+                continue;
+            }
+
+            let filename = match start.file.name {
+                FileName::Real(ref path) => {
+                    cx.sess().working_dir.0.join(path).to_string_lossy().into_owned()
+                }
+                ref other => other.to_string(),
+            };
+            let global_index = *filename_indices.entry(filename.clone()).or_insert_with(|| {
+                filenames.push(CString::new(filename).unwrap());
+                (filenames.len() - 1) as c_uint
+            });
+            let file_id = match virtual_file_mapping.iter().position(|&i| i == global_index) {
+                Some(file_id) => file_id,
+                None => {
+                    virtual_file_mapping.push(global_index);
+                    virtual_file_mapping.len() - 1
+                }
+            };
+
+            // LLVM's columns are 1-based.
+            regions.push(CounterMappingRegion {
+                counter_id,
+                file_id: file_id as u32,
+                start_line: start.line as u32,
+                start_col: start.col.0 as u32 + 1,
+                end_line: end.line as u32,
+                end_col: end.col.0 as u32 + 1,
+            });
+        }
+
+        // The regions of a file have to be consecutive.
+        regions.sort_by_key(|r| (r.file_id, r.start_line, r.start_col));
+
+        let mapping = llvm::build_byte_buffer(|s| unsafe {
+            llvm::LLVMRustCoverageWriteMappingToBuffer(virtual_file_mapping.as_ptr(),
+                                                       virtual_file_mapping.len() as c_uint,
+                                                       regions.as_ptr(),
+                                                       regions.len() as c_uint,
+                                                       s);
+        });
+
+        let name = CString::new(name).unwrap();
+        let name_ref = unsafe { llvm::LLVMRustCoverageComputeHash(name.as_ptr()) };
+        function_records.push(C_struct(cx, &[
+            C_u64(cx, name_ref),
+            C_u32(cx, mapping.len() as u32),
+            C_u64(cx, coverage.source_hash),
+        ], true));
+        mappings.extend(mapping);
+    }
+
+    let filename_ptrs: Vec<_> = filenames.iter().map(|f| f.as_ptr()).collect();
+    let mut filenames_and_mappings = llvm::build_byte_buffer(|s| unsafe {
+        llvm::LLVMRustCoverageWriteFilenamesSectionToBuffer(filename_ptrs.as_ptr(),
+                                                            filename_ptrs.len(),
+                                                            s);
+    });
+    let filenames_size = filenames_and_mappings.len();
+    filenames_and_mappings.extend(&mappings);
+
+    // The data that follows the function records is padded to 8 bytes.
+    while filenames_and_mappings.len() % 8 != 0 {
+        filenames_and_mappings.push(0);
+    }
+
+    let version = unsafe { llvm::LLVMRustCoverageMappingVersion() };
+    let header = C_struct(cx, &[
+        C_u32(cx, function_records.len() as u32),
+        C_u32(cx, filenames_size as u32),
+        C_u32(cx, mappings.len() as u32),
+        C_u32(cx, version),
+    ], false);
+    let record_ty = common::val_ty(function_records[0]);
+    let cov_data = C_struct(cx, &[
+        header,
+        common::C_array(record_ty, &function_records),
+        C_bytes(cx, &filenames_and_mappings),
+    ], false);
+
+    let var_name = llvm::build_string(|s| unsafe {
+        llvm::LLVMRustCoverageWriteMappingVarNameToString(s);
+    }).expect("coverage mapping variable name is not UTF-8");
+    let section = llvm::build_string(|s| unsafe {
+        llvm::LLVMRustCoverageWriteSectionNameToString(cx.llmod, s);
+    }).expect("coverage mapping section name is not UTF-8");
+    let section = CString::new(section).unwrap();
+
+    let llglobal = declare_coverage_mapping(cx, &var_name, common::val_ty(cov_data));
+    unsafe {
+        llvm::LLVMSetInitializer(llglobal, cov_data);
+        llvm::LLVMSetGlobalConstant(llglobal, llvm::True);
+        llvm::LLVMRustSetLinkage(llglobal, llvm::Linkage::InternalLinkage);
+        llvm::LLVMSetSection(llglobal, section.as_ptr());
+        llvm::LLVMSetAlignment(llglobal, 8);
+    }
+    cx.used_statics.borrow_mut().push(consts::ptrcast(llglobal, Type::i8p(cx)));
+}
+
+/// Hashes the regions of the counters in `mir`, relative to the start of the
+/// function. The hash changes whenever the regions do, so that profiles
+/// collected for an older version of the function are not applied to a newer
+/// one.
+fn function_source_hash(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &mir::Mir<'tcx>) -> u64 {
+    let count_code_region_fn = tcx.lang_items().count_code_region_fn();
+    let body_lo = mir.span.lo().0;
+    let mut hasher = StableHasher::<u64>::new();
+    for data in mir.basic_blocks() {
+        if let mir::TerminatorKind::Call { ref func, ref args, .. } = data.terminator().kind {
+            let is_counter = match func.ty(mir, tcx).sty {
+                ty::TyFnDef(def_id, _) => Some(def_id) == count_code_region_fn,
+                _ => false,
+            };
+            if is_counter {
+                let region = counter_region(&args[1]);
+                (region.lo().0.saturating_sub(body_lo), region.hi().0.saturating_sub(body_lo))
+                    .hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+fn declare_coverage_mapping(cx: &CodegenCx<'ll, '_>, name: &str, ty: &'ll Type) -> &'ll Value {
+    ::declare::define_global(cx, name, ty).unwrap_or_else(|| {
+        bug!("symbol `{}` is already defined", name)
+    })
+}
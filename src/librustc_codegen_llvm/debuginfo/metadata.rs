@@ -1252,6 +1252,9 @@ impl EnumMemberDescriptionFactory<'ll, 'tcx> {
                 // of discriminant instead of us having to recover its path.
                 // Right now it's not even going to work for `niche_start > 0`,
                 // and for multiple niche variants it only supports the first.
+                // FIXME: niche variants can have fields of their own, which
+                // aren't described at all. The debugger pretty printers expect
+                // exactly one union member, so they can't simply be added here.
                 fn compute_field_path<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
                                                 name: &mut String,
                                                 layout: TyLayout<'tcx>,
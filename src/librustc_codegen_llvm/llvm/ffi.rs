@@ -371,6 +371,18 @@ pub struct ThinLTOModule {
     pub len: usize,
 }
 
+/// LLVMRustCounterMappingRegion
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct CounterMappingRegion {
+    pub counter_id: u32,
+    pub file_id: u32,
+    pub start_line: u32,
+    pub start_col: u32,
+    pub end_line: u32,
+    pub end_col: u32,
+}
+
 /// LLVMThreadLocalMode
 #[derive(Copy, Clone)]
 #[repr(C)]
@@ -1632,3 +1644,22 @@ extern "C" {
                              bytecode_len: usize) -> bool;
     pub fn LLVMRustLinkerFree(linker: &'a mut Linker<'a>);
 }
+
+#[allow(improper_ctypes)] // FIXME(#52456) needed for RustString.
+extern "C" {
+    pub fn LLVMRustCoverageWriteFilenamesSectionToBuffer(Filenames: *const *const c_char,
+                                                         FilenamesLen: size_t,
+                                                         BufferOut: &RustString);
+    pub fn LLVMRustCoverageWriteMappingToBuffer(VirtualFileMappingIDs: *const c_uint,
+                                                NumVirtualFileMappingIDs: c_uint,
+                                                Regions: *const CounterMappingRegion,
+                                                NumRegions: c_uint,
+                                                BufferOut: &RustString);
+    pub fn LLVMRustCoverageCreatePGOFuncNameVar(F: &'a Value,
+                                                FuncName: *const c_char)
+                                                -> &'a Value;
+    pub fn LLVMRustCoverageComputeHash(Name: *const c_char) -> u64;
+    pub fn LLVMRustCoverageWriteSectionNameToString(M: &Module, Str: &RustString);
+    pub fn LLVMRustCoverageWriteMappingVarNameToString(Str: &RustString);
+    pub fn LLVMRustCoverageMappingVersion() -> u32;
+}
@@ -20,6 +20,7 @@ use callee;
 use builder::{Builder, MemFlags};
 use common::{self, C_bool, C_str_slice, C_struct, C_u32, C_uint_big, C_undef};
 use consts;
+use coverageinfo;
 use meth;
 use monomorphize;
 use type_of::LayoutLlvmExt;
@@ -462,6 +463,18 @@ impl FunctionCx<'a, 'll, 'tcx> {
                     return;
                 }
 
+                if intrinsic == Some("count_code_region") {
+                    let region = coverageinfo::counter_region(&args[1]);
+                    let args: Vec<_> = args.iter().map(|arg| {
+                        self.codegen_operand(&bx, arg).immediate()
+                    }).collect();
+                    coverageinfo::count_code_region(&bx, self.instance, self.mir, self.llfn,
+                                                    &args, region);
+                    let &(_, target) = destination.as_ref().unwrap();
+                    funclet_br(self, bx, target);
+                    return;
+                }
+
                 let extra_args = &args[sig.inputs().len()..];
                 let extra_args = extra_args.iter().map(|op_arg| {
                     let op_ty = op_arg.ty(self.mir, bx.tcx());
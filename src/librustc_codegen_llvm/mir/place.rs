@@ -170,8 +170,12 @@ impl PlaceRef<'ll, 'tcx> {
                 // Offsets have to match either first or second field.
                 assert_eq!(offset, a.value.size(cx).abi_align(b.value.align(cx)));
                 bx.struct_gep(self.llval, 1)
-            } else {
+            } else if self.layout.fields.memory_index(ix) < self.layout.llvm_field_count(cx) {
                 bx.struct_gep(self.llval, self.layout.llvm_field_index(ix))
+            } else {
+                // The field isn't part of the LLVM type, so offset the pointer by hand.
+                let llval = bx.pointercast(self.llval, Type::i8p(cx));
+                bx.inbounds_gep(llval, &[C_usize(cx, offset.bytes())])
             };
             PlaceRef {
                 // HACK(eddyb) have to bitcast pointers until LLVM removes pointee types.
@@ -349,8 +353,14 @@ impl PlaceRef<'ll, 'tcx> {
                 ..
             } => {
                 if variant_index != dataful_variant {
-                    if bx.sess().target.target.arch == "arm" ||
-                       bx.sess().target.target.arch == "aarch64" {
+                    // The memset is only done for variants without data. A niche variant
+                    // with fields has them written before its discriminant is set, so
+                    // zeroing the whole value here would throw them away. The miscompile
+                    // behind #34427 was seen with the niche store as the only write to an
+                    // otherwise uninitialized value, which is not the case here.
+                    let is_dataless = self.layout.for_variant(bx.cx, variant_index).is_zst();
+                    if is_dataless && (bx.sess().target.target.arch == "arm" ||
+                                       bx.sess().target.target.arch == "aarch64") {
                         // Issue #34427: As workaround for LLVM bug on ARM,
                         // use memset of 0 before assigning niche value.
                         let llptr = bx.pointercast(self.llval, Type::i8(bx.cx).ptr_to());
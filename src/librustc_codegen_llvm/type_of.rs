@@ -118,13 +118,13 @@ fn struct_llfields<'a, 'tcx>(cx: &CodegenCx<'a, 'tcx>,
                              layout: TyLayout<'tcx>)
                              -> (Vec<&'a Type>, bool) {
     debug!("struct_llfields: {:#?}", layout);
-    let field_count = layout.fields.count();
+    let field_count = layout.llvm_field_count(cx);
 
     let mut packed = false;
     let mut offset = Size::ZERO;
     let mut prev_align = layout.align;
     let mut result: Vec<_> = Vec::with_capacity(1 + field_count * 2);
-    for i in layout.fields.index_by_increasing_offset() {
+    for i in layout.fields.index_by_increasing_offset().take(field_count) {
         let field = layout.field(cx, i);
         packed |= layout.align.abi() < field.align.abi();
 
@@ -207,6 +207,7 @@ pub trait LayoutLlvmExt<'tcx> {
                                scalar: &layout::Scalar, offset: Size) -> &'a Type;
     fn scalar_pair_element_llvm_type<'a>(&self, cx: &CodegenCx<'a, 'tcx>,
                                          index: usize, immediate: bool) -> &'a Type;
+    fn llvm_field_count<'a>(&self, cx: &CodegenCx<'a, 'tcx>) -> usize;
     fn llvm_field_index(&self, index: usize) -> u64;
     fn pointee_info_at<'a>(&self, cx: &CodegenCx<'a, 'tcx>, offset: Size)
                            -> Option<PointeeInfo>;
@@ -379,6 +380,18 @@ impl<'tcx> LayoutLlvmExt<'tcx> for TyLayout<'tcx> {
         self.scalar_llvm_type_at(cx, scalar, offset)
     }
 
+    /// Returns the number of fields, in memory order, that are part of the LLVM struct type.
+    /// The locals a generator saves across suspension points may overlap each other, so they
+    /// are left out, and accessed by their offset instead.
+    fn llvm_field_count<'a>(&self, cx: &CodegenCx<'a, 'tcx>) -> usize {
+        match self.ty.sty {
+            ty::TyGenerator(def_id, ref substs, _) => {
+                substs.pre_transforms_tys(def_id, cx.tcx).count()
+            }
+            _ => self.fields.count(),
+        }
+    }
+
     fn llvm_field_index(&self, index: usize) -> u64 {
         match self.abi {
             layout::Abi::Scalar(_) |
@@ -0,0 +1,577 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Demangling of symbols produced by the `v0` mangling scheme.
+//!
+//! `demangle` checks that a symbol is well-formed, the returned value prints it like
+//! `crate[1a2b]::module::<Type<u8> as crate[1a2b]::Trait>::method`. The alternate form
+//! (`{:#}`) leaves out the crate disambiguators. The grammar is described in the `v0` module.
+
+use super::v0::{punycode_adapt, punycode_threshold};
+use super::v0::{PUNYCODE_BASE, PUNYCODE_INITIAL_BIAS, PUNYCODE_INITIAL_N};
+
+use std::char;
+use std::fmt;
+use std::mem;
+
+/// A demangled `v0` symbol, see `demangle`.
+pub struct Demangle<'a> {
+    /// The symbol without its `_R` prefix.
+    inner: &'a str,
+}
+
+/// The error returned for symbols that are not valid `v0` symbols.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Invalid;
+
+/// Paths and types can refer to each other, so bound the recursion to keep
+/// malicious symbols from overflowing the stack.
+const MAX_DEPTH: u32 = 500;
+
+/// Checks that `s` is a `v0` symbol and returns a value that demangles it when printed.
+pub fn demangle(s: &str) -> Result<Demangle, Invalid> {
+    // Some platforms prefix symbols with an extra underscore, others strip the
+    // leading one.
+    let inner = if s.starts_with("_R") {
+        &s[2..]
+    } else if s.starts_with("__R") {
+        &s[3..]
+    } else if s.starts_with("R") {
+        &s[1..]
+    } else {
+        return Err(Invalid);
+    };
+
+    // Only ASCII is allowed in symbols.
+    if !inner.is_ascii() {
+        return Err(Invalid);
+    }
+
+    let mut sink = String::new();
+    let mut printer = Printer {
+        parser: Parser { sym: inner, next: 0 },
+        out: &mut sink,
+        alternate: true,
+        skipping: true,
+        depth: 0,
+    };
+    printer.print_path(true)?;
+
+    // The instantiating crate, if any.
+    if printer.parser.next < inner.len() {
+        printer.print_path(false)?;
+    }
+    if printer.parser.next != inner.len() {
+        return Err(Invalid);
+    }
+
+    Ok(Demangle { inner })
+}
+
+impl<'a> fmt::Display for Demangle<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternate = f.alternate();
+        let mut printer = Printer {
+            parser: Parser { sym: self.inner, next: 0 },
+            out: f,
+            alternate,
+            skipping: false,
+            depth: 0,
+        };
+        printer.print_path(true).map_err(|_| fmt::Error)
+    }
+}
+
+struct Ident<'s> {
+    ascii: &'s str,
+    punycode: &'s str,
+}
+
+impl<'s> Ident<'s> {
+    fn is_empty(&self) -> bool {
+        self.ascii.is_empty() && self.punycode.is_empty()
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Parser<'s> {
+    sym: &'s str,
+    next: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn peek(&self) -> Option<u8> {
+        self.sym.as_bytes().get(self.next).cloned()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next(&mut self) -> Result<u8, Invalid> {
+        let b = self.peek().ok_or(Invalid)?;
+        self.next += 1;
+        Ok(b)
+    }
+
+    fn hex_nibbles(&mut self) -> Result<&'s str, Invalid> {
+        let start = self.next;
+        loop {
+            match self.next()? {
+                b'0'..=b'9' | b'a'..=b'f' => {}
+                b'_' => break,
+                _ => return Err(Invalid),
+            }
+        }
+        if self.next - 1 == start {
+            return Err(Invalid);
+        }
+        Ok(&self.sym[start..self.next - 1])
+    }
+
+    fn digit_10(&mut self) -> Result<u8, Invalid> {
+        let d = match self.peek() {
+            Some(d @ b'0'..=b'9') => d - b'0',
+            _ => return Err(Invalid),
+        };
+        self.next += 1;
+        Ok(d)
+    }
+
+    fn digit_62(&mut self) -> Result<u8, Invalid> {
+        let d = match self.peek() {
+            Some(d @ b'0'..=b'9') => d - b'0',
+            Some(d @ b'a'..=b'z') => 10 + (d - b'a'),
+            Some(d @ b'A'..=b'Z') => 10 + 26 + (d - b'A'),
+            _ => return Err(Invalid),
+        };
+        self.next += 1;
+        Ok(d)
+    }
+
+    fn integer_62(&mut self) -> Result<u64, Invalid> {
+        if self.eat(b'_') {
+            return Ok(0);
+        }
+
+        let mut x: u64 = 0;
+        while !self.eat(b'_') {
+            let d = self.digit_62()? as u64;
+            x = x.checked_mul(62).ok_or(Invalid)?;
+            x = x.checked_add(d).ok_or(Invalid)?;
+        }
+        x.checked_add(1).ok_or(Invalid)
+    }
+
+    fn disambiguator(&mut self) -> Result<u64, Invalid> {
+        if !self.eat(b's') {
+            return Ok(0);
+        }
+        self.integer_62()?.checked_add(1).ok_or(Invalid)
+    }
+
+    /// Returns the upper-case tag of a special namespace, or `None` for the
+    /// type and value namespaces, which are not shown.
+    fn namespace(&mut self) -> Result<Option<char>, Invalid> {
+        match self.next()? {
+            ns @ b'A'..=b'Z' => Ok(Some(ns as char)),
+            b'a'..=b'z' => Ok(None),
+            _ => Err(Invalid),
+        }
+    }
+
+    fn backref(&mut self) -> Result<Parser<'s>, Invalid> {
+        let s_start = self.next - 1;
+        let i = self.integer_62()?;
+        // Backrefs always point to something that was printed earlier.
+        if i >= s_start as u64 {
+            return Err(Invalid);
+        }
+        Ok(Parser { sym: self.sym, next: i as usize })
+    }
+
+    fn ident(&mut self) -> Result<Ident<'s>, Invalid> {
+        let is_punycode = self.eat(b'u');
+        let mut len = self.digit_10()? as usize;
+        if len != 0 {
+            while let Ok(d) = self.digit_10() {
+                len = len.checked_mul(10).ok_or(Invalid)?;
+                len = len.checked_add(d as usize).ok_or(Invalid)?;
+            }
+        }
+
+        // Skip the separator of identifiers starting with a digit or `_`.
+        self.eat(b'_');
+
+        let start = self.next;
+        self.next = self.next.checked_add(len).ok_or(Invalid)?;
+        if self.next > self.sym.len() {
+            return Err(Invalid);
+        }
+
+        let ident = &self.sym[start..self.next];
+        if is_punycode {
+            if ident.is_empty() {
+                return Err(Invalid);
+            }
+            Ok(Ident { ascii: "", punycode: ident })
+        } else {
+            Ok(Ident { ascii: ident, punycode: "" })
+        }
+    }
+}
+
+struct Printer<'a, 's> {
+    parser: Parser<'s>,
+    out: &'a mut (dyn fmt::Write + 'a),
+    alternate: bool,
+
+    /// Parse without printing, e.g. for the paths of impls, which are not shown.
+    skipping: bool,
+    depth: u32,
+}
+
+impl<'a, 's> Printer<'a, 's> {
+    fn print(&mut self, s: &str) -> Result<(), Invalid> {
+        if self.skipping {
+            return Ok(());
+        }
+        self.out.write_str(s).map_err(|_| Invalid)
+    }
+
+    fn print_fmt(&mut self, args: fmt::Arguments) -> Result<(), Invalid> {
+        if self.skipping {
+            return Ok(());
+        }
+        self.out.write_fmt(args).map_err(|_| Invalid)
+    }
+
+    fn enter(&mut self) -> Result<(), Invalid> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Invalid);
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Continues printing at the position of the backref just parsed.
+    fn print_backref<F>(&mut self, f: F) -> Result<(), Invalid>
+        where F: FnOnce(&mut Self) -> Result<(), Invalid>
+    {
+        let backref = self.parser.backref()?;
+        let orig = mem::replace(&mut self.parser, backref);
+        let result = f(self);
+        self.parser = orig;
+        result
+    }
+
+    fn print_ident(&mut self, ident: Ident) -> Result<(), Invalid> {
+        if !ident.punycode.is_empty() {
+            let decoded = punycode_decode(ident.punycode)?;
+            return self.print(&decoded);
+        }
+        self.print(ident.ascii)
+    }
+
+    /// Prints types until the closing `E`, separated by `sep`, and returns how many there were.
+    fn print_sep_list<F>(&mut self, f: F, sep: &str) -> Result<usize, Invalid>
+        where F: Fn(&mut Self) -> Result<(), Invalid>
+    {
+        let mut i = 0;
+        while !self.parser.eat(b'E') {
+            if i > 0 {
+                self.print(sep)?;
+            }
+            f(self)?;
+            i += 1;
+        }
+        Ok(i)
+    }
+
+    /// Prints a path. Generic arguments of paths in value position are written `::<..>`.
+    fn print_path(&mut self, in_value: bool) -> Result<(), Invalid> {
+        self.enter()?;
+        match self.parser.next()? {
+            b'C' => {
+                let dis = self.parser.disambiguator()?;
+                let name = self.parser.ident()?;
+                self.print_ident(name)?;
+                if !self.alternate && dis != 0 {
+                    self.print_fmt(format_args!("[{:x}]", dis))?;
+                }
+            }
+            b'N' => {
+                let ns = self.parser.namespace()?;
+                self.print_path(in_value)?;
+                let dis = self.parser.disambiguator()?;
+                let name = self.parser.ident()?;
+                match ns {
+                    Some(ns) => {
+                        self.print("::{")?;
+                        match ns {
+                            'C' => self.print("closure")?,
+                            _ => self.print_fmt(format_args!("{}", ns))?,
+                        }
+                        if !name.is_empty() {
+                            self.print(":")?;
+                            self.print_ident(name)?;
+                        }
+                        self.print_fmt(format_args!("#{}}}", dis))?;
+                    }
+                    None => {
+                        if !name.is_empty() {
+                            self.print("::")?;
+                            self.print_ident(name)?;
+                        }
+                    }
+                }
+            }
+            tag @ b'M' | tag @ b'X' | tag @ b'Y' => {
+                if tag != b'Y' {
+                    // The path the impl is defined in is not shown.
+                    self.parser.disambiguator()?;
+                    let skipping = mem::replace(&mut self.skipping, true);
+                    let result = self.print_path(false);
+                    self.skipping = skipping;
+                    result?;
+                }
+                self.print("<")?;
+                self.print_type()?;
+                if tag != b'M' {
+                    self.print(" as ")?;
+                    self.print_path(false)?;
+                }
+                self.print(">")?;
+            }
+            b'I' => {
+                self.print_path(in_value)?;
+                if in_value {
+                    self.print("::")?;
+                }
+                self.print("<")?;
+                self.print_sep_list(|this| this.print_type(), ", ")?;
+                self.print(">")?;
+            }
+            b'B' => self.print_backref(|this| this.print_path(in_value))?,
+            _ => return Err(Invalid),
+        }
+        self.leave();
+        Ok(())
+    }
+
+    /// Like `print_path`, but leaves the generic arguments open, for the
+    /// associated type bindings of `dyn Trait<Assoc = T>`. Returns whether
+    /// the arguments were opened.
+    fn print_path_maybe_open_generics(&mut self) -> Result<bool, Invalid> {
+        if self.parser.eat(b'B') {
+            let backref = self.parser.backref()?;
+            let orig = mem::replace(&mut self.parser, backref);
+            let result = self.print_path_maybe_open_generics();
+            self.parser = orig;
+            result
+        } else if self.parser.eat(b'I') {
+            self.enter()?;
+            self.print_path(false)?;
+            self.print("<")?;
+            self.print_sep_list(|this| this.print_type(), ", ")?;
+            self.leave();
+            Ok(true)
+        } else {
+            self.print_path(false)?;
+            Ok(false)
+        }
+    }
+
+    fn print_dyn_trait(&mut self) -> Result<(), Invalid> {
+        let mut open = self.print_path_maybe_open_generics()?;
+        while self.parser.eat(b'p') {
+            self.print(if open { ", " } else { "<" })?;
+            open = true;
+            let name = self.parser.ident()?;
+            self.print_ident(name)?;
+            self.print(" = ")?;
+            self.print_type()?;
+        }
+        if open {
+            self.print(">")?;
+        }
+        Ok(())
+    }
+
+    fn print_const(&mut self) -> Result<(), Invalid> {
+        match basic_type(self.parser.next()?) {
+            Some("i8") | Some("i16") | Some("i32") | Some("i64") | Some("i128") |
+            Some("isize") | Some("u8") | Some("u16") | Some("u32") | Some("u64") |
+            Some("u128") | Some("usize") => {}
+            _ => return Err(Invalid),
+        }
+        let hex = self.parser.hex_nibbles()?;
+        match u64::from_str_radix(hex, 16) {
+            Ok(x) => self.print_fmt(format_args!("{}", x)),
+            Err(_) => self.print_fmt(format_args!("0x{}", hex)),
+        }
+    }
+
+    fn print_type(&mut self) -> Result<(), Invalid> {
+        let tag = self.parser.next()?;
+        if let Some(ty) = basic_type(tag) {
+            return self.print(ty);
+        }
+
+        self.enter()?;
+        match tag {
+            b'R' | b'Q' => {
+                self.print(if tag == b'R' { "&" } else { "&mut " })?;
+                self.print_type()?;
+            }
+            b'P' | b'O' => {
+                self.print(if tag == b'P' { "*const " } else { "*mut " })?;
+                self.print_type()?;
+            }
+            b'A' | b'S' => {
+                self.print("[")?;
+                self.print_type()?;
+                if tag == b'A' {
+                    self.print("; ")?;
+                    self.print_const()?;
+                }
+                self.print("]")?;
+            }
+            b'T' => {
+                self.print("(")?;
+                let count = self.print_sep_list(|this| this.print_type(), ", ")?;
+                if count == 1 {
+                    self.print(",")?;
+                }
+                self.print(")")?;
+            }
+            b'F' => {
+                if self.parser.eat(b'U') {
+                    self.print("unsafe ")?;
+                }
+                if self.parser.eat(b'K') {
+                    self.print("extern \"")?;
+                    if self.parser.eat(b'C') {
+                        self.print("C")?;
+                    } else {
+                        let abi = self.parser.ident()?;
+                        if abi.ascii.is_empty() {
+                            return Err(Invalid);
+                        }
+                        for (i, part) in abi.ascii.split('_').enumerate() {
+                            if i > 0 {
+                                self.print("-")?;
+                            }
+                            self.print(part)?;
+                        }
+                    }
+                    self.print("\" ")?;
+                }
+                self.print("fn(")?;
+                self.print_sep_list(|this| this.print_type(), ", ")?;
+                self.print(")")?;
+                if !self.parser.eat(b'u') {
+                    self.print(" -> ")?;
+                    self.print_type()?;
+                }
+            }
+            b'D' => {
+                self.print("dyn ")?;
+                self.print_sep_list(|this| this.print_dyn_trait(), " + ")?;
+            }
+            b'B' => self.print_backref(|this| this.print_type())?,
+            _ => {
+                // Anything else has to be a path.
+                self.parser.next -= 1;
+                self.print_path(false)?;
+            }
+        }
+        self.leave();
+        Ok(())
+    }
+}
+
+fn basic_type(tag: u8) -> Option<&'static str> {
+    Some(match tag {
+        b'a' => "i8",
+        b'b' => "bool",
+        b'c' => "char",
+        b'd' => "f64",
+        b'e' => "str",
+        b'f' => "f32",
+        b'h' => "u8",
+        b'i' => "isize",
+        b'j' => "usize",
+        b'l' => "i32",
+        b'm' => "u32",
+        b'n' => "i128",
+        b'o' => "u128",
+        b's' => "i16",
+        b't' => "u16",
+        b'u' => "()",
+        b'v' => "...",
+        b'x' => "i64",
+        b'y' => "u64",
+        b'z' => "!",
+        b'p' => "_",
+        _ => return None,
+    })
+}
+
+/// Decodes a Punycode identifier as written by `v0::punycode_encode`.
+fn punycode_decode(input: &str) -> Result<String, Invalid> {
+    let (basic, extended) = match input.rfind('_') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input),
+    };
+    let mut output: Vec<char> = basic.chars().collect();
+
+    let mut n = PUNYCODE_INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = PUNYCODE_INITIAL_BIAS;
+    let mut digits = extended.bytes().peekable();
+    while digits.peek().is_some() {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = PUNYCODE_BASE;
+        loop {
+            let d = match digits.next() {
+                Some(d @ b'a'..=b'z') => (d - b'a') as u32,
+                Some(d @ b'0'..=b'9') => 26 + (d - b'0') as u32,
+                _ => return Err(Invalid),
+            };
+            i = d.checked_mul(w).and_then(|dw| i.checked_add(dw)).ok_or(Invalid)?;
+            let t = punycode_threshold(k, bias);
+            if d < t {
+                break;
+            }
+            w = w.checked_mul(PUNYCODE_BASE - t).ok_or(Invalid)?;
+            k += PUNYCODE_BASE;
+        }
+
+        let len = output.len() as u32 + 1;
+        bias = punycode_adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len).ok_or(Invalid)?;
+        i %= len;
+        output.insert(i as usize, char::from_u32(n).ok_or(Invalid)?);
+        i += 1;
+    }
+
+    Ok(output.into_iter().collect())
+}
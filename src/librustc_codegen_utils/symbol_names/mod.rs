@@ -96,12 +96,20 @@
 //! makes re-using previously compiled code for incremental compilation
 //! virtually impossible. Thus, symbol hash generation exclusively relies on
 //! DefPaths which are much more robust in the face of changes to the code base.
-
-use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+//!
+//! The `v0` mangling scheme
+//! ------------------------
+//! The scheme described above cannot be reversed: everything but the item path
+//! only shows up in the hash. `-Z symbol-mangling-version=v0` instead encodes
+//! the generic arguments, impls and crate disambiguators in the symbol itself,
+//! so that it can be demangled completely. See the `v0` and `demangle` modules.
+
+use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::hir::map as hir_map;
 use rustc::hir::map::definitions::DefPathData;
 use rustc::ich::NodeIdHashingMode;
 use rustc::middle::weak_lang_items;
+use rustc::session::config::SymbolManglingVersion;
 use rustc::ty::item_path::{self, ItemPathBuffer, RootMode};
 use rustc::ty::query::Providers;
 use rustc::ty::subst::Substs;
@@ -116,6 +124,9 @@ use syntax_pos::symbol::Symbol;
 
 use std::fmt::Write;
 
+pub mod demangle;
+mod v0;
+
 pub fn provide(providers: &mut Providers) {
     *providers = Providers {
         def_symbol_name,
@@ -125,6 +136,50 @@ pub fn provide(providers: &mut Providers) {
     };
 }
 
+/// Returns the crate that instantiates `instance`, if its symbol has to be distinguished from
+/// the symbols other crates use for the same instance.
+fn instantiating_crate<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+    instance: Instance<'tcx>,
+) -> Option<CrateNum> {
+    let substs = instance.substs;
+    let is_generic = substs.types().next().is_some();
+    let avoid_cross_crate_conflicts =
+        // If this is an instance of a generic function, we also hash in
+        // the ID of the instantiating crate. This avoids symbol conflicts
+        // in case the same instances is emitted in two crates of the same
+        // project.
+        is_generic ||
+
+        // If we're dealing with an instance of a function that's inlined from
+        // another crate but we're marking it as globally shared to our
+        // compliation (aka we're not making an internal copy in each of our
+        // codegen units) then this symbol may become an exported (but hidden
+        // visibility) symbol. This means that multiple crates may do the same
+        // and we want to be sure to avoid any symbol conflicts here.
+        match MonoItem::Fn(instance).instantiation_mode(tcx) {
+            InstantiationMode::GloballyShared { may_conflict: true } => true,
+            _ => false,
+        };
+
+    if !avoid_cross_crate_conflicts {
+        return None;
+    }
+
+    if is_generic && !def_id.is_local() && tcx.share_generics() {
+        // If we are re-using a monomorphization from another crate,
+        // we have to compute the symbol name accordingly.
+        let upstream_monomorphizations = tcx.upstream_monomorphizations_for(def_id);
+
+        Some(upstream_monomorphizations
+            .and_then(|monos| monos.get(&substs).cloned())
+            .unwrap_or(LOCAL_CRATE))
+    } else {
+        Some(LOCAL_CRATE)
+    }
+}
+
 fn get_symbol_hash<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
@@ -180,42 +235,7 @@ fn get_symbol_hash<'a, 'tcx>(
         assert!(!substs.needs_subst());
         substs.hash_stable(&mut hcx, &mut hasher);
 
-        let is_generic = substs.types().next().is_some();
-        let avoid_cross_crate_conflicts =
-            // If this is an instance of a generic function, we also hash in
-            // the ID of the instantiating crate. This avoids symbol conflicts
-            // in case the same instances is emitted in two crates of the same
-            // project.
-            is_generic ||
-
-            // If we're dealing with an instance of a function that's inlined from
-            // another crate but we're marking it as globally shared to our
-            // compliation (aka we're not making an internal copy in each of our
-            // codegen units) then this symbol may become an exported (but hidden
-            // visibility) symbol. This means that multiple crates may do the same
-            // and we want to be sure to avoid any symbol conflicts here.
-            match MonoItem::Fn(instance).instantiation_mode(tcx) {
-                InstantiationMode::GloballyShared { may_conflict: true } => true,
-                _ => false,
-            };
-
-        if avoid_cross_crate_conflicts {
-            let instantiating_crate = if is_generic {
-                if !def_id.is_local() && tcx.share_generics() {
-                    // If we are re-using a monomorphization from another crate,
-                    // we have to compute the symbol hash accordingly.
-                    let upstream_monomorphizations = tcx.upstream_monomorphizations_for(def_id);
-
-                    upstream_monomorphizations
-                        .and_then(|monos| monos.get(&substs).cloned())
-                        .unwrap_or(LOCAL_CRATE)
-                } else {
-                    LOCAL_CRATE
-                }
-            } else {
-                LOCAL_CRATE
-            };
-
+        if let Some(instantiating_crate) = instantiating_crate(tcx, def_id, instance) {
             (&tcx.original_crate_name(instantiating_crate).as_str()[..])
                 .hash_stable(&mut hcx, &mut hasher);
             (&tcx.crate_disambiguator(instantiating_crate)).hash_stable(&mut hcx, &mut hasher);
@@ -292,6 +312,10 @@ fn compute_symbol_name<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: Instance
         return tcx.item_name(def_id).to_string();
     }
 
+    if tcx.sess.opts.debugging_opts.symbol_mangling_version == SymbolManglingVersion::V0 {
+        return v0::mangle(tcx, instance, instantiating_crate(tcx, def_id, instance));
+    }
+
     // We want to compute the "type" of this item. Unfortunately, some
     // kinds of items (e.g., closures) don't have an entry in the
     // item-type array. So walk back up the find the closest parent
@@ -0,0 +1,495 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `v0` symbol mangling scheme, enabled with `-Z symbol-mangling-version=v0`.
+//!
+//! Instead of hiding everything but the item path in a hash, these symbols spell out the full
+//! path of the instance: the crate disambiguators, the self types and traits of impls, and the
+//! generic arguments of every path segment. Everything that is encoded can be demangled again
+//! (see the `demangle` module). The grammar is:
+//!
+//! ```text
+//! symbol         = "_R" path [path]                 // instance, instantiating crate
+//! path           = "C" [disambiguator] ident        // crate root
+//!                | "N" namespace path [disambiguator] ident
+//!                | "M" [disambiguator] path type    // <T>, inherent impl
+//!                | "X" [disambiguator] path type path  // <T as Trait>, trait impl
+//!                | "Y" type path                    // <T as Trait>, trait item
+//!                | "I" path {type} "E"              // generic arguments
+//!                | backref
+//! namespace      = "C"                              // closures and generators
+//!                | "t" | "v"                        // type and value namespaces
+//!                | "c"                              // unnamed items such as constructors
+//! type           = basic-type
+//!                | path                             // ADTs, fn items, closures
+//!                | "A" type const                   // [T; N]
+//!                | "S" type                         // [T]
+//!                | "T" {type} "E"                   // (T, U)
+//!                | "R" type | "Q" type              // &T, &mut T
+//!                | "P" type | "O" type              // *const T, *mut T
+//!                | "F" ["U"] ["K" abi] {type} "E" type  // fn pointers, "v" for `...`
+//!                | "D" {path {"p" ident type}} "E"  // dyn Trait<Assoc = T> + Auto
+//!                | backref
+//! const          = basic-type {hex-digit} "_"
+//! abi            = "C" | ident                      // `-` is written as `_`
+//! disambiguator  = "s" base-62-number
+//! ident          = ["u"] decimal-number ["_"] bytes // "u": punycode, `-` written as `_`
+//! backref        = "B" base-62-number               // position after the "_R" prefix
+//! base-62-number = {0-9 a-z A-Z} "_"                // "_" is 0, "0_" is 1, ...
+//! ```
+//!
+//! The basic types are written as single lower-case letters, see `basic_type`. Lifetimes are
+//! erased before mangling and are not encoded.
+
+use rustc::hir;
+use rustc::hir::def_id::{CrateNum, DefId, CRATE_DEF_INDEX};
+use rustc::hir::map::definitions::DefPathData;
+use rustc::ty::subst::{Subst, Substs, UnpackedKind};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::base_n;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_mir::monomorphize::Instance;
+use rustc_target::spec::abi::Abi;
+use syntax::ast::{FloatTy, IntTy, UintTy};
+
+use std::fmt::Write;
+
+pub fn mangle<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    instance: Instance<'tcx>,
+    instantiating_crate: Option<CrateNum>,
+) -> String {
+    let def_id = instance.def_id();
+    // Lifetimes are not part of the symbol.
+    let substs = tcx.erase_regions(&instance.substs);
+
+    let mut cx = SymbolMangler {
+        tcx,
+        out: String::from("_R"),
+        paths: FxHashMap(),
+        types: FxHashMap(),
+    };
+    cx.print_def_path(def_id, substs);
+    if let Some(krate) = instantiating_crate {
+        cx.print_def_path(DefId { krate, index: CRATE_DEF_INDEX }, tcx.intern_substs(&[]));
+    }
+    cx.out
+}
+
+struct SymbolMangler<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    out: String,
+
+    /// The positions of paths and types that were already printed, for backrefs.
+    paths: FxHashMap<(DefId, &'tcx Substs<'tcx>), usize>,
+    types: FxHashMap<Ty<'tcx>, usize>,
+}
+
+impl<'a, 'tcx> SymbolMangler<'a, 'tcx> {
+    fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn push_integer_62(&mut self, x: u64) {
+        if let Some(x) = x.checked_sub(1) {
+            base_n::push_str(x as u128, base_n::ALPHANUMERIC_ONLY, &mut self.out);
+        }
+        self.push("_");
+    }
+
+    fn push_disambiguator(&mut self, dis: u64) {
+        if let Some(dis) = dis.checked_sub(1) {
+            self.push("s");
+            self.push_integer_62(dis);
+        }
+    }
+
+    fn push_ident(&mut self, ident: &str) {
+        let punycode;
+        let ident = if ident.is_ascii() {
+            ident
+        } else {
+            punycode = punycode_encode(ident);
+            self.push("u");
+            &punycode
+        };
+
+        let _ = write!(self.out, "{}", ident.len());
+
+        // Separate the length from identifiers that start with a digit, or
+        // with the separator itself.
+        if let Some('_') | Some('0'..='9') = ident.chars().next() {
+            self.push("_");
+        }
+
+        self.push(ident);
+    }
+
+    fn print_backref(&mut self, i: usize) {
+        self.push("B");
+        // Positions are relative to the start of the path, after the `_R` prefix.
+        self.push_integer_62((i - 2) as u64);
+    }
+
+    fn print_def_path(&mut self, def_id: DefId, substs: &'tcx Substs<'tcx>) {
+        if let Some(&i) = self.paths.get(&(def_id, substs)) {
+            return self.print_backref(i);
+        }
+        let start = self.out.len();
+
+        let key = self.tcx.def_key(def_id);
+        match key.disambiguated_data.data {
+            DefPathData::CrateRoot => self.print_crate_root(def_id.krate),
+            DefPathData::Impl => self.print_impl_path(def_id, substs),
+            data => {
+                let parent_def_id = DefId { index: key.parent.unwrap(), ..def_id };
+                let (parent_substs, own_types) = self.split_substs(def_id, substs);
+
+                if !own_types.is_empty() {
+                    self.push("I");
+                }
+
+                self.push("N");
+                let ns = match data {
+                    DefPathData::ClosureExpr => "C",
+
+                    DefPathData::TypeNs(_) |
+                    DefPathData::Trait(_) |
+                    DefPathData::AssocTypeInTrait(_) |
+                    DefPathData::AssocTypeInImpl(_) |
+                    DefPathData::AssocExistentialInImpl(_) |
+                    DefPathData::Module(_) |
+                    DefPathData::MacroDef(_) |
+                    DefPathData::TypeParam(_) |
+                    DefPathData::EnumVariant(_) |
+                    DefPathData::GlobalMetaData(_) => "t",
+
+                    DefPathData::ValueNs(_) |
+                    DefPathData::LifetimeParam(_) |
+                    DefPathData::Field(_) => "v",
+
+                    DefPathData::StructCtor |
+                    DefPathData::Misc |
+                    DefPathData::AnonConst |
+                    DefPathData::ImplTrait => "c",
+
+                    DefPathData::CrateRoot |
+                    DefPathData::Impl => unreachable!(),
+                };
+                self.push(ns);
+
+                self.print_parent_path(parent_def_id, parent_substs);
+                self.push_disambiguator(key.disambiguated_data.disambiguator as u64);
+                let name = data.get_opt_name().map(|name| name.as_str());
+                self.push_ident(name.as_ref().map_or("", |name| &name[..]));
+
+                if !own_types.is_empty() {
+                    for ty in own_types {
+                        self.print_type(ty);
+                    }
+                    self.push("E");
+                }
+            }
+        }
+
+        self.paths.insert((def_id, substs), start);
+    }
+
+    /// Splits `substs` into the substs of the parent of `def_id` and the types `def_id`
+    /// introduces itself. The latter exclude lifetimes, the `Self` type of traits and the
+    /// synthetic types of closures and generators.
+    fn split_substs(
+        &self,
+        def_id: DefId,
+        substs: &'tcx Substs<'tcx>,
+    ) -> (&'tcx Substs<'tcx>, Vec<Ty<'tcx>>) {
+        if substs.is_empty() {
+            return (substs, vec![]);
+        }
+
+        let generics = self.tcx.generics_of(def_id);
+        let parent_substs = self.tcx.intern_substs(&substs[..generics.parent_count]);
+        let own_types = match self.tcx.def_key(def_id).disambiguated_data.data {
+            DefPathData::ClosureExpr => vec![],
+            _ => {
+                let own_start = generics.parent_count + generics.has_self as usize;
+                substs[own_start..generics.count()].iter().filter_map(|k| match k.unpack() {
+                    UnpackedKind::Type(ty) => Some(ty),
+                    UnpackedKind::Lifetime(_) => None,
+                }).collect()
+            }
+        };
+        (parent_substs, own_types)
+    }
+
+    fn print_parent_path(&mut self, def_id: DefId, substs: &'tcx Substs<'tcx>) {
+        // Items of traits are qualified with `<Self as Trait>`, if we know `Self`.
+        if let DefPathData::Trait(_) = self.tcx.def_key(def_id).disambiguated_data.data {
+            if !substs.is_empty() {
+                self.push("Y");
+                self.print_type(substs.type_at(0));
+            }
+        }
+        self.print_def_path(def_id, substs);
+    }
+
+    fn print_crate_root(&mut self, krate: CrateNum) {
+        self.push("C");
+        let fingerprint = self.tcx.crate_disambiguator(krate).to_fingerprint();
+        self.push_disambiguator(fingerprint.to_smaller_hash());
+        let name = self.tcx.original_crate_name(krate).as_str();
+        self.push_ident(&name);
+    }
+
+    fn print_impl_path(&mut self, impl_def_id: DefId, substs: &'tcx Substs<'tcx>) {
+        let tcx = self.tcx;
+        let key = tcx.def_key(impl_def_id);
+        let parent_def_id = DefId { index: key.parent.unwrap(), ..impl_def_id };
+
+        // Items nested in the impl's items, like a `fn` inside a method, are printed without
+        // the substs of the impl. Its parameters, and the projections on them, are then
+        // printed as placeholders, as there is nothing to normalize them with.
+        let (self_ty, trait_ref) = if substs.is_empty() {
+            let self_ty = tcx.erase_regions(&tcx.type_of(impl_def_id));
+            let trait_ref = tcx.impl_trait_ref(impl_def_id).map(|trait_ref| {
+                tcx.erase_regions(&trait_ref)
+            });
+            (self_ty, trait_ref)
+        } else {
+            let impl_substs = tcx.intern_substs(&substs[..tcx.generics_of(impl_def_id).count()]);
+            let param_env = ty::ParamEnv::reveal_all();
+            let self_ty = tcx.type_of(impl_def_id).subst(tcx, impl_substs);
+            let self_ty = tcx.normalize_erasing_regions(param_env, self_ty);
+            let trait_ref = tcx.impl_trait_ref(impl_def_id).map(|trait_ref| {
+                tcx.normalize_erasing_regions(param_env, trait_ref.subst(tcx, impl_substs))
+            });
+            (self_ty, trait_ref)
+        };
+
+        self.push(if trait_ref.is_some() { "X" } else { "M" });
+        self.push_disambiguator(key.disambiguated_data.disambiguator as u64);
+        self.print_def_path(parent_def_id, tcx.intern_substs(&[]));
+        self.print_type(self_ty);
+        if let Some(trait_ref) = trait_ref {
+            self.print_def_path(trait_ref.def_id, trait_ref.substs);
+        }
+    }
+
+    fn print_type(&mut self, ty: Ty<'tcx>) {
+        if let Some(basic) = basic_type(ty) {
+            return self.push(basic);
+        }
+
+        if let Some(&i) = self.types.get(&ty) {
+            return self.print_backref(i);
+        }
+        let start = self.out.len();
+
+        match ty.sty {
+            ty::TyRef(_, ty, mutbl) => {
+                self.push(if mutbl == hir::MutMutable { "Q" } else { "R" });
+                self.print_type(ty);
+            }
+            ty::TyRawPtr(mt) => {
+                self.push(if mt.mutbl == hir::MutMutable { "O" } else { "P" });
+                self.print_type(mt.ty);
+            }
+            ty::TyArray(ty, len) => {
+                self.push("A");
+                self.print_type(ty);
+                let len = len.unwrap_usize(self.tcx);
+                let _ = write!(self.out, "j{:x}_", len);
+            }
+            ty::TySlice(ty) => {
+                self.push("S");
+                self.print_type(ty);
+            }
+            ty::TyTuple(tys) => {
+                self.push("T");
+                for &ty in tys {
+                    self.print_type(ty);
+                }
+                self.push("E");
+            }
+            ty::TyAdt(def, substs) => self.print_def_path(def.did, substs),
+            ty::TyForeign(def_id) => self.print_def_path(def_id, self.tcx.intern_substs(&[])),
+            ty::TyFnDef(def_id, substs) |
+            ty::TyClosure(def_id, ty::ClosureSubsts { substs }) |
+            ty::TyGenerator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.print_def_path(def_id, substs);
+            }
+            ty::TyFnPtr(sig) => {
+                let sig = sig.skip_binder();
+                self.push("F");
+                if sig.unsafety == hir::Unsafety::Unsafe {
+                    self.push("U");
+                }
+                match sig.abi {
+                    Abi::Rust => {}
+                    Abi::C => self.push("KC"),
+                    abi => {
+                        self.push("K");
+                        self.push_ident(&abi.name().replace('-', "_"));
+                    }
+                }
+                for &ty in sig.inputs() {
+                    self.print_type(ty);
+                }
+                if sig.variadic {
+                    self.push("v");
+                }
+                self.push("E");
+                self.print_type(sig.output());
+            }
+            ty::TyDynamic(predicates, _) => {
+                let predicates = predicates.skip_binder();
+                self.push("D");
+                if let Some(principal) = predicates.principal() {
+                    // The existential trait ref has no `Self`, so print its generic
+                    // arguments here rather than through `print_def_path`.
+                    let types: Vec<_> = principal.substs.types().collect();
+                    if !types.is_empty() {
+                        self.push("I");
+                    }
+                    self.print_def_path(principal.def_id, self.tcx.intern_substs(&[]));
+                    if !types.is_empty() {
+                        for ty in types {
+                            self.print_type(ty);
+                        }
+                        self.push("E");
+                    }
+                    for projection in predicates.projection_bounds() {
+                        let name = self.tcx.associated_item(projection.item_def_id).name;
+                        self.push("p");
+                        self.push_ident(&name.as_str());
+                        self.print_type(projection.ty);
+                    }
+                }
+                for def_id in predicates.auto_traits() {
+                    self.print_def_path(def_id, self.tcx.intern_substs(&[]));
+                }
+                self.push("E");
+            }
+            _ => bug!("symbol_names: unexpected type `{}`", ty),
+        }
+
+        self.types.insert(ty, start);
+    }
+}
+
+fn basic_type(ty: Ty) -> Option<&'static str> {
+    Some(match ty.sty {
+        ty::TyBool => "b",
+        ty::TyChar => "c",
+        ty::TyStr => "e",
+        ty::TyTuple(tys) if tys.is_empty() => "u",
+        ty::TyInt(IntTy::I8) => "a",
+        ty::TyInt(IntTy::I16) => "s",
+        ty::TyInt(IntTy::I32) => "l",
+        ty::TyInt(IntTy::I64) => "x",
+        ty::TyInt(IntTy::I128) => "n",
+        ty::TyInt(IntTy::Isize) => "i",
+        ty::TyUint(UintTy::U8) => "h",
+        ty::TyUint(UintTy::U16) => "t",
+        ty::TyUint(UintTy::U32) => "m",
+        ty::TyUint(UintTy::U64) => "y",
+        ty::TyUint(UintTy::U128) => "o",
+        ty::TyUint(UintTy::Usize) => "j",
+        ty::TyFloat(FloatTy::F32) => "f",
+        ty::TyFloat(FloatTy::F64) => "d",
+        ty::TyNever => "z",
+        // Generic parameters and projections on them only show up in the paths
+        // of items that are not instantiated, e.g. impls referred to without
+        // substs.
+        ty::TyParam(_) | ty::TyProjection(_) => "p",
+        _ => return None,
+    })
+}
+
+// Punycode parameters, see RFC 3492.
+pub(super) const PUNYCODE_BASE: u32 = 36;
+pub(super) const PUNYCODE_TMIN: u32 = 1;
+pub(super) const PUNYCODE_TMAX: u32 = 26;
+pub(super) const PUNYCODE_INITIAL_BIAS: u32 = 72;
+pub(super) const PUNYCODE_INITIAL_N: u32 = 128;
+
+pub(super) fn punycode_adapt(mut delta: u32, num_points: u32, first: bool) -> u32 {
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+
+    delta /= if first { DAMP } else { 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((PUNYCODE_BASE - PUNYCODE_TMIN) * PUNYCODE_TMAX) / 2 {
+        delta /= PUNYCODE_BASE - PUNYCODE_TMIN;
+        k += PUNYCODE_BASE;
+    }
+    k + (((PUNYCODE_BASE - PUNYCODE_TMIN + 1) * delta) / (delta + SKEW))
+}
+
+pub(super) fn punycode_threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        PUNYCODE_TMIN
+    } else if k >= bias + PUNYCODE_TMAX {
+        PUNYCODE_TMAX
+    } else {
+        k - bias
+    }
+}
+
+/// Encodes `input` with Punycode, using `_` instead of `-` to delimit the basic code points,
+/// so that the result can be used in a symbol.
+pub(super) fn punycode_encode(input: &str) -> String {
+    let chars: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: String = input.chars().filter(|c| c.is_ascii()).collect();
+    let basic_len = output.len() as u32;
+    if basic_len > 0 {
+        output.push('_');
+    }
+
+    let encode_digit = |d: u32| {
+        (if d < 26 { b'a' + d as u8 } else { b'0' + (d - 26) as u8 }) as char
+    };
+
+    let mut n = PUNYCODE_INITIAL_N;
+    let mut delta = 0;
+    let mut bias = PUNYCODE_INITIAL_BIAS;
+    let mut handled = basic_len;
+    while (handled as usize) < chars.len() {
+        let m = *chars.iter().filter(|&&c| c >= n).min().unwrap();
+        delta += (m - n) * (handled + 1);
+        n = m;
+        for &c in &chars {
+            if c < n {
+                delta += 1;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = PUNYCODE_BASE;
+                loop {
+                    let t = punycode_threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (PUNYCODE_BASE - t)));
+                    q = (q - t) / (PUNYCODE_BASE - t);
+                    k += PUNYCODE_BASE;
+                }
+                output.push(encode_digit(q));
+                bias = punycode_adapt(delta, handled + 1, handled == basic_len);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    output
+}
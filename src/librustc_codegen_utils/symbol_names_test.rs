@@ -19,6 +19,7 @@ use rustc::ty::TyCtxt;
 use syntax::ast;
 
 use rustc_mir::monomorphize::Instance;
+use symbol_names::demangle;
 
 const SYMBOL_NAME: &'static str = "rustc_symbol_name";
 const ITEM_PATH: &'static str = "rustc_item_path";
@@ -52,6 +53,10 @@ impl<'a, 'tcx> SymbolNamesTest<'a, 'tcx> {
                 let instance = Instance::mono(tcx, def_id);
                 let name = self.tcx.symbol_name(instance);
                 tcx.sess.span_err(attr.span, &format!("symbol-name({})", name));
+                if let Ok(demangling) = demangle::demangle(&name.as_str()) {
+                    tcx.sess.span_err(attr.span, &format!("demangling({})", demangling));
+                    tcx.sess.span_err(attr.span, &format!("demangling-alt({:#})", demangling));
+                }
             } else if attr.check_name(ITEM_PATH) {
                 let path = tcx.item_path_str(def_id);
                 tcx.sess.span_err(attr.span, &format!("item-path({})", path));
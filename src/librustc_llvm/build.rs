@@ -114,7 +114,8 @@ fn main() {
                                 "mcjit",
                                 "lto",
                                 "interpreter",
-                                "instrumentation"];
+                                "instrumentation",
+                                "coverage"];
 
     let components = output(Command::new(&llvm_config).arg("--components"));
     let mut components = components.split_whitespace().collect::<Vec<_>>();
@@ -166,6 +167,7 @@ fn main() {
        .file("../rustllvm/RustWrapper.cpp")
        .file("../rustllvm/ArchiveWrapper.cpp")
        .file("../rustllvm/Linker.cpp")
+       .file("../rustllvm/CoverageMappingWrapper.cpp")
        .cpp(true)
        .cpp_link_stdlib(None) // we handle this below
        .compile("rustllvm");
@@ -5,6 +5,7 @@ use std::mem;
 use rustc::hir::def_id::DefId;
 use rustc::hir::def::Def;
 use rustc::hir::map::definitions::DefPathData;
+use rustc::middle::recursion_limit::DEFAULT_CONST_EVAL_LIMIT;
 use rustc::mir;
 use rustc::ty::layout::{self, Size, Align, HasDataLayout, IntegerExt, LayoutOf, TyLayout, Primitive};
 use rustc::ty::subst::{Subst, Substs};
@@ -68,6 +69,13 @@ pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     /// The maximum number of stack frames allowed
     pub(crate) stack_limit: usize,
 
+    /// The maximum number of steps allowed, or 0 if there is no limit
+    pub(crate) step_limit: usize,
+
+    /// The number of steps taken so far. Other constants read along the way are evaluated by
+    /// queries of their own, each counting its steps against a separate budget.
+    pub(crate) steps: usize,
+
     /// When this value is negative, it indicates the number of interpreter
     /// steps *until* the loop detector is enabled. When it is positive, it is
     /// the number of steps after the detector has been enabled modulo the loop
@@ -335,6 +343,10 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             memory: Memory::new(tcx, memory_data),
             stack: Vec::new(),
             stack_limit: tcx.sess.const_eval_stack_frame_limit,
+            // The limit is only set once the crate attributes have been read.
+            step_limit: tcx.sess.const_eval_limit.try_get().cloned()
+                .unwrap_or(DEFAULT_CONST_EVAL_LIMIT),
+            steps: 0,
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
         }
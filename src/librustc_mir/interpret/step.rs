@@ -13,6 +13,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         /// Should be a power of two for performance reasons.
         const DETECTOR_SNAPSHOT_PERIOD: isize = 256;
 
+        self.steps += 1;
+        if self.step_limit != 0 && self.steps > self.step_limit {
+            return err!(StepLimitReached);
+        }
+
         {
             let steps = &mut self.steps_since_detector_enabled;
 
@@ -161,6 +161,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | Unimplemented(_)
                     // don't report const evaluator limits
                     | StackFrameLimitReached
+                    | StepLimitReached
                     | NoMirFor(..)
                     | InlineAsm
                     => {},
@@ -0,0 +1,417 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Destination propagation.
+//!
+//! This pass looks for assignments of one local to another:
+//!
+//!     DEST = move SRC
+//!
+//! and merges the two locals into one, so that whatever computed `SRC` writes into `DEST`
+//! directly and the copy disappears. When `DEST` is the return place `_0`, this is the "named
+//! return value optimization": a large value is built in place rather than in a temporary that is
+//! copied out on return.
+//!
+//! Two locals may only be merged if they don't *conflict*, which is the case when neither of them
+//! is written (other than by the copy itself) at a point where the other one is live, and when
+//! one isn't the destination of a call, aggregate or inline assembly that reads the other: those
+//! may start writing their destination before all of their operands have been read. Locals whose
+//! address is taken are never merged, as the borrow could observe the shared storage, and neither
+//! are arguments, whose storage belongs to the caller.
+//!
+//! Unlike `CopyPropagation`, this places no restriction on the number of defs and uses of either
+//! local, at the cost of a liveness computation per round of merges.
+
+use rustc::mir::{BasicBlock, Local, LocalKind, Location, Mir, Operand, Place, ProjectionElem};
+use rustc::mir::{Rvalue, Statement, StatementKind, TerminatorKind, RETURN_PLACE};
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_data_structures::work_queue::WorkQueue;
+use transform::{MirPass, MirSource};
+use util::liveness::{categorize, DefUse, LivenessMode};
+
+pub struct DestinationPropagation;
+
+impl MirPass for DestinationPropagation {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        // Like `CopyPropagation`, only run when the MIR optimization level is > 1, as merging
+        // user variables messes up debug info.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let ineligible = ineligible_locals(mir);
+
+        // Every round merges at least one local, so this terminates.
+        while merge_round(mir, &ineligible) {}
+    }
+}
+
+/// A `to = move from` (or `to = copy from`) assignment at `location`, whose locals we would like
+/// to merge by replacing `from` with `to` everywhere.
+#[derive(Debug)]
+struct Candidate {
+    location: Location,
+    from: Local,
+    to: Local,
+}
+
+/// Finds the candidates, checks them for conflicts and merges the ones it can. Returns `true`
+/// if anything was merged.
+fn merge_round<'tcx>(mir: &mut Mir<'tcx>, ineligible: &IdxSetBuf<Local>) -> bool {
+    let candidates = find_candidates(mir, ineligible);
+    if candidates.is_empty() {
+        return false;
+    }
+
+    let conflicts = find_conflicts(mir, &candidates);
+
+    // Each local takes part in at most one merge per round: the conflicts were computed for the
+    // locals as they are now, not for the result of an earlier merge.
+    let mut touched = IdxSetBuf::new_empty(mir.local_decls.len());
+    let mut renames: IndexVec<Local, Local> = mir.local_decls.indices().collect();
+    let mut merged = Vec::new();
+    for (candidate, &conflict) in candidates.iter().zip(&conflicts) {
+        if conflict || touched.contains(&candidate.from) || touched.contains(&candidate.to) {
+            continue;
+        }
+        debug!("DestinationPropagation: merging {:?}", candidate);
+        touched.add(&candidate.from);
+        touched.add(&candidate.to);
+        renames[candidate.from] = candidate.to;
+        merged.push(candidate.location);
+    }
+
+    if merged.is_empty() {
+        return false;
+    }
+
+    LocalRenamer { renames }.visit_mir(mir);
+
+    // The copies are now self-assignments, and the storage markers of the merged locals no
+    // longer describe a single live range.
+    for location in merged {
+        mir.make_statement_nop(location);
+    }
+    for block in mir.basic_blocks_mut() {
+        for statement in &mut block.statements {
+            match statement.kind {
+                StatementKind::StorageLive(local) |
+                StatementKind::StorageDead(local) if touched.contains(&local) => {
+                    statement.make_nop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    true
+}
+
+fn find_candidates<'tcx>(mir: &Mir<'tcx>, ineligible: &IdxSetBuf<Local>) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            let (dest, src) = match statement.kind {
+                StatementKind::Assign(
+                    Place::Local(dest),
+                    Rvalue::Use(Operand::Copy(Place::Local(src))),
+                ) |
+                StatementKind::Assign(
+                    Place::Local(dest),
+                    Rvalue::Use(Operand::Move(Place::Local(src))),
+                ) => (dest, src),
+                _ => continue,
+            };
+            if dest == src || ineligible.contains(&dest) || ineligible.contains(&src) {
+                continue;
+            }
+            if mir.local_decls[dest].ty != mir.local_decls[src].ty {
+                continue;
+            }
+
+            // Keep the return place, and otherwise prefer getting rid of temporaries, so that
+            // user variables keep their debug info where possible.
+            let (from, to) = match (mir.local_kind(dest), mir.local_kind(src)) {
+                (LocalKind::ReturnPointer, _) => (src, dest),
+                (_, LocalKind::ReturnPointer) => (dest, src),
+                (_, LocalKind::Temp) => (src, dest),
+                (LocalKind::Temp, _) => (dest, src),
+                _ => (src, dest),
+            };
+            candidates.push(Candidate {
+                location: Location { block, statement_index },
+                from,
+                to,
+            });
+        }
+    }
+    candidates
+}
+
+/// Returns, for each candidate, whether its locals conflict.
+fn find_conflicts<'tcx>(mir: &Mir<'tcx>, candidates: &[Candidate]) -> Vec<bool> {
+    let num_locals = mir.local_decls.len();
+
+    let mut candidates_by_local: IndexVec<Local, Vec<usize>> =
+        IndexVec::from_elem_n(Vec::new(), num_locals);
+    for (index, candidate) in candidates.iter().enumerate() {
+        candidates_by_local[candidate.from].push(index);
+        candidates_by_local[candidate.to].push(index);
+    }
+
+    let accesses: IndexVec<BasicBlock, Vec<Accesses>> = mir.basic_blocks()
+        .iter_enumerated()
+        .map(|(block, data)| {
+            (0..data.statements.len() + 1).map(|statement_index| {
+                let mut accesses = Accesses::default();
+                accesses.visit_location(mir, Location { block, statement_index });
+                accesses
+            }).collect()
+        })
+        .collect();
+    let outs = live_locals_on_exit(mir, &accesses);
+
+    let mut conflicts = vec![false; candidates.len()];
+    let mut live = IdxSetBuf::new_empty(num_locals);
+    for (block, block_accesses) in accesses.iter_enumerated() {
+        live.overwrite(&outs[block]);
+        for (statement_index, access) in block_accesses.iter().enumerate().rev() {
+            // `live` holds the locals live right after this statement.
+            let location = Location { block, statement_index };
+            for &written in &access.writes {
+                for &index in &candidates_by_local[written] {
+                    let candidate = &candidates[index];
+                    if candidate.location == location {
+                        continue;
+                    }
+                    let other = if written == candidate.from {
+                        candidate.to
+                    } else {
+                        candidate.from
+                    };
+                    if live.contains(&other) {
+                        debug!("DestinationPropagation: {:?} conflicts at {:?}",
+                               candidate, location);
+                        conflicts[index] = true;
+                    }
+                }
+            }
+            for &dest in &access.dests {
+                for &index in &candidates_by_local[dest] {
+                    let candidate = &candidates[index];
+                    let other = if dest == candidate.from {
+                        candidate.to
+                    } else {
+                        candidate.from
+                    };
+                    if access.operands.contains(&other) {
+                        debug!("DestinationPropagation: {:?} would overlap operands at {:?}",
+                               candidate, location);
+                        conflicts[index] = true;
+                    }
+                }
+            }
+            access.apply(&mut live);
+        }
+    }
+    conflicts
+}
+
+/// Computes the locals live on exit from each basic block.
+///
+/// This differs from `util::liveness` in that the return place is live at `return`, which is
+/// what makes it unsafe to write to a local merged into `_0` after its final value is set.
+fn live_locals_on_exit<'tcx>(mir: &Mir<'tcx>, accesses: &IndexVec<BasicBlock, Vec<Accesses>>)
+                             -> IndexVec<BasicBlock, IdxSetBuf<Local>> {
+    let num_locals = mir.local_decls.len();
+    let mut outs: IndexVec<BasicBlock, _> = mir.basic_blocks()
+        .indices()
+        .map(|_| IdxSetBuf::new_empty(num_locals))
+        .collect();
+
+    let mut bits = IdxSetBuf::new_empty(num_locals);
+    let mut dirty_queue: WorkQueue<BasicBlock> = WorkQueue::with_all(mir.basic_blocks().len());
+    let predecessors = mir.predecessors();
+    while let Some(block) = dirty_queue.pop() {
+        bits.overwrite(&outs[block]);
+        for access in accesses[block].iter().rev() {
+            access.apply(&mut bits);
+        }
+        for &predecessor in &predecessors[block] {
+            if outs[predecessor].union(&bits) {
+                dirty_queue.insert(predecessor);
+            }
+        }
+    }
+    outs
+}
+
+/// The locals accessed by a single statement or terminator.
+#[derive(Default)]
+struct Accesses {
+    /// Locals whose value (or part of it) is changed.
+    writes: Vec<Local>,
+    /// Locals that are live before, for the purposes of liveness.
+    uses: Vec<Local>,
+    /// Locals that are dead before, for the purposes of liveness.
+    defs: Vec<Local>,
+    /// Locals in the destination of a call, aggregate or inline assembly, which may be written
+    /// before all of `operands` have been read.
+    dests: Vec<Local>,
+    /// Locals in the operands of that call, aggregate or inline assembly.
+    operands: Vec<Local>,
+}
+
+impl Accesses {
+    /// Turns the locals live after the statement into the locals live before it.
+    fn apply(&self, live: &mut IdxSetBuf<Local>) {
+        for local in &self.defs {
+            live.remove(local);
+        }
+        for local in &self.uses {
+            live.add(local);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for Accesses {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        match statement.kind {
+            StatementKind::Assign(ref place, Rvalue::Aggregate(_, ref operands)) => {
+                let mut dests = CollectLocals { locals: &mut self.dests };
+                dests.visit_place(place, PlaceContext::Store, location);
+                let mut collector = CollectLocals { locals: &mut self.operands };
+                for operand in operands {
+                    collector.visit_operand(operand, location);
+                }
+            }
+            StatementKind::InlineAsm { ref outputs, ref inputs, .. } => {
+                let mut dests = CollectLocals { locals: &mut self.dests };
+                for output in outputs {
+                    dests.visit_place(output, PlaceContext::AsmOutput, location);
+                }
+                let mut collector = CollectLocals { locals: &mut self.operands };
+                for input in inputs {
+                    collector.visit_operand(input, location);
+                }
+            }
+            _ => {}
+        }
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: BasicBlock,
+                             kind: &TerminatorKind<'tcx>,
+                             location: Location) {
+        match *kind {
+            TerminatorKind::Return => self.uses.push(RETURN_PLACE),
+            TerminatorKind::Call { ref func, ref args, destination: Some((ref dest, _)), .. } => {
+                let mut dests = CollectLocals { locals: &mut self.dests };
+                dests.visit_place(dest, PlaceContext::Call, location);
+                let mut collector = CollectLocals { locals: &mut self.operands };
+                collector.visit_operand(func, location);
+                for arg in args {
+                    collector.visit_operand(arg, location);
+                }
+            }
+            _ => {}
+        }
+        self.super_terminator_kind(block, kind, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if context.is_mutating_use() {
+            self.writes.push(local);
+        }
+        let mode = LivenessMode { include_regular_use: true, include_drops: true };
+        match categorize(context, mode) {
+            Some(DefUse::Def) => self.defs.push(local),
+            Some(DefUse::Use) => self.uses.push(local),
+            None => {}
+        }
+    }
+}
+
+/// Collects every local mentioned in the places and operands it visits.
+struct CollectLocals<'a> {
+    locals: &'a mut Vec<Local>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for CollectLocals<'a> {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        self.locals.push(local);
+    }
+}
+
+/// Finds the locals that must never be merged: arguments, locals that are borrowed, and locals
+/// used as outputs of inline assembly.
+fn ineligible_locals<'tcx>(mir: &Mir<'tcx>) -> IdxSetBuf<Local> {
+    let mut ineligible = IdxSetBuf::new_empty(mir.local_decls.len());
+    for arg in mir.args_iter() {
+        ineligible.add(&arg);
+    }
+    IneligibleLocals { ineligible: &mut ineligible }.visit_mir(mir);
+    ineligible
+}
+
+struct IneligibleLocals<'a> {
+    ineligible: &'a mut IdxSetBuf<Local>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for IneligibleLocals<'a> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if let PlaceContext::Borrow { .. } = context {
+            // Borrowing through a pointer doesn't borrow the pointer itself.
+            let mut place = place;
+            loop {
+                match *place {
+                    Place::Local(local) => {
+                        self.ineligible.add(&local);
+                        break;
+                    }
+                    Place::Projection(ref proj) if proj.elem != ProjectionElem::Deref => {
+                        place = &proj.base;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        if let PlaceContext::AsmOutput = context {
+            self.ineligible.add(&local);
+        }
+    }
+}
+
+struct LocalRenamer {
+    renames: IndexVec<Local, Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for LocalRenamer {
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext<'tcx>, _: Location) {
+        *local = self.renames[*local];
+    }
+}
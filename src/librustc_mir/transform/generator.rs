@@ -35,6 +35,11 @@
 //!         mir_locals...,
 //!     }
 //!
+//! The MIR locals are accessed through the generator struct for as long as their storage is
+//! live, not just across suspension points. Two of them whose storage is never live at the same
+//! time can share space in the struct, and the layout of the generator type overlaps them. The
+//! pairs which can't be overlapped are recorded in the `GeneratorLayout`.
+//!
 //! This pass computes the meaning of the state field and the MIR locals which are live
 //! across a suspension point. There are however two hardcoded generator states:
 //!     0 - Generator have not been resumed yet
@@ -76,7 +81,7 @@ use std::mem;
 use transform::{MirPass, MirSource};
 use transform::simplify;
 use transform::no_landing_pads::no_landing_pads;
-use dataflow::{do_dataflow, DebugFormatted, state_for_location};
+use dataflow::{do_dataflow, DataflowResults, DebugFormatted, state_for_location};
 use dataflow::{MaybeStorageLive, HaveBeenBorrowedLocals};
 
 pub struct StateTransform;
@@ -368,7 +373,8 @@ fn locals_live_across_suspend_points<'a, 'tcx,>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                                source: MirSource,
                                                movable: bool) ->
                                                (liveness::LiveVarSet<Local>,
-                                                HashMap<BasicBlock, liveness::LiveVarSet<Local>>) {
+                                                HashMap<BasicBlock, liveness::LiveVarSet<Local>>,
+                                                Vec<(Local, Local)>) {
     let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
     let node_id = tcx.hir.as_local_node_id(source.def_id).unwrap();
 
@@ -471,7 +477,67 @@ fn locals_live_across_suspend_points<'a, 'tcx,>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     // The generator argument is ignored
     set.remove(&self_arg());
 
-    (set, storage_liveness_map)
+    let storage_conflicts = compute_storage_conflicts(mir, &set, &ignored, &storage_live);
+
+    (set, storage_liveness_map, storage_conflicts)
+}
+
+/// Finds the pairs of saved locals whose storage may be live at the same time, at any point in
+/// the generator. The other pairs can share space in the generator struct.
+fn compute_storage_conflicts<'a, 'tcx>(
+    mir: &'a Mir<'tcx>,
+    saved_locals: &liveness::LiveVarSet<Local>,
+    ignored: &StorageIgnored,
+    storage_live: &DataflowResults<MaybeStorageLive<'a, 'tcx>>,
+) -> Vec<(Local, Local)> {
+    let num_locals = mir.local_decls.len();
+    let mut conflicts: HashMap<Local, liveness::LiveVarSet<Local>> = saved_locals.iter()
+        .map(|local| (local, IdxSetBuf::new_empty(num_locals)))
+        .collect();
+
+    {
+        // Every saved local with live storage conflicts with all the others.
+        let mut record = |live: &liveness::LiveVarSet<Local>| {
+            let mut live_saved = live.clone();
+            live_saved.intersect(saved_locals);
+            for local in live_saved.iter() {
+                conflicts.get_mut(&local).unwrap().union(&live_saved);
+            }
+        };
+
+        // The set of locals with live storage only grows at `StorageLive`, so that and the start of
+        // each block are the only places to look at.
+        let mut live = IdxSetBuf::new_empty(num_locals);
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            live.overwrite(storage_live.sets().on_entry_set_for(block.index()));
+            // Locals without storage statements always have live storage.
+            live.union(&ignored.0);
+            record(&live);
+
+            for statement in &data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(l) => {
+                        live.add(&l);
+                        record(&live);
+                    }
+                    StatementKind::StorageDead(l) => {
+                        live.remove(&l);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for local in saved_locals.iter() {
+        for other in conflicts[&local].iter() {
+            if local < other {
+                pairs.push((local, other));
+            }
+        }
+    }
+    pairs
 }
 
 fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -485,10 +551,8 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         HashMap<BasicBlock, liveness::LiveVarSet<Local>>)
 {
     // Use a liveness analysis to compute locals which are live across a suspension point
-    let (live_locals, storage_liveness) = locals_live_across_suspend_points(tcx,
-                                                                            mir,
-                                                                            source,
-                                                                            movable);
+    let (live_locals, storage_liveness, storage_conflicts) =
+        locals_live_across_suspend_points(tcx, mir, source, movable);
     // Erase regions from the types passed in from typeck so we can compare them with
     // MIR types
     let allowed_upvars = tcx.erase_regions(&upvars);
@@ -531,8 +595,18 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         ((local, (var.ty, upvar_len + 1 + idx)), var)
     }).unzip();
 
+    // Refer to the locals by their index in `vars`.
+    let saved_index: HashMap<Local, usize> = live_locals.iter()
+        .enumerate()
+        .map(|(idx, local)| (local, idx))
+        .collect();
+    let storage_conflicts = storage_conflicts.into_iter()
+        .map(|(a, b)| (saved_index[&a], saved_index[&b]))
+        .collect();
+
     let layout = GeneratorLayout {
-        fields: vars
+        fields: vars,
+        storage_conflicts,
     };
 
     (remap, layout, storage_liveness)
@@ -53,6 +53,11 @@ impl MirPass for Inline {
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>) {
+        // The optimized MIR of an instrumented callee calls its own coverage
+        // counters, which codegen would attribute to the caller.
+        if tcx.sess.opts.debugging_opts.instrument_coverage {
+            return;
+        }
         if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
             Inliner { tcx, source }.run_pass(mir);
         }
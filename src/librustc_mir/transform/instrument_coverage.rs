@@ -0,0 +1,153 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Inserts a coverage counter at the start of every basic block, for
+//! `-Z instrument-coverage`.
+//!
+//! Each counter is a call to the `count_code_region` intrinsic, which is given
+//! the number of counters and the index of the counter. The source region the
+//! block was built from is the span of the index, so that it is translated like
+//! any other span when the MIR is loaded by another crate. Codegen turns these
+//! calls into `llvm.instrprof.increment` and collects the regions into the
+//! coverage map of the crate.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::{self, Slice, TyCtxt};
+use syntax::codemap::original_sp;
+use syntax_pos::{BytePos, Span};
+use transform::{MirPass, MirSource};
+
+pub struct InstrumentCoverage;
+
+impl MirPass for InstrumentCoverage {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          src: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.instrument_coverage {
+            return;
+        }
+
+        // Promoted constants and the bodies of constants and statics are
+        // evaluated at compile time, so there is nothing to count. `const fn`s
+        // are instrumented for their calls at run time; the const evaluator
+        // ignores their counters.
+        if src.promoted.is_some() {
+            return;
+        }
+        let id = tcx.hir.as_local_node_id(src.def_id).unwrap();
+        if tcx.hir.body_owner_kind(id) != hir::BodyOwnerKind::Fn {
+            return;
+        }
+
+        // `#![no_core]` crates may not define the intrinsic.
+        let count_code_region_fn = match tcx.lang_items().count_code_region_fn() {
+            Some(def_id) => def_id,
+            None => return,
+        };
+
+        instrument(tcx, count_code_region_fn, mir);
+    }
+}
+
+fn instrument<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                        count_code_region_fn: DefId,
+                        mir: &mut Mir<'tcx>) {
+    let body_span = mir.span;
+
+    // Cleanup blocks only run while unwinding; they get no counters.
+    let blocks: Vec<_> = mir.basic_blocks().iter_enumerated()
+        .filter(|(_, data)| !data.is_cleanup)
+        .map(|(bb, data)| (bb, block_region(data, body_span)))
+        .collect();
+    if blocks.is_empty() {
+        return;
+    }
+
+    let num_counters = blocks.len() as u32;
+    let temp = mir.local_decls.push(LocalDecl::new_temp(tcx.mk_nil(), body_span));
+
+    for (index, (bb, region)) in blocks.into_iter().enumerate() {
+        // Move the contents of the block into a new one and make the block call
+        // the counter before jumping there, so that all the predecessors of the
+        // block still reach it through the counter.
+        let (source_info, counted) = {
+            let data = &mut mir.basic_blocks_mut()[bb];
+            (data.terminator().source_info, BasicBlockData {
+                statements: data.statements.drain(..).collect(),
+                terminator: data.terminator.take(),
+                is_cleanup: false,
+            })
+        };
+        let target = mir.basic_blocks_mut().push(counted);
+
+        let span = source_info.span;
+        let constant = |span: Span, value: u32| {
+            let ty = tcx.types.u32;
+            Operand::Constant(box Constant {
+                span,
+                ty,
+                literal: ty::Const::from_bits(tcx, value as u128,
+                                              ty::ParamEnv::empty().and(ty)),
+            })
+        };
+        let args = vec![
+            constant(span, num_counters),
+            constant(region, index as u32),
+        ];
+
+        mir.basic_blocks_mut()[bb].terminator = Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Call {
+                func: Operand::function_handle(tcx, count_code_region_fn, Slice::empty(), span),
+                args,
+                destination: Some((Place::Local(temp), target)),
+                cleanup: None,
+            },
+        });
+    }
+}
+
+/// Returns the source region covered by a block: everything its statements
+/// were built from, with macro expansions mapped back to the function body.
+///
+/// The terminator only counts when there are no statements. Gotos and
+/// switches of loops and `if`s carry the span of the whole expression, and
+/// `llvm-cov` adds up the counts of regions with the same span.
+fn block_region(data: &BasicBlockData, body_span: Span) -> Span {
+    let statement_spans: Vec<_> = data.statements.iter()
+        .filter(|statement| match statement.kind {
+            // These span the whole scope of the local, not the code of the block.
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => false,
+            _ => true,
+        })
+        .map(|statement| statement.source_info.span)
+        .collect();
+    let spans = if statement_spans.is_empty() {
+        vec![data.terminator().source_info.span]
+    } else {
+        statement_spans
+    };
+
+    let mut region: Option<(BytePos, BytePos)> = None;
+    for span in spans.into_iter()
+        .map(|span| original_sp(span, body_span))
+        .filter(|span| body_span.contains(*span))
+    {
+        region = Some(match region {
+            Some((lo, hi)) => (lo.min(span.lo()), hi.max(span.hi())),
+            None => (span.lo(), span.hi()),
+        });
+    }
+    let (lo, hi) = region.unwrap_or((body_span.lo(), body_span.lo()));
+    body_span.with_lo(lo).with_hi(hi)
+}
@@ -0,0 +1,219 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Jump threading over known enum discriminants.
+//!
+//! Matching on an enum that was just built, as happens all the time with `?` once `into_result`
+//! and `from_error` have been inlined, produces MIR like:
+//!
+//!     bb1: {
+//!         _3 = std::result::Result<u32, E>::Ok(move _4,);
+//!         goto -> bb2;
+//!     }
+//!     bb2: {
+//!         _5 = discriminant(_3);
+//!         switchInt(move _5) -> [0isize: bb3, 1isize: bb4, otherwise: bb5];
+//!     }
+//!
+//! `SimplifyBranches` can't do anything here as the switch isn't on a constant. This pass looks
+//! back from the switch for the statement that set the discriminant. If it is in the same block,
+//! the switch is replaced by a `goto` to the right target. If it is in a predecessor that jumps
+//! straight to the switch, the switching block is duplicated for that predecessor, with a `goto`
+//! in place of the switch, so that the edge is threaded past the test. Blocks with more than
+//! `MAX_DUPLICATED_STATEMENTS` statements are not duplicated.
+//!
+//! Only writes to locals are looked through: any statement that could write to memory, and so
+//! to the enum through a pointer, stops the search.
+
+use rustc::mir::{AggregateKind, BasicBlock, Local, Mir, Operand, Place, Rvalue};
+use rustc::mir::{Statement, StatementKind, TerminatorKind};
+use rustc::ty::{self, AdtDef, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use transform::{MirPass, MirSource};
+
+/// The largest block that is duplicated to thread an edge past its switch.
+const MAX_DUPLICATED_STATEMENTS: usize = 8;
+
+pub struct JumpThreading;
+
+impl MirPass for JumpThreading {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level == 0 {
+            return;
+        }
+
+        // Switches whose block sets the discriminant itself, and the target to jump to instead.
+        let mut folds = Vec::new();
+        // Edges `pred -> block` that can skip the switch in `block` and jump to a known target.
+        let mut threads = Vec::new();
+
+        {
+            let predecessors = mir.predecessors();
+            for (block, data) in mir.basic_blocks().iter_enumerated() {
+                let (discr, values, targets) = match data.terminator().kind {
+                    TerminatorKind::SwitchInt {
+                        discr: Operand::Copy(Place::Local(discr)), ref values, ref targets, ..
+                    } |
+                    TerminatorKind::SwitchInt {
+                        discr: Operand::Move(Place::Local(discr)), ref values, ref targets, ..
+                    } => (discr, values, targets),
+                    _ => continue,
+                };
+                let (index, local) = match find_discriminant_read(&data.statements, discr) {
+                    Some(read) => read,
+                    None => continue,
+                };
+
+                match scan_back(mir, &data.statements[..index], local) {
+                    Scan::Known(adt_def, variant_index) => {
+                        let target = switch_target(tcx, adt_def, variant_index, values, targets);
+                        debug!("JumpThreading: folding the switch in {:?} to {:?}", block, target);
+                        folds.push((block, target));
+                    }
+                    Scan::Forwarded(_) if data.statements.len() > MAX_DUPLICATED_STATEMENTS => {
+                        debug!("JumpThreading: {:?} is too large to duplicate", block);
+                    }
+                    Scan::Forwarded(local) => {
+                        for &pred in &predecessors[block] {
+                            if pred == block {
+                                continue;
+                            }
+                            let pred_data = &mir[pred];
+                            match pred_data.terminator().kind {
+                                TerminatorKind::Goto { target } if target == block => {}
+                                _ => continue,
+                            }
+                            if let Scan::Known(adt_def, variant_index) =
+                                scan_back(mir, &pred_data.statements, local)
+                            {
+                                let target =
+                                    switch_target(tcx, adt_def, variant_index, values, targets);
+                                debug!("JumpThreading: threading {:?} -> {:?} to {:?}",
+                                       pred, block, target);
+                                threads.push((pred, block, target));
+                            }
+                        }
+                    }
+                    Scan::Unknown => {}
+                }
+            }
+        }
+
+        for (block, target) in folds {
+            mir[block].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+
+        // Predecessors of the same block that go to the same target share the copy of the block.
+        let mut copies = FxHashMap();
+        for (pred, block, target) in threads {
+            let copy = *copies.entry((block, target)).or_insert_with(|| {
+                let mut data = mir[block].clone();
+                data.terminator_mut().kind = TerminatorKind::Goto { target };
+                mir.basic_blocks_mut().push(data)
+            });
+            mir[pred].terminator_mut().kind = TerminatorKind::Goto { target: copy };
+        }
+    }
+}
+
+/// What the statements leading up to a point say about the discriminant of a local there.
+enum Scan<'tcx> {
+    /// The local was last set to the given variant of the enum.
+    Known(&'tcx AdtDef, usize),
+    /// The statements don't change the discriminant, which is that of the given local before
+    /// them. This is the original local, or one it was copied from.
+    Forwarded(Local),
+    /// The discriminant can't be determined.
+    Unknown,
+}
+
+/// Walks `statements` backwards, looking for the last write to `local`.
+fn scan_back<'tcx>(mir: &Mir<'tcx>, statements: &[Statement<'tcx>], mut local: Local)
+                   -> Scan<'tcx> {
+    for statement in statements.iter().rev() {
+        match statement.kind {
+            StatementKind::SetDiscriminant { place: Place::Local(l), variant_index }
+                if l == local =>
+            {
+                return match mir.local_decls[local].ty.sty {
+                    ty::TyAdt(adt_def, _) if adt_def.is_enum() => {
+                        Scan::Known(adt_def, variant_index)
+                    }
+                    _ => Scan::Unknown,
+                };
+            }
+            StatementKind::Assign(Place::Local(l), ref rvalue) if l == local => {
+                match *rvalue {
+                    Rvalue::Aggregate(box AggregateKind::Adt(adt_def, variant_index, ..), _)
+                        if adt_def.is_enum() =>
+                    {
+                        return Scan::Known(adt_def, variant_index);
+                    }
+                    Rvalue::Use(Operand::Copy(Place::Local(src))) |
+                    Rvalue::Use(Operand::Move(Place::Local(src))) => {
+                        local = src;
+                    }
+                    _ => return Scan::Unknown,
+                }
+            }
+            _ if writes_only_locals_other_than(statement, local) => {}
+            _ => return Scan::Unknown,
+        }
+    }
+    Scan::Forwarded(local)
+}
+
+/// Finds the `discr = discriminant(local)` statement that the value switched on comes from,
+/// returning its index and `local`.
+fn find_discriminant_read(statements: &[Statement], discr: Local) -> Option<(usize, Local)> {
+    for (index, statement) in statements.iter().enumerate().rev() {
+        match statement.kind {
+            StatementKind::Assign(Place::Local(l), Rvalue::Discriminant(Place::Local(local)))
+                if l == discr =>
+            {
+                return Some((index, local));
+            }
+            _ if writes_only_locals_other_than(statement, discr) => {}
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Returns `true` if the statement can't change the value of `local`, even through a pointer.
+fn writes_only_locals_other_than(statement: &Statement, local: Local) -> bool {
+    match statement.kind {
+        StatementKind::Assign(Place::Local(l), _) |
+        StatementKind::SetDiscriminant { place: Place::Local(l), .. } => l != local,
+        StatementKind::ReadForMatch(_) |
+        StatementKind::StorageLive(_) |
+        StatementKind::StorageDead(_) |
+        StatementKind::EndRegion(_) |
+        StatementKind::Nop => true,
+        _ => false,
+    }
+}
+
+fn switch_target<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           adt_def: &'tcx AdtDef,
+                           variant_index: usize,
+                           values: &[u128],
+                           targets: &[BasicBlock])
+                           -> BasicBlock {
+    let discr = adt_def.discriminant_for_variant(tcx, variant_index).val;
+    let (otherwise, targets) = targets.split_last().unwrap();
+    values.iter()
+        .zip(targets)
+        .find(|&(&value, _)| value == discr)
+        .map_or(*otherwise, |(_, &target)| target)
+}
@@ -39,13 +39,18 @@ pub mod qualify_consts;
 pub mod remove_noop_landing_pads;
 pub mod dump_mir;
 pub mod deaggregator;
+pub mod sroa;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod dest_prop;
 pub mod const_prop;
 pub mod generator;
 pub mod inline;
+pub mod jump_threading;
 pub mod lower_128bit;
 pub mod uniform_array_move_out;
+pub mod validate;
+pub mod instrument_coverage;
 
 pub(crate) fn provide(providers: &mut Providers) {
     self::qualify_consts::provide(providers);
@@ -170,6 +175,9 @@ pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $
             run_hooks(mir, index, false);
             pass.run_pass($tcx, source, mir);
             run_hooks(mir, index, true);
+            if $tcx.sess.opts.debugging_opts.validate_mir {
+                validate::validate($tcx, source, mir, &pass.name());
+            }
 
             index += 1;
         };
@@ -273,12 +281,19 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         instcombine::InstCombine,
         const_prop::ConstProp,
         simplify_branches::SimplifyBranches::new("after-const-prop"),
+        jump_threading::JumpThreading,
         deaggregator::Deaggregator,
+        sroa::ScalarReplacementOfAggregates,
         copy_prop::CopyPropagation,
+        dest_prop::DestinationPropagation,
         remove_noop_landing_pads::RemoveNoopLandingPads,
         simplify::SimplifyCfg::new("final"),
         simplify::SimplifyLocals,
 
+        // Coverage counters go in last, so that they count the blocks that are
+        // actually codegened.
+        instrument_coverage::InstrumentCoverage,
+
         add_call_guards::CriticalCallEdges,
         dump_mir::Marker("PreCodegen"),
     ];
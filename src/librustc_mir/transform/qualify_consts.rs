@@ -115,6 +115,8 @@ struct Qualifier<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     param_env: ty::ParamEnv<'tcx>,
     local_qualif: IndexVec<Local, Option<Qualif>>,
+    /// Locals assigned in more than one place, whose qualifs are also based on their type.
+    reassigned: BitArray<Local>,
     qualif: Qualif,
     const_fn_arg_vars: BitArray<Local>,
     temp_promotion_state: IndexVec<Local, TempState>,
@@ -149,6 +151,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             tcx,
             param_env,
             local_qualif,
+            reassigned: BitArray::new(mir.local_decls.len()),
             qualif: Qualif::empty(),
             const_fn_arg_vars: BitArray::new(mir.local_decls.len()),
             temp_promotion_state: temps,
@@ -199,6 +202,20 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         }
     }
 
+    /// Error about branches and loops in a constant.
+    fn control_flow(&mut self) {
+        self.add(Qualif::NOT_CONST);
+        if self.mode != Mode::Fn {
+            feature_err(
+                &self.tcx.sess.parse_sess,
+                "const_control_flow",
+                self.span,
+                GateIssue::Language,
+                &format!("branches and loops in {}s are unstable", self.mode),
+            ).emit();
+        }
+    }
+
     /// Add the given qualification to self.qualif.
     fn add(&mut self, qualif: Qualif) {
         self.qualif = self.qualif | qualif;
@@ -223,13 +240,26 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
     /// Assign the current qualification to the given destination.
     fn assign(&mut self, dest: &Place<'tcx>, location: Location) {
         trace!("assign: {:?}", dest);
-        let qualif = self.qualif;
+        let mut qualif = self.qualif;
+        if let Place::Local(index) = *dest {
+            if self.reassigned.contains(index) {
+                // A read of the local may be qualified before a later assignment is (say, in
+                // the next iteration of a loop), so add everything its type allows for.
+                let mut by_type = Qualif::MUTABLE_INTERIOR | Qualif::NEEDS_DROP;
+                by_type.restrict(self.mir.local_decls[index].ty, self.tcx, self.param_env);
+                qualif = qualif | by_type;
+            }
+        }
         let span = self.span;
+        // With branches, each one can assign its own value to the same local.
+        let control_flow = self.mode != Mode::Fn &&
+                           self.tcx.sess.features_untracked().const_control_flow;
         let store = |slot: &mut Option<Qualif>| {
-            if slot.is_some() {
-                span_bug!(span, "multiple assignments to {:?}", dest);
+            match *slot {
+                Some(old) if control_flow => *slot = Some(old | qualif),
+                Some(_) => span_bug!(span, "multiple assignments to {:?}", dest),
+                None => *slot = Some(qualif),
             }
-            *slot = Some(qualif);
         };
 
         // Only handle promotable temps in non-const functions.
@@ -249,7 +279,11 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                                    self.mir.local_kind(index) == LocalKind::Arg) &&
                                    self.tcx.sess.features_untracked().const_let => {
                 debug!("store to var {:?}", index);
-                self.local_qualif[index] = Some(self.qualif);
+                match self.local_qualif[index] {
+                    // The value may come from any of the assignments.
+                    Some(old) if control_flow => self.local_qualif[index] = Some(old | qualif),
+                    _ => self.local_qualif[index] = Some(qualif),
+                }
             }
             Place::Local(index) if self.mir.local_kind(index) == LocalKind::Temp ||
                                    self.mir.local_kind(index) == LocalKind::ReturnPointer => {
@@ -284,6 +318,48 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
         let mir = self.mir;
 
+        if self.tcx.sess.features_untracked().const_control_flow {
+            self.qualify_control_flow();
+        } else {
+            self.qualify_straight_line();
+        }
+
+        self.qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
+
+        // Account for errors in consts by using the
+        // conservative type qualification instead.
+        if self.qualif.intersects(Qualif::CONST_ERROR) {
+            self.qualif = Qualif::empty();
+            let return_ty = mir.return_ty();
+            self.add_type(return_ty);
+        }
+
+
+        // Collect all the temps we need to promote.
+        let mut promoted_temps = IdxSetBuf::new_empty(self.temp_promotion_state.len());
+
+        for candidate in &self.promotion_candidates {
+            match *candidate {
+                Candidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
+                    match self.mir[bb].statements[stmt_idx].kind {
+                        StatementKind::Assign(_, Rvalue::Ref(_, _, Place::Local(index))) => {
+                            promoted_temps.add(&index);
+                        }
+                        _ => {}
+                    }
+                }
+                Candidate::Argument { .. } => {}
+            }
+        }
+
+        (self.qualif, Lrc::new(promoted_temps))
+    }
+
+    /// Qualify the blocks along the single path from the start block that
+    /// constants are limited to without `const_control_flow`.
+    fn qualify_straight_line(&mut self) {
+        let mir = self.mir;
+
         let mut seen_blocks = BitArray::new(mir.basic_blocks().len());
         let mut bb = START_BLOCK;
         loop {
@@ -315,90 +391,127 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 TerminatorKind::FalseUnwind { .. } => None,
 
                 TerminatorKind::Return => {
-                    if !self.tcx.sess.features_untracked().const_let {
-                        // Check for unused values. This usually means
-                        // there are extra statements in the AST.
-                        for temp in mir.temps_iter() {
-                            if self.local_qualif[temp].is_none() {
-                                continue;
-                            }
-
-                            let state = self.temp_promotion_state[temp];
-                            if let TempState::Defined { location, uses: 0 } = state {
-                                let data = &mir[location.block];
-                                let stmt_idx = location.statement_index;
-
-                                // Get the span for the initialization.
-                                let source_info = if stmt_idx < data.statements.len() {
-                                    data.statements[stmt_idx].source_info
-                                } else {
-                                    data.terminator().source_info
-                                };
-                                self.span = source_info.span;
-
-                                // Treat this as a statement in the AST.
-                                self.statement_like();
-                            }
-                        }
-
-                        // Make sure there are no extra unassigned variables.
-                        self.qualif = Qualif::NOT_CONST;
-                        for index in mir.vars_iter() {
-                            if !self.const_fn_arg_vars.contains(index) {
-                                debug!("unassigned variable {:?}", index);
-                                self.assign(&Place::Local(index), Location {
-                                    block: bb,
-                                    statement_index: usize::MAX,
-                                });
-                            }
-                        }
-                    }
-
+                    self.check_return(bb);
                     break;
                 }
             };
 
+            let branches = match mir[bb].terminator().kind {
+                TerminatorKind::SwitchInt { .. } |
+                TerminatorKind::FalseEdges { .. } |
+                TerminatorKind::FalseUnwind { .. } => true,
+                _ => false,
+            };
+
             match target {
                 // No loops allowed.
                 Some(target) if !seen_blocks.contains(target.index()) => {
                     bb = target;
                 }
+                Some(_) => {
+                    self.control_flow();
+                    break;
+                }
+                _ if branches => {
+                    self.control_flow();
+                    break;
+                }
                 _ => {
                     self.not_const();
                     break;
                 }
             }
         }
+    }
 
-        self.qualif = self.local_qualif[RETURN_PLACE].unwrap_or(Qualif::NOT_CONST);
+    /// Qualify every block that can be reached without unwinding, in an order
+    /// where (loops aside) a block comes after all of its predecessors, so that
+    /// the qualifs of values joining after a branch include every branch.
+    fn qualify_control_flow(&mut self) {
+        let mir = self.mir;
 
-        // Account for errors in consts by using the
-        // conservative type qualification instead.
-        if self.qualif.intersects(Qualif::CONST_ERROR) {
-            self.qualif = Qualif::empty();
-            let return_ty = mir.return_ty();
-            self.add_type(return_ty);
+        // Blocks are only qualified once, so a local assigned in several places may be read
+        // before some of its assignments have been seen.
+        let mut assigned = BitArray::new(mir.local_decls.len());
+        for data in mir.basic_blocks() {
+            let statement_dests = data.statements.iter().filter_map(|statement| {
+                match statement.kind {
+                    StatementKind::Assign(Place::Local(index), _) => Some(index),
+                    _ => None,
+                }
+            });
+            let terminator_dest = match data.terminator().kind {
+                TerminatorKind::Call { destination: Some((Place::Local(index), _)), .. } |
+                TerminatorKind::DropAndReplace { location: Place::Local(index), .. } => {
+                    Some(index)
+                }
+                _ => None,
+            };
+            for index in statement_dests.chain(terminator_dest) {
+                if !assigned.insert(index) {
+                    self.reassigned.insert(index);
+                }
+            }
         }
 
+        while let Some((bb, data)) = self.rpo.next() {
+            // Unwinding never happens during constant evaluation.
+            if data.is_cleanup {
+                continue;
+            }
 
-        // Collect all the temps we need to promote.
-        let mut promoted_temps = IdxSetBuf::new_empty(self.temp_promotion_state.len());
+            self.visit_basic_block_data(bb, data);
 
-        for candidate in &self.promotion_candidates {
-            match *candidate {
-                Candidate::Ref(Location { block: bb, statement_index: stmt_idx }) => {
-                    match self.mir[bb].statements[stmt_idx].kind {
-                        StatementKind::Assign(_, Rvalue::Ref(_, _, Place::Local(index))) => {
-                            promoted_temps.add(&index);
-                        }
-                        _ => {}
-                    }
-                }
-                Candidate::Argument { .. } => {}
+            match data.terminator().kind {
+                TerminatorKind::DropAndReplace { .. } => self.not_const(),
+                TerminatorKind::Return => self.check_return(bb),
+                _ => {}
             }
         }
+    }
 
-        (self.qualif, Lrc::new(promoted_temps))
+    /// Checks done when reaching the return of the constant.
+    fn check_return(&mut self, bb: BasicBlock) {
+        let mir = self.mir;
+
+        if !self.tcx.sess.features_untracked().const_let {
+            // Check for unused values. This usually means
+            // there are extra statements in the AST.
+            for temp in mir.temps_iter() {
+                if self.local_qualif[temp].is_none() {
+                    continue;
+                }
+
+                let state = self.temp_promotion_state[temp];
+                if let TempState::Defined { location, uses: 0 } = state {
+                    let data = &mir[location.block];
+                    let stmt_idx = location.statement_index;
+
+                    // Get the span for the initialization.
+                    let source_info = if stmt_idx < data.statements.len() {
+                        data.statements[stmt_idx].source_info
+                    } else {
+                        data.terminator().source_info
+                    };
+                    self.span = source_info.span;
+
+                    // Treat this as a statement in the AST.
+                    self.statement_like();
+                }
+            }
+
+            // Make sure there are no extra unassigned variables.
+            self.qualif = Qualif::NOT_CONST;
+            for index in mir.vars_iter() {
+                if !self.const_fn_arg_vars.contains(index) {
+                    debug!("unassigned variable {:?}", index);
+                    self.assign(&Place::Local(index), Location {
+                        block: bb,
+                        statement_index: usize::MAX,
+                    });
+                }
+            }
+        }
     }
 }
 
@@ -534,6 +647,10 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                             this.qualif.restrict(ty, this.tcx, this.param_env);
                         }
 
+                        // Reading the fields of a variant after matching on it.
+                        ProjectionElem::Downcast(..) if this.mode != Mode::Fn &&
+                            this.tcx.sess.features_untracked().const_control_flow => {}
+
                         ProjectionElem::ConstantIndex {..} |
                         ProjectionElem::Subslice {..} |
                         ProjectionElem::Downcast(..) => {
@@ -0,0 +1,235 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Scalar replacement of aggregates.
+//!
+//! This pass splits locals of tuple and struct type into one local per field, when the local is
+//! only ever accessed field by field. After the `Deaggregator` has turned
+//!
+//!     _1 = (move _2, move _3);
+//!
+//! into field assignments, code that builds a tuple and immediately destructures it becomes:
+//!
+//!     (_1.0: A) = move _2;
+//!     (_1.1: B) = move _3;
+//!     ...
+//!     _4 = move (_1.0: A);
+//!
+//! which this pass rewrites to:
+//!
+//!     _5 = move _2;
+//!     _6 = move _3;
+//!     ...
+//!     _4 = move _5;
+//!
+//! so that codegen no longer needs an `alloca` for `_1`.
+//!
+//! A local is left alone if it is used as a whole anywhere (other than by its storage markers),
+//! if any part of it is borrowed, or if it is an argument or the return place. Fields that are
+//! aggregates themselves are split by further rounds of the pass.
+
+use rustc::mir::{Field, Local, LocalDecl, LocalKind, Location, Mir, Mutability};
+use rustc::mir::{Place, ProjectionElem, Statement, StatementKind};
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_set::IdxSetBuf;
+use transform::{MirPass, MirSource};
+
+pub struct ScalarReplacementOfAggregates;
+
+impl MirPass for ScalarReplacementOfAggregates {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        let mir_opt_level = tcx.sess.opts.debugging_opts.mir_opt_level;
+        if mir_opt_level == 0 {
+            return;
+        }
+        // Splitting a user variable loses its debug info, so only do it when optimizing harder.
+        let split_vars = mir_opt_level > 1;
+
+        // Each round splits the aggregates one level deep.
+        while split_aggregates(mir, split_vars) {}
+    }
+}
+
+/// Splits every local that can be split. Returns `true` if any was.
+fn split_aggregates<'tcx>(mir: &mut Mir<'tcx>, split_vars: bool) -> bool {
+    let mut collector = FieldAccesses {
+        escaped: IdxSetBuf::new_empty(mir.local_decls.len()),
+        fields: FxHashMap(),
+    };
+    for local in mir.local_decls.indices() {
+        let splittable = match mir.local_kind(local) {
+            LocalKind::Temp => true,
+            LocalKind::Var => split_vars,
+            LocalKind::Arg | LocalKind::ReturnPointer => false,
+        };
+        if !splittable || !is_splittable_ty(mir.local_decls[local].ty) {
+            collector.escaped.add(&local);
+        }
+    }
+    collector.visit_mir(mir);
+
+    let FieldAccesses { escaped, fields } = collector;
+    let mut fields: Vec<_> = fields.into_iter()
+        .filter(|&((local, _), _)| !escaped.contains(&local))
+        .collect();
+    if fields.is_empty() {
+        return false;
+    }
+    // Create the new locals in a deterministic order.
+    fields.sort_by_key(|&(key, _)| key);
+
+    let mut replacements = FxHashMap();
+    let mut split: FxHashMap<Local, Vec<Local>> = FxHashMap();
+    for ((local, field), ty) in fields {
+        let decl = LocalDecl {
+            mutability: Mutability::Mut,
+            ty,
+            name: None,
+            is_user_variable: None,
+            ..mir.local_decls[local].clone()
+        };
+        let new_local = mir.local_decls.push(decl);
+        debug!("ScalarReplacementOfAggregates: {:?}.{:?} becomes {:?}",
+               local, field, new_local);
+        replacements.insert((local, field), new_local);
+        split.entry(local).or_insert(vec![]).push(new_local);
+    }
+
+    FieldReplacer { replacements }.visit_mir(mir);
+
+    // The storage of the aggregate is now the storage of each of its fields.
+    for block in mir.basic_blocks_mut() {
+        block.expand_statements(|statement| {
+            let (local, live) = match statement.kind {
+                StatementKind::StorageLive(local) => (local, true),
+                StatementKind::StorageDead(local) => (local, false),
+                _ => return None,
+            };
+            let new_locals = split.get(&local)?;
+            let source_info = statement.source_info;
+            let statements: Vec<_> = new_locals.iter().map(|&new_local| {
+                Statement {
+                    source_info,
+                    kind: if live {
+                        StatementKind::StorageLive(new_local)
+                    } else {
+                        StatementKind::StorageDead(new_local)
+                    },
+                }
+            }).collect();
+            Some(statements.into_iter())
+        });
+    }
+
+    true
+}
+
+fn is_splittable_ty(ty: Ty) -> bool {
+    match ty.sty {
+        ty::TyTuple(..) => true,
+        ty::TyAdt(adt_def, _) => {
+            adt_def.is_struct() && !adt_def.repr.packed() && !adt_def.repr.simd()
+        }
+        _ => false,
+    }
+}
+
+/// Collects the fields through which each local is accessed, and the locals that are accessed
+/// in any other way.
+struct FieldAccesses<'tcx> {
+    escaped: IdxSetBuf<Local>,
+    fields: FxHashMap<(Local, Field), Ty<'tcx>>,
+}
+
+impl<'tcx> Visitor<'tcx> for FieldAccesses<'tcx> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        // Find the projection applied directly to the local, if any.
+        let mut base = place;
+        let mut first_elem = None;
+        while let Place::Projection(ref proj) = *base {
+            first_elem = Some(&proj.elem);
+            base = &proj.base;
+        }
+        let local = match *base {
+            Place::Local(local) => local,
+            _ => {
+                self.super_place(place, context, location);
+                return;
+            }
+        };
+
+        match (first_elem, context) {
+            // The place this is the base of has already been looked at.
+            (None, PlaceContext::Projection(..)) |
+            (None, PlaceContext::StorageLive) |
+            (None, PlaceContext::StorageDead) => {}
+            // Borrowing a field could give access to its neighbours, and inline assembly
+            // could do anything.
+            (_, PlaceContext::Borrow { .. }) |
+            (_, PlaceContext::AsmOutput) => {
+                self.escaped.add(&local);
+            }
+            (Some(&ProjectionElem::Field(field, ty)), _) => {
+                self.fields.insert((local, field), ty);
+            }
+            _ => {
+                self.escaped.add(&local);
+            }
+        }
+
+        // Visit the bases, and the locals used to index into the place.
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
+        // Locals used as a whole have been handled by `visit_place`, bases of projections are
+        // fine, and anything else, such as an array index, isn't an aggregate anyway.
+        if let PlaceContext::Projection(..) = context {
+            return;
+        }
+        if !context.is_storage_marker() {
+            self.escaped.add(&local);
+        }
+    }
+}
+
+/// Replaces each `local.field` by the local it was split into.
+struct FieldReplacer {
+    replacements: FxHashMap<(Local, Field), Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for FieldReplacer {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        let replacement = match *place {
+            Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+                (&Place::Local(local), &ProjectionElem::Field(field, _)) => {
+                    self.replacements.get(&(local, field)).cloned()
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match replacement {
+            Some(new_local) => *place = Place::Local(new_local),
+            None => self.super_place(place, context, location),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Checks that the MIR is well-formed, to catch passes that break it.
+//!
+//! With `-Z validate-mir`, this is run after every MIR pass. It checks that places and operands
+//! only refer to locals, blocks and promoted constants that exist and only project in ways their
+//! types allow, that assignments have matching types on both sides, and that terminators jump to
+//! existing blocks and respect the invariants of cleanup blocks. The first problem found is
+//! reported as an ICE naming the pass that just ran.
+//!
+//! This is not a type checker: assignments are compared up to regions, and types involving
+//! projections or late-bound regions are not compared at all, as passes are free to leave those
+//! in a different (but equivalent) form.
+
+use rustc::mir::{BasicBlock, Local, Location, Mir, Place, ProjectionElem, Rvalue};
+use rustc::mir::{Statement, StatementKind, Terminator, TerminatorKind};
+use rustc::mir::tcx::PlaceTy;
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
+use rustc_data_structures::indexed_vec::Idx;
+use transform::MirSource;
+
+/// Checks `mir`, which was just transformed by the pass called `pass_name`.
+pub fn validate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          source: MirSource,
+                          mir: &Mir<'tcx>,
+                          pass_name: &str) {
+    Validator { tcx, source, mir, pass_name }.visit_mir(mir);
+}
+
+struct Validator<'a, 'b, 'tcx: 'a + 'b> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    source: MirSource,
+    mir: &'b Mir<'tcx>,
+    pass_name: &'b str,
+}
+
+impl<'a, 'b, 'tcx> Validator<'a, 'b, 'tcx> {
+    fn fail(&self, location: Location, msg: &str) -> ! {
+        let span = self.mir.source_info(location).span;
+        span_bug!(span,
+                  "broken MIR in {:?} ({:?}) after pass `{}`, at {:?}: {}",
+                  self.source.def_id, self.source.promoted, self.pass_name, location, msg);
+    }
+
+    /// Returns `true` if a value of type `src` may be assigned to a place of type `dest`.
+    fn types_match(&self, dest: Ty<'tcx>, src: Ty<'tcx>) -> bool {
+        if dest == src {
+            return true;
+        }
+        // Normalizing or relating these would need a full type checker.
+        if dest.has_projections() || src.has_projections() ||
+           dest.has_late_bound_regions() || src.has_late_bound_regions() {
+            return true;
+        }
+        self.tcx.erase_regions(&dest) == self.tcx.erase_regions(&src)
+    }
+
+    fn check_local(&self, local: Local, location: Location) {
+        if local.index() >= self.mir.local_decls.len() {
+            self.fail(location, &format!("use of undeclared local {:?}", local));
+        }
+    }
+
+    fn check_block(&self, block: BasicBlock, location: Location) {
+        if block.index() >= self.mir.basic_blocks().len() {
+            self.fail(location, &format!("jump to nonexistent block {:?}", block));
+        }
+    }
+
+    /// Checks the place and every base of it, so that its type can be computed.
+    fn check_place(&self, place: &Place<'tcx>, location: Location) {
+        let proj = match *place {
+            Place::Local(local) => return self.check_local(local, location),
+            Place::Promoted(ref promoted) => {
+                if promoted.0.index() >= self.mir.promoted.len() {
+                    self.fail(location, &format!("use of nonexistent {:?}", promoted.0));
+                }
+                return;
+            }
+            Place::Static(_) => return,
+            Place::Projection(ref proj) => proj,
+        };
+
+        self.check_place(&proj.base, location);
+        let base_ty = proj.base.ty(self.mir, self.tcx);
+        let ok = match proj.elem {
+            ProjectionElem::Deref => base_ty.to_ty(self.tcx).builtin_deref(true).is_some(),
+            ProjectionElem::Index(local) => {
+                self.check_local(local, location);
+                if self.mir.local_decls[local].ty != self.tcx.types.usize {
+                    self.fail(location, &format!("indexing {:?} with a non-`usize` local",
+                                                 proj.base));
+                }
+                base_ty.to_ty(self.tcx).builtin_index().is_some()
+            }
+            ProjectionElem::ConstantIndex { .. } |
+            ProjectionElem::Subslice { .. } => {
+                match base_ty.to_ty(self.tcx).sty {
+                    ty::TyArray(..) | ty::TySlice(..) => true,
+                    _ => false,
+                }
+            }
+            ProjectionElem::Downcast(adt_def, variant_index) => {
+                match base_ty {
+                    PlaceTy::Ty { ty } => match ty.sty {
+                        ty::TyAdt(base_adt_def, _) => {
+                            base_adt_def == adt_def && adt_def.is_enum() &&
+                                variant_index < adt_def.variants.len()
+                        }
+                        _ => false,
+                    },
+                    PlaceTy::Downcast { .. } => false,
+                }
+            }
+            ProjectionElem::Field(field, _) => {
+                let num_fields = match base_ty {
+                    PlaceTy::Downcast { adt_def, variant_index, .. } => {
+                        Some(adt_def.variants[variant_index].fields.len())
+                    }
+                    PlaceTy::Ty { ty } => match ty.sty {
+                        ty::TyAdt(adt_def, _) if !adt_def.is_enum() => {
+                            Some(adt_def.non_enum_variant().fields.len())
+                        }
+                        ty::TyTuple(tys) => Some(tys.len()),
+                        // Upvars and generator fields aren't described by the type.
+                        ty::TyClosure(..) | ty::TyGenerator(..) => None,
+                        _ => Some(0),
+                    },
+                };
+                num_fields.map_or(true, |num_fields| field.index() < num_fields)
+            }
+        };
+        if !ok {
+            self.fail(location, &format!("invalid projection {:?} of {:?} of type {:?}",
+                                         proj.elem, proj.base, base_ty.to_ty(self.tcx)));
+        }
+    }
+}
+
+impl<'a, 'b, 'tcx> Visitor<'tcx> for Validator<'a, 'b, 'tcx> {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext<'tcx>, location: Location) {
+        self.check_local(local, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   _context: PlaceContext<'tcx>,
+                   location: Location) {
+        // This checks the bases and index locals too, so there's no need to walk the place.
+        self.check_place(place, location);
+    }
+
+    fn visit_assign(&mut self,
+                    block: BasicBlock,
+                    place: &Place<'tcx>,
+                    rvalue: &Rvalue<'tcx>,
+                    location: Location) {
+        self.super_assign(block, place, rvalue, location);
+
+        let place_ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+        let rvalue_ty = rvalue.ty(self.mir, self.tcx);
+        if !self.types_match(place_ty, rvalue_ty) {
+            self.fail(location, &format!("assignment of a `{:?}` to {:?} of type `{:?}`",
+                                         rvalue_ty, place, place_ty));
+        }
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location) {
+        self.super_statement(block, statement, location);
+
+        if let StatementKind::SetDiscriminant { ref place, variant_index } = statement.kind {
+            let ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+            match ty.sty {
+                ty::TyAdt(adt_def, _) if adt_def.is_enum() => {
+                    if variant_index >= adt_def.variants.len() {
+                        self.fail(location, &format!("`{:?}` has no variant {}",
+                                                     ty, variant_index));
+                    }
+                }
+                ty::TyGenerator(..) => {}
+                _ => {
+                    self.fail(location, &format!("setting the discriminant of a `{:?}`", ty));
+                }
+            }
+        }
+    }
+
+    fn visit_terminator(&mut self,
+                        block: BasicBlock,
+                        terminator: &Terminator<'tcx>,
+                        location: Location) {
+        self.super_terminator(block, terminator, location);
+
+        // Cleanup blocks only ever jump to each other, and are only entered by unwinding.
+        let is_cleanup = self.mir[block].is_cleanup;
+        let unwind = terminator.unwind().and_then(|&unwind| unwind);
+        for &target in terminator.successors() {
+            self.check_block(target, location);
+            if Some(target) != unwind && self.mir[target].is_cleanup != is_cleanup {
+                self.fail(location, &format!("jump to {:?}, which is {}a cleanup block",
+                                             target, if is_cleanup { "not " } else { "" }));
+            }
+        }
+        if let Some(unwind) = unwind {
+            if is_cleanup {
+                self.fail(location, "unwind edge out of a cleanup block");
+            }
+            if !self.mir[unwind].is_cleanup {
+                self.fail(location, &format!("unwind to {:?}, which is not a cleanup block",
+                                             unwind));
+            }
+        }
+
+        match terminator.kind {
+            TerminatorKind::Return |
+            TerminatorKind::GeneratorDrop |
+            TerminatorKind::Yield { .. } if is_cleanup => {
+                self.fail(location, &format!("`{:?}` in a cleanup block", terminator.kind));
+            }
+            TerminatorKind::Resume if !is_cleanup => {
+                self.fail(location, "`resume` outside of a cleanup block");
+            }
+            TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+                if values.len() + 1 != targets.len() {
+                    self.fail(location, &format!("switch with {} values but {} targets",
+                                                 values.len(), targets.len()));
+                }
+                let discr_ty = discr.ty(self.mir, self.tcx);
+                if !self.types_match(switch_ty, discr_ty) {
+                    self.fail(location, &format!("switch on a `{:?}` as a `{:?}`",
+                                                 discr_ty, switch_ty));
+                }
+            }
+            TerminatorKind::Assert { ref cond, .. } => {
+                let cond_ty = cond.ty(self.mir, self.tcx);
+                if cond_ty != self.tcx.types.bool {
+                    self.fail(location, &format!("assertion on a `{:?}`", cond_ty));
+                }
+            }
+            TerminatorKind::DropAndReplace { location: ref place, ref value, .. } => {
+                let place_ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
+                let value_ty = value.ty(self.mir, self.tcx);
+                if !self.types_match(place_ty, value_ty) {
+                    self.fail(location, &format!("replacing {:?} of type `{:?}` with a `{:?}`",
+                                                 place, place_ty, value_ty));
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,513 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for capturing a stack backtrace of an OS thread.
+//!
+//! This module contains the [`Backtrace`] type, which captures the stack of
+//! the current thread so that it can be inspected or displayed later. This is
+//! primarily useful for error types, which can capture a backtrace when they
+//! are created and print it when they are reported.
+//!
+//! ## Accuracy
+//!
+//! Backtraces are best-effort. Frames may be missing because of inlining or
+//! tail calls, and symbol names and source locations depend on the debug
+//! information available in the binary. The format of a displayed backtrace
+//! is not guaranteed to be stable.
+//!
+//! ## Platform support
+//!
+//! Not all platforms support capturing backtraces. On those that don't, a
+//! captured backtrace reports [`BacktraceStatus::Unsupported`].
+//!
+//! ## Environment variables
+//!
+//! Capturing a backtrace can be expensive, so [`Backtrace::capture`] only
+//! does so when enabled through the environment:
+//!
+//! * If `RUST_LIB_BACKTRACE` is set, backtraces are captured unless it is
+//!   `0`.
+//! * Otherwise, if `RUST_BACKTRACE` is set, backtraces are captured unless it
+//!   is `0`.
+//! * Otherwise, backtraces are not captured.
+//!
+//! The environment is only read once, the first time a backtrace is
+//! requested. [`Backtrace::force_capture`] ignores it entirely.
+//!
+//! Symbol names and source locations are only resolved when they are first
+//! needed, so capturing a backtrace which is never looked at is relatively
+//! cheap.
+//!
+//! [`Backtrace`]: struct.Backtrace.html
+//! [`Backtrace::capture`]: struct.Backtrace.html#method.capture
+//! [`Backtrace::force_capture`]: struct.Backtrace.html#method.force_capture
+//! [`BacktraceStatus::Unsupported`]: enum.BacktraceStatus.html#variant.Unsupported
+
+#![unstable(feature = "backtrace", issue = "0")]
+
+use env;
+use fmt;
+use path::{Path, PathBuf};
+use slice;
+use sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "backtrace")]
+use ptr;
+#[cfg(feature = "backtrace")]
+use str;
+#[cfg(feature = "backtrace")]
+use sync::{Mutex, OnceCell};
+#[cfg(feature = "backtrace")]
+use sys_common::backtrace::{self as sys_backtrace, BacktraceContext, Frame, PrintFormat};
+
+/// A captured stack backtrace of an OS thread.
+///
+/// A `Backtrace` is created with [`capture`] or [`force_capture`]. Its frames
+/// can be inspected with [`frames`], and it can be printed with `{}` (short
+/// format, hiding the frames of the runtime) or `{:#}` (full format).
+///
+/// [`capture`]: #method.capture
+/// [`force_capture`]: #method.force_capture
+/// [`frames`]: #method.frames
+pub struct Backtrace {
+    inner: Inner,
+}
+
+/// The current status of a backtrace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktraceStatus {
+    /// Capturing a backtrace is not supported on this platform.
+    Unsupported,
+    /// Capturing a backtrace has been disabled through the `RUST_LIB_BACKTRACE`
+    /// or `RUST_BACKTRACE` environment variables.
+    Disabled,
+    /// A backtrace has been captured and its frames can be inspected.
+    Captured,
+}
+
+enum Inner {
+    Unsupported,
+    Disabled,
+    #[cfg(feature = "backtrace")]
+    Captured(Capture),
+}
+
+#[cfg(feature = "backtrace")]
+struct Capture {
+    // The index of the first frame after the ones of `Backtrace` itself.
+    actual_start: usize,
+    frames: Vec<Frame>,
+    // Needed to resolve `frames`, and released once that is done.
+    context: Mutex<Option<BacktraceContext>>,
+    resolved: OnceCell<Vec<BacktraceFrame>>,
+}
+
+// The raw frames are plain addresses and the resolver context is only used
+// under the global backtrace lock, so captures can be shared between threads.
+#[cfg(feature = "backtrace")]
+unsafe impl Send for Backtrace {}
+#[cfg(feature = "backtrace")]
+unsafe impl Sync for Backtrace {}
+
+/// A single frame of a [`Backtrace`].
+///
+/// [`Backtrace`]: struct.Backtrace.html
+pub struct BacktraceFrame {
+    ip: usize,
+    symbol_address: usize,
+    symbols: Vec<BacktraceSymbol>,
+}
+
+/// A symbol resolved for a [`BacktraceFrame`].
+///
+/// A frame may resolve to several symbols when functions have been inlined
+/// into each other.
+///
+/// [`BacktraceFrame`]: struct.BacktraceFrame.html
+#[derive(Debug)]
+pub struct BacktraceSymbol {
+    name: Option<String>,
+    filename: Option<PathBuf>,
+    lineno: Option<u32>,
+}
+
+/// An iterator over the frames of a [`Backtrace`], created by
+/// [`Backtrace::frames`].
+///
+/// [`Backtrace`]: struct.Backtrace.html
+/// [`Backtrace::frames`]: struct.Backtrace.html#method.frames
+#[derive(Debug)]
+pub struct Frames<'a> {
+    inner: slice::Iter<'a, BacktraceFrame>,
+}
+
+impl Backtrace {
+    /// Captures a backtrace of the current thread, if backtraces are enabled.
+    ///
+    /// Whether backtraces are enabled is decided by the `RUST_LIB_BACKTRACE`
+    /// and `RUST_BACKTRACE` environment variables, as described in the
+    /// [module documentation]. If they are disabled, the returned backtrace
+    /// has the [`Disabled`] status and capturing it costs next to nothing.
+    ///
+    /// [module documentation]: index.html#environment-variables
+    /// [`Disabled`]: enum.BacktraceStatus.html#variant.Disabled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(backtrace)]
+    ///
+    /// use std::backtrace::{Backtrace, BacktraceStatus};
+    ///
+    /// let bt = Backtrace::capture();
+    /// if bt.status() == BacktraceStatus::Captured {
+    ///     println!("{}", bt);
+    /// }
+    /// ```
+    #[inline(never)] // so that `create` is always called from this frame
+    pub fn capture() -> Backtrace {
+        if !enabled() {
+            return Backtrace { inner: Inner::Disabled };
+        }
+        Backtrace::create(Backtrace::capture as usize)
+    }
+
+    /// Captures a backtrace of the current thread, regardless of the
+    /// environment.
+    ///
+    /// The backtrace still has the [`Unsupported`] status on platforms which
+    /// can't capture one.
+    ///
+    /// [`Unsupported`]: enum.BacktraceStatus.html#variant.Unsupported
+    #[inline(never)] // so that `create` is always called from this frame
+    pub fn force_capture() -> Backtrace {
+        Backtrace::create(Backtrace::force_capture as usize)
+    }
+
+    // `caller` is the address of the public function this was called from.
+    // Its frame and everything above it are left out of the backtrace.
+    #[cfg(feature = "backtrace")]
+    #[inline(never)]
+    fn create(caller: usize) -> Backtrace {
+        let mut frames = [Frame {
+            exact_position: ptr::null(),
+            symbol_addr: ptr::null(),
+            inline_context: 0,
+        }; sys_backtrace::MAX_NB_FRAMES];
+        let (nb_frames, context) =
+            match sys_backtrace::with_lock(|| sys_backtrace::unwind_backtrace(&mut frames)) {
+                Ok((0, _)) | Err(_) => return Backtrace { inner: Inner::Unsupported },
+                Ok(res) => res,
+            };
+        let frames = frames[..nb_frames].to_vec();
+        let actual_start = frames.iter()
+            .position(|frame| frame.symbol_addr as usize == caller)
+            .map_or(0, |i| i + 1);
+        Backtrace {
+            inner: Inner::Captured(Capture {
+                actual_start,
+                frames,
+                context: Mutex::new(Some(context)),
+                resolved: OnceCell::new(),
+            }),
+        }
+    }
+
+    #[cfg(not(feature = "backtrace"))]
+    fn create(_caller: usize) -> Backtrace {
+        Backtrace { inner: Inner::Unsupported }
+    }
+
+    /// Returns whether this backtrace was captured.
+    pub fn status(&self) -> BacktraceStatus {
+        match self.inner {
+            Inner::Unsupported => BacktraceStatus::Unsupported,
+            Inner::Disabled => BacktraceStatus::Disabled,
+            #[cfg(feature = "backtrace")]
+            Inner::Captured(_) => BacktraceStatus::Captured,
+        }
+    }
+
+    /// Returns an iterator over the frames of this backtrace, innermost
+    /// first.
+    ///
+    /// The first call resolves the symbols of every frame, which may be
+    /// slow. Backtraces which were not captured have no frames.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(backtrace)]
+    ///
+    /// use std::backtrace::Backtrace;
+    ///
+    /// let bt = Backtrace::force_capture();
+    /// for frame in bt.frames() {
+    ///     for symbol in frame.symbols() {
+    ///         if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
+    ///             println!("{:#x} at {}:{}", frame.ip(), file.display(), line);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn frames(&self) -> Frames {
+        Frames { inner: self.resolved_frames().iter() }
+    }
+
+    fn resolved_frames(&self) -> &[BacktraceFrame] {
+        match self.inner {
+            Inner::Unsupported | Inner::Disabled => &[],
+            #[cfg(feature = "backtrace")]
+            Inner::Captured(ref capture) => &capture.resolve()[capture.actual_start..],
+        }
+    }
+}
+
+#[cfg(feature = "backtrace")]
+impl Capture {
+    fn resolve(&self) -> &[BacktraceFrame] {
+        self.resolved.get_or_init(|| {
+            let context = match self.context.lock().unwrap().take() {
+                Some(context) => context,
+                // A previous attempt panicked.
+                None => return Vec::new(),
+            };
+            sys_backtrace::with_lock(|| {
+                self.frames.iter().map(|frame| resolve_frame(*frame, &context)).collect()
+            })
+        })
+    }
+}
+
+#[cfg(feature = "backtrace")]
+fn resolve_frame(frame: Frame, context: &BacktraceContext) -> BacktraceFrame {
+    let mut name = None;
+    let _ = sys_backtrace::resolve_symname(frame, |symname| {
+        if let Some(symname) = symname {
+            let mut demangled = Vec::new();
+            sys_backtrace::demangle(&mut demangled, symname, PrintFormat::Full)?;
+            name = String::from_utf8(demangled).ok();
+        }
+        Ok(())
+    }, context);
+
+    let mut symbols = Vec::new();
+    let _ = sys_backtrace::foreach_symbol_fileline(frame, |file, line| {
+        symbols.push(BacktraceSymbol {
+            name: None,
+            filename: str::from_utf8(file).ok().map(PathBuf::from),
+            lineno: Some(line),
+        });
+        Ok(())
+    }, context);
+
+    // The symbol table only names the function the code was inlined into,
+    // which is the outermost (last) symbol of the frame.
+    match symbols.last_mut() {
+        Some(outermost) => outermost.name = name,
+        None => symbols.push(BacktraceSymbol { name, filename: None, lineno: None }),
+    }
+
+    BacktraceFrame {
+        ip: frame.exact_position as usize,
+        symbol_address: frame.symbol_addr as usize,
+        symbols,
+    }
+}
+
+// Whether `Backtrace::capture` should capture anything. The environment is
+// only looked at once.
+fn enabled() -> bool {
+    // 0: not checked yet, 1: disabled, 2: enabled.
+    static ENABLED: AtomicUsize = AtomicUsize::new(0);
+    match ENABLED.load(Ordering::SeqCst) {
+        0 => {}
+        1 => return false,
+        _ => return true,
+    }
+
+    let enabled = match env::var_os("RUST_LIB_BACKTRACE") {
+        Some(x) => &x != "0",
+        None => match env::var_os("RUST_BACKTRACE") {
+            Some(x) => &x != "0",
+            None => false,
+        },
+    };
+    ENABLED.store(enabled as usize + 1, Ordering::SeqCst);
+    enabled
+}
+
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Returns the starting address of the function this frame is in, if
+    /// the platform provides it, or the instruction pointer otherwise.
+    pub fn symbol_address(&self) -> usize {
+        self.symbol_address
+    }
+
+    /// Returns the symbols this frame resolved to, innermost inlined
+    /// function first.
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        &self.symbols
+    }
+}
+
+impl BacktraceSymbol {
+    /// Returns the demangled name of the function, if it could be resolved.
+    ///
+    /// Only the outermost symbol of a frame has a name; the names of inlined
+    /// functions are not available.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|s| &s[..])
+    }
+
+    /// Returns the source file of this symbol, if debug information is
+    /// available.
+    pub fn filename(&self) -> Option<&Path> {
+        self.filename.as_ref().map(|p| p.as_path())
+    }
+
+    /// Returns the source line of this symbol, if debug information is
+    /// available.
+    pub fn lineno(&self) -> Option<u32> {
+        self.lineno
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = &'a BacktraceFrame;
+
+    fn next(&mut self) -> Option<&'a BacktraceFrame> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for Frames<'a> {}
+
+impl fmt::Display for Backtrace {
+    /// Formats the backtrace like the panic handler does.
+    ///
+    /// The alternate flag (`{:#}`) prints every frame along with its address,
+    /// while the default format leaves out the frames of the runtime below
+    /// `main` or the thread's entry point.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            Inner::Unsupported => return f.write_str("unsupported backtrace"),
+            Inner::Disabled => return f.write_str("disabled backtrace"),
+            #[cfg(feature = "backtrace")]
+            Inner::Captured(_) => {}
+        }
+
+        let full = f.alternate();
+        let mut frames = self.resolved_frames();
+        if !full {
+            let marker = frames.iter().position(|frame| {
+                frame.symbols.iter().any(|symbol| {
+                    symbol.name().map_or(false, |name| {
+                        name.contains("__rust_begin_short_backtrace")
+                    })
+                })
+            });
+            if let Some(marker) = marker {
+                frames = &frames[..marker];
+            }
+        }
+
+        writeln!(f, "stack backtrace:")?;
+        for (idx, frame) in frames.iter().enumerate() {
+            if full {
+                write!(f, "  {:2}: {:#018x} - ", idx, frame.ip)?;
+            } else {
+                write!(f, "  {:2}: ", idx)?;
+            }
+            match frame.symbols.iter().filter_map(|symbol| symbol.name()).next() {
+                Some(name) => writeln!(f, "{}", name)?,
+                None => writeln!(f, "<unknown>")?,
+            }
+            for symbol in &frame.symbols {
+                if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
+                    writeln!(f, "             at {}:{}", file.display(), line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            Inner::Unsupported => f.write_str("<unsupported>"),
+            Inner::Disabled => f.write_str("<disabled>"),
+            #[cfg(feature = "backtrace")]
+            Inner::Captured(_) => f.debug_list().entries(self.frames()).finish(),
+        }
+    }
+}
+
+impl fmt::Debug for BacktraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BacktraceFrame")
+            .field("ip", &format_args!("{:#x}", self.ip))
+            .field("symbols", &self.symbols)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled() {
+        let bt = Backtrace { inner: Inner::Disabled };
+        assert_eq!(bt.status(), BacktraceStatus::Disabled);
+        assert_eq!(bt.frames().count(), 0);
+        assert_eq!(bt.to_string(), "disabled backtrace");
+        assert_eq!(format!("{:?}", bt), "<disabled>");
+    }
+
+    #[test]
+    #[cfg_attr(any(target_os = "emscripten", target_arch = "wasm32"), ignore)]
+    fn force_capture() {
+        let bt = Backtrace::force_capture();
+        if bt.status() != BacktraceStatus::Captured {
+            return;
+        }
+        assert!(bt.frames().len() > 0);
+        assert!(bt.frames().all(|frame| !frame.symbols().is_empty()));
+        assert!(bt.to_string().starts_with("stack backtrace:\n"));
+        assert!(format!("{:#}", bt).starts_with("stack backtrace:\n"));
+    }
+
+    #[test]
+    #[cfg_attr(any(target_os = "emscripten", target_arch = "wasm32"), ignore)]
+    fn frames_start_at_caller() {
+        let bt = Backtrace::force_capture();
+        if bt.status() != BacktraceStatus::Captured {
+            return;
+        }
+        // None of the frames of the capturing functions themselves show up.
+        for frame in bt.frames() {
+            for symbol in frame.symbols() {
+                if let Some(name) = symbol.name() {
+                    assert!(!name.contains("Backtrace::create"), "{}", name);
+                }
+            }
+        }
+    }
+}
@@ -23,7 +23,7 @@
 // coherence challenge (e.g., specialization, neg impls, etc) we can
 // reconsider what crate these items belong in.
 
-use alloc::{AllocErr, LayoutErr, CannotReallocInPlace};
+use alloc::{Alloc, AllocErr, LayoutErr, CannotReallocInPlace};
 use any::TypeId;
 use borrow::Cow;
 use cell;
@@ -294,7 +294,7 @@ impl Error for num::ParseFloatError {
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
-impl Error for string::FromUtf8Error {
+impl<A: Alloc + Debug> Error for string::FromUtf8Error<A> {
     fn description(&self) -> &str {
         "invalid utf-8"
     }
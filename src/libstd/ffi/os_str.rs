@@ -13,6 +13,7 @@ use fmt;
 use ops;
 use cmp;
 use hash::{Hash, Hasher};
+use iter::FusedIterator;
 use rc::Rc;
 use sync::Arc;
 
@@ -499,6 +500,10 @@ impl OsStr {
         unsafe { &*(inner as *const Slice as *const OsStr) }
     }
 
+    fn from_inner_mut(inner: &mut Slice) -> &mut OsStr {
+        unsafe { &mut *(inner as *mut Slice as *mut OsStr) }
+    }
+
     /// Yields a [`&str`] slice if the `OsStr` is valid Unicode.
     ///
     /// This conversion may entail doing a check for UTF-8 validity.
@@ -617,6 +622,218 @@ impl OsStr {
         OsString { inner: Buf::from_box(boxed) }
     }
 
+    /// Returns `true` if the given prefix matches a prefix of this `OsStr`.
+    ///
+    /// The comparison works on the platform's encoding, so `prefix` may be
+    /// any `OsStr`, including one that isn't valid Unicode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("--verbose");
+    /// assert!(os_str.starts_with("--"));
+    /// assert!(!os_str.starts_with("-v"));
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn starts_with<S: AsRef<OsStr>>(&self, prefix: S) -> bool {
+        self.bytes().starts_with(prefix.as_ref().bytes())
+    }
+
+    /// Returns this `OsStr` with `prefix` removed, or `None` if it doesn't
+    /// start with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("--verbose");
+    /// assert_eq!(os_str.strip_prefix("--"), Some(OsStr::new("verbose")));
+    /// assert_eq!(os_str.strip_prefix("-v"), None);
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn strip_prefix<S: AsRef<OsStr>>(&self, prefix: S) -> Option<&OsStr> {
+        let prefix = prefix.as_ref().bytes();
+        if self.bytes().starts_with(prefix) {
+            // A well-formed prefix always ends on a boundary of the encoding.
+            Some(unsafe { OsStr::from_bytes_unchecked(&self.bytes()[prefix.len()..]) })
+        } else {
+            None
+        }
+    }
+
+    /// Splits this `OsStr` on the first occurrence of `delimiter`, returning
+    /// the parts before and after it, or `None` if it doesn't occur.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiter` is not ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("--path=/tmp/foo");
+    /// assert_eq!(os_str.split_once('='), Some((OsStr::new("--path"), OsStr::new("/tmp/foo"))));
+    /// assert_eq!(os_str.split_once(':'), None);
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn split_once(&self, delimiter: char) -> Option<(&OsStr, &OsStr)> {
+        let delimiter = ascii_delimiter(delimiter);
+        let bytes = self.bytes();
+        bytes.iter().position(|&b| b == delimiter).map(|i| unsafe {
+            (OsStr::from_bytes_unchecked(&bytes[..i]),
+             OsStr::from_bytes_unchecked(&bytes[i + 1..]))
+        })
+    }
+
+    /// Returns an iterator over the parts of this `OsStr` separated by
+    /// `delimiter`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `delimiter` is not ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// let os_str = OsStr::new("/bin:/usr/bin:");
+    /// let parts: Vec<_> = os_str.split(':').collect();
+    /// assert_eq!(parts, [OsStr::new("/bin"), OsStr::new("/usr/bin"), OsStr::new("")]);
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn split(&self, delimiter: char) -> OsStrSplit {
+        OsStrSplit { remainder: Some(self), delimiter: ascii_delimiter(delimiter) }
+    }
+
+    /// Checks if all characters in this `OsStr` are within the ASCII range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert!(OsStr::new("hello!\n").is_ascii());
+    /// assert!(!OsStr::new("Grüße, Jürgen ❤").is_ascii());
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn is_ascii(&self) -> bool {
+        self.bytes().is_ascii()
+    }
+
+    /// Checks that two `OsStr`s are an ASCII case-insensitive match.
+    ///
+    /// Same as `to_ascii_lowercase(a) == to_ascii_lowercase(b)`, but without
+    /// allocating and copying temporaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert!(OsStr::new("Ferris").eq_ignore_ascii_case("FERRIS"));
+    /// assert!(!OsStr::new("Ferrös").eq_ignore_ascii_case("FERRÖS"));
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn eq_ignore_ascii_case<S: AsRef<OsStr>>(&self, other: S) -> bool {
+        self.bytes().eq_ignore_ascii_case(other.as_ref().bytes())
+    }
+
+    /// Converts this `OsStr` to its ASCII lower case equivalent in-place.
+    ///
+    /// ASCII letters 'A' to 'Z' are mapped to 'a' to 'z', but everything
+    /// else, including non-Unicode data, is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::{OsStr, OsString};
+    ///
+    /// let mut s: Box<OsStr> = OsString::from("GRÜSSE").into_boxed_os_str();
+    /// s.make_ascii_lowercase();
+    /// assert_eq!(&*s, "grÜsse");
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn make_ascii_lowercase(&mut self) {
+        self.bytes_mut().make_ascii_lowercase()
+    }
+
+    /// Converts this `OsStr` to its ASCII upper case equivalent in-place.
+    ///
+    /// ASCII letters 'a' to 'z' are mapped to 'A' to 'Z', but everything
+    /// else, including non-Unicode data, is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::{OsStr, OsString};
+    ///
+    /// let mut s: Box<OsStr> = OsString::from("grüße").into_boxed_os_str();
+    /// s.make_ascii_uppercase();
+    /// assert_eq!(&*s, "GRüßE");
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn make_ascii_uppercase(&mut self) {
+        self.bytes_mut().make_ascii_uppercase()
+    }
+
+    /// Returns a copy of this `OsStr` where each character is mapped to its
+    /// ASCII lower case equivalent.
+    ///
+    /// To lowercase the value in-place, use [`make_ascii_lowercase`].
+    ///
+    /// [`make_ascii_lowercase`]: #method.make_ascii_lowercase
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("GRÜSSE").to_ascii_lowercase(), "grÜsse");
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn to_ascii_lowercase(&self) -> OsString {
+        let mut s = self.to_os_string();
+        OsStr::from_inner_mut(s.inner.as_mut_slice()).make_ascii_lowercase();
+        s
+    }
+
+    /// Returns a copy of this `OsStr` where each character is mapped to its
+    /// ASCII upper case equivalent.
+    ///
+    /// To uppercase the value in-place, use [`make_ascii_uppercase`].
+    ///
+    /// [`make_ascii_uppercase`]: #method.make_ascii_uppercase
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(osstr_str_ops)]
+    /// use std::ffi::OsStr;
+    ///
+    /// assert_eq!(OsStr::new("grüße").to_ascii_uppercase(), "GRüßE");
+    /// ```
+    #[unstable(feature = "osstr_str_ops", issue = "0")]
+    pub fn to_ascii_uppercase(&self) -> OsString {
+        let mut s = self.to_os_string();
+        OsStr::from_inner_mut(s.inner.as_mut_slice()).make_ascii_uppercase();
+        s
+    }
+
     /// Gets the underlying byte representation.
     ///
     /// Note: it is *crucial* that this API is private, to avoid
@@ -624,8 +841,81 @@ impl OsStr {
     fn bytes(&self) -> &[u8] {
         unsafe { &*(&self.inner as *const _ as *const [u8]) }
     }
+
+    /// Gets the underlying byte representation, mutably.
+    ///
+    /// Callers must only change ASCII bytes into other ASCII bytes, which
+    /// keeps every platform's encoding well-formed.
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { &mut *(&mut self.inner as *mut _ as *mut [u8]) }
+    }
+
+    /// Converts a slice of the underlying byte representation back into an
+    /// `OsStr`.
+    ///
+    /// This is unsafe because `bytes` must be well-formed in the platform's
+    /// encoding. Any slice of another `OsStr`'s bytes starting and ending on
+    /// an ASCII byte or on the ends of the string is.
+    unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &OsStr {
+        &*(bytes as *const [u8] as *const OsStr)
+    }
+}
+
+fn ascii_delimiter(delimiter: char) -> u8 {
+    assert!(delimiter.is_ascii(), "delimiter must be ASCII");
+    delimiter as u8
+}
+
+/// An iterator over the parts of an [`OsStr`] separated by an ASCII
+/// delimiter.
+///
+/// This struct is created by the [`split`] method on [`OsStr`]. See its
+/// documentation for more.
+///
+/// [`OsStr`]: struct.OsStr.html
+/// [`split`]: struct.OsStr.html#method.split
+#[derive(Clone, Debug)]
+#[unstable(feature = "osstr_str_ops", issue = "0")]
+pub struct OsStrSplit<'a> {
+    remainder: Option<&'a OsStr>,
+    delimiter: u8,
+}
+
+#[unstable(feature = "osstr_str_ops", issue = "0")]
+impl<'a> Iterator for OsStrSplit<'a> {
+    type Item = &'a OsStr;
+
+    fn next(&mut self) -> Option<&'a OsStr> {
+        let rest = self.remainder?;
+        let delimiter = self.delimiter;
+        match rest.split_once(delimiter as char) {
+            Some((part, rest)) => {
+                self.remainder = Some(rest);
+                Some(part)
+            }
+            None => self.remainder.take(),
+        }
+    }
+}
+
+#[unstable(feature = "osstr_str_ops", issue = "0")]
+impl<'a> DoubleEndedIterator for OsStrSplit<'a> {
+    fn next_back(&mut self) -> Option<&'a OsStr> {
+        let rest = self.remainder?;
+        let bytes = rest.bytes();
+        match bytes.iter().rposition(|&b| b == self.delimiter) {
+            Some(i) => unsafe {
+                self.remainder = Some(OsStr::from_bytes_unchecked(&bytes[..i]));
+                Some(OsStr::from_bytes_unchecked(&bytes[i + 1..]))
+            },
+            None => self.remainder.take(),
+        }
+    }
 }
 
+#[unstable(feature = "osstr_str_ops", issue = "0")]
+impl<'a> FusedIterator for OsStrSplit<'a> {}
+
 #[stable(feature = "box_from_os_str", since = "1.17.0")]
 impl<'a> From<&'a OsStr> for Box<OsStr> {
     fn from(s: &'a OsStr) -> Box<OsStr> {
@@ -1103,4 +1393,75 @@ mod tests {
         assert_eq!(&*rc2, os_str);
         assert_eq!(&*arc2, os_str);
     }
+
+    #[test]
+    fn test_os_str_prefix_and_split() {
+        let os_str = OsStr::new("--path=a=b");
+        assert!(os_str.starts_with("--"));
+        assert!(os_str.starts_with(""));
+        assert!(!os_str.starts_with("--path=a=b="));
+        assert_eq!(os_str.strip_prefix("--path="), Some(OsStr::new("a=b")));
+        assert_eq!(os_str.strip_prefix("-p"), None);
+        assert_eq!(os_str.split_once('='), Some((OsStr::new("--path"), OsStr::new("a=b"))));
+
+        let parts: Vec<_> = os_str.split('=').collect();
+        assert_eq!(parts, ["--path", "a", "b"]);
+        let parts: Vec<_> = os_str.split('=').rev().collect();
+        assert_eq!(parts, ["b", "a", "--path"]);
+        assert_eq!(OsStr::new("").split(',').collect::<Vec<_>>(), [""]);
+        assert_eq!(OsStr::new(",").split(',').collect::<Vec<_>>(), ["", ""]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_os_str_split_non_ascii() {
+        OsStr::new("a\u{e9}b").split_once('\u{e9}');
+    }
+
+    #[test]
+    fn test_os_str_ascii_case() {
+        let os_str = OsStr::new("Hello, Wörld");
+        assert!(!os_str.is_ascii());
+        assert!(OsStr::new("Hello").is_ascii());
+        assert_eq!(os_str.to_ascii_lowercase(), "hello, wörld");
+        assert_eq!(os_str.to_ascii_uppercase(), "HELLO, WöRLD");
+        assert!(os_str.eq_ignore_ascii_case("hELLO, wöRLD"));
+        assert!(!os_str.eq_ignore_ascii_case("hELLO, WÖRLD"));
+
+        let mut boxed: Box<OsStr> = os_str.into();
+        boxed.make_ascii_uppercase();
+        assert_eq!(&*boxed, "HELLO, WöRLD");
+        boxed.make_ascii_lowercase();
+        assert_eq!(&*boxed, "hello, wörld");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_os_str_ops_non_unicode() {
+        use os::unix::ffi::OsStrExt;
+
+        let os_str = OsStr::from_bytes(b"--Path=\xff/\xfe");
+        assert!(os_str.to_str().is_none());
+        let (flag, value) = os_str.split_once('=').unwrap();
+        assert_eq!(flag, "--Path");
+        assert_eq!(value.as_bytes(), b"\xff/\xfe");
+        assert_eq!(value.strip_prefix(OsStr::from_bytes(b"\xff")).unwrap().as_bytes(), b"/\xfe");
+        assert_eq!(os_str.to_ascii_lowercase().as_bytes(), b"--path=\xff/\xfe");
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn test_os_str_ops_non_unicode() {
+        use os::windows::ffi::{OsStringExt, OsStrExt};
+
+        // "--Path=" followed by an unpaired surrogate.
+        let mut wide: Vec<u16> = "--Path=".encode_utf16().collect();
+        wide.push(0xD800);
+        let os_string = OsString::from_wide(&wide);
+        assert!(os_string.to_str().is_none());
+        let (flag, value) = os_string.split_once('=').unwrap();
+        assert_eq!(flag, "--Path");
+        assert_eq!(value.encode_wide().collect::<Vec<_>>(), [0xD800]);
+        assert_eq!(os_string.to_ascii_lowercase().encode_wide().skip(2).next(), Some(b'p' as u16));
+    }
 }
@@ -17,10 +17,13 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use env;
+use error;
 use fmt;
 use ffi::OsString;
 use io::{self, SeekFrom, Seek, Read, Initializer, Write};
 use path::{Path, PathBuf};
+use sys;
 use sys::fs as fs_imp;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
 use time::SystemTime;
@@ -132,6 +135,28 @@ pub struct Metadata(fs_imp::FileAttr);
 #[derive(Debug)]
 pub struct ReadDir(fs_imp::ReadDir);
 
+/// Iterator over all the entries of a directory tree, recursively.
+///
+/// This iterator is returned from the [`walk_dir`] function of this module
+/// and will yield instances of [`io::Result`]`<`[`DirEntry`]`>`. The
+/// entries are returned depth-first: a directory is returned before its
+/// contents, which are returned before any of its following siblings. The
+/// root directory itself is not returned.
+///
+/// # Errors
+///
+/// An error is returned in place of an entry whenever a directory can't be
+/// read, and when following symbolic links leads back to one of the
+/// directories currently being walked. The walk carries on with the next
+/// entry after an error.
+///
+/// [`walk_dir`]: fn.walk_dir.html
+/// [`DirEntry`]: struct.DirEntry.html
+/// [`io::Result`]: ../io/type.Result.html
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+#[derive(Debug)]
+pub struct WalkDir(fs_imp::WalkDir);
+
 /// Entries returned by the [`ReadDir`] iterator.
 ///
 /// [`ReadDir`]: struct.ReadDir.html
@@ -220,6 +245,80 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// A temporary file, which is removed when it is dropped unless it has been
+/// [`persist`]ed.
+///
+/// The file is created with a random name which did not exist before, and
+/// on Unix with permissions only allowing the current user to access it, so
+/// that other users can't tamper with it. On Linux, the file is created
+/// with `O_TMPFILE` where the filesystem supports it, which means that it
+/// has no name at all until it is persisted and can't be left behind if
+/// the process is killed.
+///
+/// [`persist`]: #method.persist
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_tempfile)]
+///
+/// use std::fs::TempFile;
+/// use std::io::{Read, Seek, SeekFrom, Write};
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut file = TempFile::new()?;
+///     file.write_all(b"scratch data")?;
+///     file.seek(SeekFrom::Start(0))?;
+///
+///     let mut contents = String::new();
+///     file.read_to_string(&mut contents)?;
+///     assert_eq!(contents, "scratch data");
+///     Ok(())
+/// } // the file is removed here
+/// ```
+#[unstable(feature = "fs_tempfile", issue = "0")]
+pub struct TempFile {
+    // Dropped first, as Windows can't remove files which are still open.
+    file: File,
+    // The directory the file was created in, which it must stay in until it
+    // is renamed by `persist`.
+    dir: PathBuf,
+    path: TempPath,
+}
+
+// The name of a temporary file, which is removed when this is dropped.
+struct TempPath(Option<PathBuf>);
+
+/// A temporary directory, which is removed along with its contents when it
+/// is dropped.
+///
+/// The directory is created with a random name which did not exist before,
+/// and on Unix with permissions only allowing the current user to access
+/// it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_tempfile)]
+///
+/// use std::fs::{self, TempDir};
+///
+/// fn main() -> std::io::Result<()> {
+///     let dir = TempDir::new()?;
+///     fs::write(dir.path().join("config.toml"), "[build]")?;
+///
+///     // Errors are ignored when the directory is dropped, `close` reports
+///     // them instead.
+///     dir.close()?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_tempfile", issue = "0")]
+pub struct TempDir {
+    // `None` once `close` has been called.
+    path: Option<PathBuf>,
+}
+
 /// How large a buffer to pre-allocate before reading the entire file.
 fn initial_buffer_size(file: &File) -> usize {
     // Allocate one extra byte so the buffer doesn't need to grow before the
@@ -579,6 +678,157 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it
+    /// can be acquired.
+    ///
+    /// At most one handle may hold an exclusive lock on a file at a time, and
+    /// no handle may hold a shared lock while it is held. If this handle
+    /// already holds a shared lock, it is converted to an exclusive one.
+    ///
+    /// The lock is advisory: it doesn't prevent other processes from reading
+    /// or writing the file unless they also take the lock. It is released by
+    /// [`unlock`], or when the last handle to the underlying open file is
+    /// closed (this includes handles created with [`try_clone`]).
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX` flag (emulated with `fcntl` on Solaris, where locks
+    /// are held by the process rather than the handle), and the `LockFileEx`
+    /// function on Windows with the `LOCKFILE_EXCLUSIVE_LOCK` flag. Note
+    /// that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`unlock`]: #method.unlock
+    /// [`try_clone`]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::prelude::*;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::create("foo.txt")?;
+    ///     f.lock_exclusive()?;
+    ///     f.write_all(b"Hello, world!")?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock_exclusive()
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of handles may hold a shared lock on a file at the same
+    /// time, as long as no handle holds an exclusive lock. See
+    /// [`lock_exclusive`] for how the lock is released.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_SH` flag, and the `LockFileEx` function on Windows. Note
+    /// that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::prelude::*;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::open("foo.txt")?;
+    ///     f.lock_shared()?;
+    ///     let mut contents = String::new();
+    ///     f.read_to_string(&mut contents)?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the file without
+    /// blocking.
+    ///
+    /// See [`lock_exclusive`] for the semantics of the lock.
+    ///
+    /// # Errors
+    ///
+    /// If the lock is held by another handle, this function returns an error
+    /// of kind [`ErrorKind::WouldBlock`]. Other errors are returned for
+    /// failures unrelated to contention.
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::ErrorKind;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     match f.try_lock_exclusive() {
+    ///         Ok(()) => println!("got the lock"),
+    ///         Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+    ///             println!("somebody else holds the lock")
+    ///         }
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.inner.try_lock_exclusive()
+    }
+
+    /// Attempts to acquire a shared advisory lock on the file without
+    /// blocking.
+    ///
+    /// See [`lock_shared`] for the semantics of the lock.
+    ///
+    /// # Errors
+    ///
+    /// If an exclusive lock is held by another handle, this function returns
+    /// an error of kind [`ErrorKind::WouldBlock`].
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases any advisory lock held on the file by this handle.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_UN` flag, and the `UnlockFile` function on Windows. On
+    /// Windows, unlocking a file which isn't locked returns an error. Note
+    /// that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -1265,6 +1515,30 @@ impl Iterator for ReadDir {
     }
 }
 
+impl WalkDir {
+    /// Sets whether symbolic links to directories are followed, in which
+    /// case the contents of the directory they point to are walked as well.
+    ///
+    /// By default links are not followed. Links are still returned as
+    /// entries either way. When they are followed, a link pointing back to
+    /// one of its ancestors is reported as an error instead of being walked
+    /// forever.
+    #[unstable(feature = "fs_walk_dir", issue = "0")]
+    pub fn follow_links(mut self, follow_links: bool) -> WalkDir {
+        self.0.set_follow_links(follow_links);
+        self
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+impl Iterator for WalkDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        self.0.next().map(|entry| entry.map(DirEntry))
+    }
+}
+
 impl DirEntry {
     /// Returns the full path to the file that this entry represents.
     ///
@@ -1855,9 +2129,10 @@ pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
 ///
 /// # Platform-specific behavior
 ///
-/// This function currently corresponds to `opendir`, `lstat`, `rm` and `rmdir` functions on Unix
-/// and the `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`, and `RemoveDirectory` functions
-/// on Windows.
+/// This function currently corresponds to `openat`, `fdopendir`, `unlinkat` and `lstat` functions
+/// on Unix, except on macOS and iOS where `opendir`, `lstat`, `rm` and `rmdir` are used, and the
+/// `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`, and `RemoveDirectory` functions on
+/// Windows.
 /// Note that, this [may change in the future][changes].
 ///
 /// [changes]: ../io/index.html#platform-specific-behavior
@@ -1934,6 +2209,54 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
     fs_imp::readdir(path.as_ref()).map(ReadDir)
 }
 
+/// Returns an iterator over all the entries within a directory, recursively.
+///
+/// See [`WalkDir`] for the order in which entries are returned. Symbolic
+/// links are not followed unless [`WalkDir::follow_links`] is used, except
+/// for `path` itself.
+///
+/// # Platform-specific behavior
+///
+/// On Unix other than macOS and iOS, each directory is opened relative to
+/// its parent with `openat` and read with `fdopendir`. Unless links are
+/// followed, the walk therefore can't be redirected outside of the tree by a
+/// directory concurrently being replaced with a symbolic link. Elsewhere,
+/// directories are opened by path.
+/// Note that, this [may change in the future][changes].
+///
+/// [`WalkDir`]: struct.WalkDir.html
+/// [`WalkDir::follow_links`]: struct.WalkDir.html#method.follow_links
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error if `path` can't be read as a
+/// directory, in the same situations as [`read_dir`].
+///
+/// [`read_dir`]: fn.read_dir.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+///
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     for entry in fs::walk_dir("src")? {
+///         let entry = entry?;
+///         if entry.file_type()?.is_file() {
+///             println!("{}", entry.path().display());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "0")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> io::Result<WalkDir> {
+    fs_imp::WalkDir::new(path.as_ref()).map(WalkDir)
+}
+
 /// Changes the permissions found on a file or a directory.
 ///
 /// # Platform-specific behavior
@@ -2069,6 +2392,368 @@ impl AsInnerMut<fs_imp::DirBuilder> for DirBuilder {
     }
 }
 
+/// Write a slice as the entire contents of a file, atomically replacing it
+/// if it already exists.
+///
+/// The contents are first written to a [`TempFile`] created in the same
+/// directory as `path`, which is then [`persist`]ed over `path`. Other
+/// processes therefore see either the old file or the new one, never a
+/// partially written file, and the new file is on disk once this function
+/// returns. If `path` already exists, its permissions are copied over to the
+/// new file.
+///
+/// [`TempFile`]: struct.TempFile.html
+/// [`persist`]: struct.TempFile.html#method.persist
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_tempfile)]
+///
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::write_atomic("Cargo.lock", b"# This file is automatically @generated")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_tempfile", issue = "0")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut file = TempFile::new_in(parent_dir(path))?;
+    if let Ok(metadata) = metadata(path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents.as_ref())?;
+    file.persist(path)?;
+    Ok(())
+}
+
+/// The directory containing `path`, which is `.` for relative paths with a
+/// single component.
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Calls `create` with random paths in `dir`, until it doesn't fail because
+/// the path already exists.
+fn create_unique<T, F>(dir: &Path, mut create: F) -> io::Result<T>
+    where F: FnMut(&Path) -> io::Result<T>
+{
+    const ATTEMPTS: u32 = 128;
+
+    for _ in 0..ATTEMPTS {
+        let path = dir.join(random_name());
+        match create(&path) {
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            result => return result,
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists,
+                       "failed to find an unused name for a temporary file"))
+}
+
+fn random_name() -> String {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+    let mut bits = sys::hashmap_random_keys().0;
+    let mut name = String::from(".tmp");
+    for _ in 0..12 {
+        name.push(CHARS[(bits % CHARS.len() as u64) as usize] as char);
+        bits /= CHARS.len() as u64;
+    }
+    name
+}
+
+// Temporary files and directories are only accessible by their owner.
+#[cfg(any(unix, target_os = "redox"))]
+fn set_private_file_mode(opts: &mut OpenOptions) {
+    use os::unix::fs::OpenOptionsExt;
+    opts.mode(0o600);
+}
+
+#[cfg(any(unix, target_os = "redox"))]
+fn set_private_dir_mode(builder: &mut DirBuilder) {
+    use os::unix::fs::DirBuilderExt;
+    builder.mode(0o700);
+}
+
+#[cfg(not(any(unix, target_os = "redox")))]
+fn set_private_file_mode(_opts: &mut OpenOptions) {}
+
+#[cfg(not(any(unix, target_os = "redox")))]
+fn set_private_dir_mode(_builder: &mut DirBuilder) {}
+
+impl TempFile {
+    /// Creates a new temporary file in the directory returned by
+    /// [`env::temp_dir`].
+    ///
+    /// [`env::temp_dir`]: ../env/fn.temp_dir.html
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn new() -> io::Result<TempFile> {
+        TempFile::new_in(env::temp_dir())
+    }
+
+    /// Creates a new temporary file in `dir`.
+    ///
+    /// A file which is going to be [`persist`]ed must be created in the
+    /// directory of its final location, or at least on the same filesystem.
+    ///
+    /// [`persist`]: #method.persist
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempFile> {
+        let dir = dir.as_ref();
+        if let Some(file) = fs_imp::tmpfile(dir)? {
+            return Ok(TempFile {
+                file: File { inner: file },
+                dir: dir.to_path_buf(),
+                path: TempPath(None),
+            });
+        }
+
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true).create_new(true);
+        set_private_file_mode(&mut opts);
+        let (file, path) = create_unique(dir, |path| {
+            opts.open(path).map(|file| (file, path.to_path_buf()))
+        })?;
+        Ok(TempFile {
+            file,
+            dir: dir.to_path_buf(),
+            path: TempPath(Some(path)),
+        })
+    }
+
+    /// Returns a reference to the underlying file.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns a mutable reference to the underlying file.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Atomically moves the file to `path`, replacing any existing file, and
+    /// returns it as a regular file.
+    ///
+    /// The contents of the file are flushed to disk first, and the directory
+    /// containing `path` afterwards, so that once this returns, `path` refers
+    /// to the complete new file even if the system crashes.
+    ///
+    /// `path` must be on the same filesystem as the temporary file.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be flushed or moved, `path` is left untouched and
+    /// the temporary file is handed back in the [`PersistError`], so that it
+    /// can be persisted somewhere else. If only flushing the directory fails,
+    /// `path` has already been replaced, and dropping the returned file
+    /// leaves it in place.
+    ///
+    /// [`PersistError`]: struct.PersistError.html
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn persist<P: AsRef<Path>>(mut self, path: P) -> Result<File, PersistError> {
+        let path = path.as_ref();
+        if let Err(error) = self.file.sync_all() {
+            return Err(PersistError { error, file: self });
+        }
+
+        if self.path.0.is_none() {
+            // An unnamed file needs a name before it can be moved over
+            // `path`, as linking it there can't replace an existing file.
+            let name = {
+                let file = &self.file;
+                create_unique(&self.dir, |name| {
+                    fs_imp::link_tmpfile(&file.inner, name).map(|()| name.to_path_buf())
+                })
+            };
+            match name {
+                Ok(name) => self.path.0 = Some(name),
+                Err(error) => return Err(PersistError { error, file: self }),
+            }
+        }
+        if let Err(error) = rename(self.path.0.as_ref().unwrap(), path) {
+            return Err(PersistError { error, file: self });
+        }
+        self.path.0 = None;
+
+        if let Err(error) = fs_imp::fsync_dir(parent_dir(path)) {
+            return Err(PersistError { error, file: self });
+        }
+        let TempFile { file, .. } = self;
+        Ok(file)
+    }
+}
+
+/// The error returned by [`TempFile::persist`], which gives back the
+/// temporary file.
+///
+/// [`TempFile::persist`]: struct.TempFile.html#method.persist
+#[unstable(feature = "fs_tempfile", issue = "0")]
+pub struct PersistError {
+    error: io::Error,
+    file: TempFile,
+}
+
+impl PersistError {
+    /// Returns the error which caused the call to `persist` to fail.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Returns the temporary file which failed to be persisted.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn into_file(self) -> TempFile {
+        self.file
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl From<PersistError> for io::Error {
+    fn from(e: PersistError) -> io::Error {
+        e.error
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl fmt::Debug for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PersistError")
+            .field("error", &self.error)
+            .field("file", &self.file)
+            .finish()
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl error::Error for PersistError {
+    fn description(&self) -> &str {
+        error::Error::description(&self.error)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.0 {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TempFile")
+            .field("file", &self.file)
+            .field("path", &self.path.0)
+            .finish()
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl Read for TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+
+    #[inline]
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl Write for TempFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl Seek for TempFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+}
+
+impl TempDir {
+    /// Creates a new temporary directory in the directory returned by
+    /// [`env::temp_dir`].
+    ///
+    /// [`env::temp_dir`]: ../env/fn.temp_dir.html
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn new() -> io::Result<TempDir> {
+        TempDir::new_in(env::temp_dir())
+    }
+
+    /// Creates a new temporary directory in `dir`.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempDir> {
+        let mut builder = DirBuilder::new();
+        set_private_dir_mode(&mut builder);
+        let path = create_unique(dir.as_ref(), |path| {
+            builder.create(path).map(|()| path.to_path_buf())
+        })?;
+        Ok(TempDir { path: Some(path) })
+    }
+
+    /// Returns the path of the directory.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn path(&self) -> &Path {
+        self.path.as_ref().unwrap()
+    }
+
+    /// Removes the directory and its contents, reporting any error.
+    ///
+    /// Dropping a `TempDir` removes it as well, but ignores errors.
+    #[unstable(feature = "fs_tempfile", issue = "0")]
+    pub fn close(mut self) -> io::Result<()> {
+        let path = self.path.take().unwrap();
+        remove_dir_all(&path)
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.path {
+            let _ = remove_dir_all(path);
+        }
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        self.path()
+    }
+}
+
+#[unstable(feature = "fs_tempfile", issue = "0")]
+impl fmt::Debug for TempDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TempDir").field("path", &self.path).finish()
+    }
+}
+
 #[cfg(all(test, not(any(target_os = "cloudabi", target_os = "emscripten"))))]
 mod tests {
     use io::prelude::*;
@@ -2625,6 +3310,132 @@ mod tests {
         assert!(canary.exists());
     }
 
+    #[test]
+    fn tempfile_removed_on_drop() {
+        let tmpdir = tmpdir();
+        let mut file = check!(fs::TempFile::new_in(tmpdir.path()));
+        check!(file.write_all(b"foo"));
+        check!(file.seek(SeekFrom::Start(0)));
+        let mut contents = String::new();
+        check!(file.read_to_string(&mut contents));
+        assert_eq!(contents, "foo");
+        drop(file);
+
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 0);
+    }
+
+    #[test]
+    fn tempfile_persist() {
+        let tmpdir = tmpdir();
+        let target = tmpdir.join("target");
+        check!(fs::write(&target, b"old"));
+
+        let mut file = check!(fs::TempFile::new_in(tmpdir.path()));
+        check!(file.write_all(b"new"));
+        let mut file = check!(file.persist(&target));
+        check!(file.write_all(b" and more"));
+        drop(file);
+
+        assert_eq!(check!(fs::read(&target)), b"new and more");
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+    }
+
+    #[test]
+    fn tempfile_persist_failure_keeps_file() {
+        let tmpdir = tmpdir();
+        let target = tmpdir.join("missing").join("target");
+
+        let mut file = check!(fs::TempFile::new_in(tmpdir.path()));
+        check!(file.write_all(b"data"));
+        let mut file = file.persist(&target).unwrap_err().into_file();
+        assert!(!target.exists());
+
+        check!(file.seek(SeekFrom::Start(0)));
+        let mut contents = String::new();
+        check!(file.read_to_string(&mut contents));
+        assert_eq!(contents, "data");
+
+        let target = tmpdir.join("target");
+        check!(file.persist(&target));
+        assert_eq!(check!(fs::read(&target)), b"data");
+    }
+
+    #[test]
+    fn write_atomic() {
+        let tmpdir = tmpdir();
+        let target = tmpdir.join("target");
+        check!(fs::write_atomic(&target, b"first"));
+        assert_eq!(check!(fs::read(&target)), b"first");
+        check!(fs::write_atomic(&target, b"second"));
+        assert_eq!(check!(fs::read(&target)), b"second");
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn write_atomic_keeps_permissions() {
+        use os::unix::fs::PermissionsExt;
+
+        let tmpdir = tmpdir();
+        let target = tmpdir.join("target");
+        check!(fs::write(&target, b"first"));
+        check!(fs::set_permissions(&target, fs::Permissions::from_mode(0o640)));
+        check!(fs::write_atomic(&target, b"second"));
+        assert_eq!(check!(fs::metadata(&target)).permissions().mode() & 0o777, 0o640);
+    }
+
+    #[test]
+    fn tempdir_removed() {
+        let tmpdir = tmpdir();
+        let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+        let path = dir.path().to_path_buf();
+        check!(fs::create_dir(path.join("sub")));
+        check!(fs::write(path.join("sub").join("file"), b"foo"));
+        drop(dir);
+        assert!(!path.exists());
+
+        let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+        let path = dir.path().to_path_buf();
+        check!(dir.close());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn walk_dir_depth_first() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        check!(fs::create_dir_all(&root.join("a").join("b")));
+        check!(fs::create_dir_all(&root.join("c")));
+        check!(File::create(&root.join("a").join("b").join("f")));
+        check!(File::create(&root.join("a").join("g")));
+        check!(File::create(&root.join("c").join("h")));
+
+        let paths = check!(fs::walk_dir(&root)).map(|e| check!(e).path()).collect::<Vec<_>>();
+        assert_eq!(paths.len(), 6);
+        // Every directory is directly followed by all of its contents.
+        for (i, path) in paths.iter().enumerate() {
+            let contents = paths.iter().filter(|p| p.starts_with(path) && *p != path).count();
+            assert!(paths[i + 1..i + 1 + contents].iter().all(|p| p.starts_with(path)),
+                    "{:?} not grouped in {:?}", path, paths);
+        }
+    }
+
+    #[test]
+    fn walk_dir_symlink_loop() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        let link = root.join("a").join("loop");
+        check!(fs::create_dir_all(&root.join("a")));
+        check!(symlink_junction(&root, &link));
+
+        let paths = check!(fs::walk_dir(&root)).map(|e| check!(e).path()).collect::<Vec<_>>();
+        assert_eq!(paths, [root.join("a"), link.clone()]);
+
+        let results = check!(fs::walk_dir(&root)).follow_links(true).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    }
+
     #[test]
     fn recursive_rmdir_of_symlink() {
         // test we do not recursively delete a symlink but only dirs.
@@ -3312,6 +4123,76 @@ mod tests {
         assert!(d.exists());
     }
 
+    // Solaris emulates these locks with `fcntl`, whose locks belong to the
+    // process rather than to the file descriptor, so two descriptors opened
+    // by the same process never conflict there.
+    #[test]
+    #[cfg(not(target_os = "solaris"))]
+    fn file_lock_exclusive() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock_exclusive");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_exclusive());
+        assert_eq!(f2.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(f2.try_lock_shared().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f1.unlock());
+        check!(f2.try_lock_exclusive());
+        assert_eq!(f1.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f2.unlock());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "solaris"))]
+    fn file_lock_shared() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock_shared");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(File::open(&path));
+
+        check!(f1.lock_shared());
+        check!(f2.try_lock_shared());
+        assert_eq!(f2.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f1.unlock());
+        check!(f2.unlock());
+        check!(f1.try_lock_exclusive());
+        check!(f1.unlock());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "solaris"))]
+    fn file_lock_released_on_close() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock_close");
+        let f1 = check!(File::create(&path));
+        check!(f1.lock_exclusive());
+        drop(f1);
+        let f2 = check!(File::open(&path));
+        check!(f2.try_lock_exclusive());
+        check!(f2.unlock());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "solaris"))]
+    fn file_lock_blocking() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock_blocking");
+        let f1 = check!(File::create(&path));
+        check!(f1.lock_exclusive());
+        let t = {
+            let path = path.clone();
+            thread::spawn(move || {
+                let f2 = check!(File::open(&path));
+                check!(f2.lock_exclusive());
+                check!(f2.unlock());
+            })
+        };
+        thread::sleep(::time::Duration::from_millis(50));
+        check!(f1.unlock());
+        t.join().unwrap();
+    }
+
     #[test]
     fn metadata_access_times() {
         let tmpdir = tmpdir();
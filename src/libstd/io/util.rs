@@ -29,6 +29,18 @@ use mem;
 /// `write` returns an error. All instances of `ErrorKind::Interrupted` are
 /// handled by this function and the underlying operation is retried.
 ///
+/// # Platform-specific behavior
+///
+/// On Linux and Android, if both ends are unbuffered wrappers around file descriptors
+/// (such as [`File`], [`TcpStream`], [`UnixStream`] or the pipes of a child process), the
+/// data is moved by the kernel using `copy_file_range`, `sendfile` or `splice` where
+/// possible, without passing through userspace. This falls back to reading and writing
+/// whenever the kernel doesn't support those for the given descriptors.
+///
+/// [`File`]: ../fs/struct.File.html
+/// [`TcpStream`]: ../net/struct.TcpStream.html
+/// [`UnixStream`]: ../os/unix/net/struct.UnixStream.html
+///
 /// # Examples
 ///
 /// ```
@@ -47,6 +59,21 @@ use mem;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
     where R: Read, W: Write
+{
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        ::sys::kernel_copy::copy_spec(reader, writer)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        generic_copy(reader, writer)
+    }
+}
+
+/// The buffered read/write loop behind `copy`, for when there is nothing better to do.
+pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W)
+                                                 -> io::Result<u64>
+    where R: Read, W: Write
 {
     let mut buf = unsafe {
         let mut buf: [u8; super::DEFAULT_BUF_SIZE] = mem::uninitialized();
@@ -48,6 +48,10 @@ pub use self::addr::{SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
 pub use self::tcp::{TcpStream, TcpListener, Incoming};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::udp::UdpSocket;
+#[unstable(feature = "socket_builder", issue = "0")]
+pub use self::tcp::TcpSocket;
+#[unstable(feature = "socket_builder", issue = "0")]
+pub use self::udp::UdpSocketBuilder;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::parser::AddrParseError;
 
@@ -12,7 +12,7 @@ use io::prelude::*;
 
 use fmt;
 use io::{self, Initializer};
-use net::{ToSocketAddrs, SocketAddr, Shutdown};
+use net::{ToSocketAddrs, SocketAddr, Shutdown, Ipv4Addr, Ipv6Addr};
 use sys_common::net as net_imp;
 use sys_common::{AsInner, FromInner, IntoInner};
 use time::Duration;
@@ -916,6 +916,222 @@ impl fmt::Debug for TcpListener {
     }
 }
 
+/// A TCP socket which has not yet been bound, connected or turned into a
+/// listener.
+///
+/// Unlike [`TcpListener::bind`] and [`TcpStream::connect`], which create and
+/// set up a socket in one go, a `TcpSocket` allows socket options that only
+/// take effect before the socket is bound or connected to be set first. It
+/// is turned into a [`TcpListener`] with [`listen`], or into a [`TcpStream`]
+/// with [`connect`].
+///
+/// [`TcpListener::bind`]: struct.TcpListener.html#method.bind
+/// [`TcpStream::connect`]: struct.TcpStream.html#method.connect
+/// [`TcpListener`]: struct.TcpListener.html
+/// [`TcpStream`]: struct.TcpStream.html
+/// [`listen`]: #method.listen
+/// [`connect`]: #method.connect
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(socket_builder)]
+///
+/// use std::net::TcpSocket;
+///
+/// fn main() -> std::io::Result<()> {
+///     let socket = TcpSocket::new_v4()?;
+///     socket.set_reuseaddr(true)?;
+///     socket.bind("127.0.0.1:8080".parse().unwrap())?;
+///     let listener = socket.listen(1024)?;
+///
+///     for stream in listener.incoming() {
+///         // ...
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "socket_builder", issue = "0")]
+pub struct TcpSocket(net_imp::TcpSocket);
+
+impl TcpSocket {
+    /// Creates a new IPv4 TCP socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        let addr = SocketAddr::from((Ipv4Addr::new(0, 0, 0, 0), 0));
+        net_imp::TcpSocket::new(&addr).map(TcpSocket)
+    }
+
+    /// Creates a new IPv6 TCP socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        let addr = SocketAddr::from((Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 0));
+        net_imp::TcpSocket::new(&addr).map(TcpSocket)
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// This allows the socket to be bound to an address which is still in
+    /// the `TIME_WAIT` state, for example after a server has been restarted.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.0.set_reuseaddr(reuseaddr)
+    }
+
+    /// Gets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// For more information about this option, see [`set_reuseaddr`].
+    ///
+    /// [`set_reuseaddr`]: #method.set_reuseaddr
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.0.reuseaddr()
+    }
+
+    /// Sets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// This allows several sockets to be bound to the same address and port.
+    /// An error is returned on platforms which do not support this option.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.0.set_reuseport(reuseport)
+    }
+
+    /// Sets the size of the send buffer, the `SO_SNDBUF` option.
+    ///
+    /// The operating system is free to adjust the requested size.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.0.set_send_buffer_size(size)
+    }
+
+    /// Gets the size of the send buffer.
+    ///
+    /// For more information about this option, see [`set_send_buffer_size`].
+    ///
+    /// [`set_send_buffer_size`]: #method.set_send_buffer_size
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.0.send_buffer_size()
+    }
+
+    /// Sets the size of the receive buffer, the `SO_RCVBUF` option.
+    ///
+    /// The operating system is free to adjust the requested size.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.0.set_recv_buffer_size(size)
+    }
+
+    /// Gets the size of the receive buffer.
+    ///
+    /// For more information about this option, see [`set_recv_buffer_size`].
+    ///
+    /// [`set_recv_buffer_size`]: #method.set_recv_buffer_size
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.0.recv_buffer_size()
+    }
+
+    /// Enables or disables TCP keepalive.
+    ///
+    /// If `keepalive` is `Some`, keepalive probes are enabled and, where the
+    /// platform supports it, the connection is probed after it has been idle
+    /// for the given duration. If `None`, keepalive is disabled.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_keepalive(&self, keepalive: Option<Duration>) -> io::Result<()> {
+        self.0.set_keepalive(keepalive)
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// See [`TcpStream::set_nodelay`] for more information.
+    ///
+    /// [`TcpStream::set_nodelay`]: struct.TcpStream.html#method.set_nodelay
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.0.set_nodelay(nodelay)
+    }
+
+    /// Sets the value of the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// If this is set to `true`, an IPv6 socket only communicates over IPv6.
+    /// Otherwise it may also be used to communicate with IPv4 addresses
+    /// mapped into the IPv6 address space. The default is platform specific.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        self.0.set_only_v6(only_v6)
+    }
+
+    /// Binds the socket to the given local address.
+    ///
+    /// This is needed before [`listen`], and may be used before [`connect`]
+    /// to choose the local address of an outgoing connection.
+    ///
+    /// [`listen`]: #method.listen
+    /// [`connect`]: #method.connect
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.0.bind(&addr)
+    }
+
+    /// Returns the local socket address of this socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Starts listening for incoming connections, turning the socket into a
+    /// [`TcpListener`].
+    ///
+    /// `backlog` is the maximum number of pending connections; it may be
+    /// capped by the operating system.
+    ///
+    /// [`TcpListener`]: struct.TcpListener.html
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        self.0.listen(backlog).map(TcpListener)
+    }
+
+    /// Opens a connection to a remote host, turning the socket into a
+    /// [`TcpStream`].
+    ///
+    /// [`TcpStream`]: struct.TcpStream.html
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        self.0.connect(&addr).map(TcpStream)
+    }
+
+    /// Opens a connection to a remote host with a timeout.
+    ///
+    /// See [`TcpStream::connect_timeout`] for more information.
+    ///
+    /// [`TcpStream::connect_timeout`]: struct.TcpStream.html#method.connect_timeout
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn connect_timeout(self, addr: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        self.0.connect_timeout(&addr, timeout).map(TcpStream)
+    }
+}
+
+impl AsInner<net_imp::TcpSocket> for TcpSocket {
+    fn as_inner(&self) -> &net_imp::TcpSocket { &self.0 }
+}
+
+impl FromInner<net_imp::TcpSocket> for TcpSocket {
+    fn from_inner(inner: net_imp::TcpSocket) -> TcpSocket { TcpSocket(inner) }
+}
+
+impl IntoInner<net_imp::TcpSocket> for TcpSocket {
+    fn into_inner(self) -> net_imp::TcpSocket { self.0 }
+}
+
+#[unstable(feature = "socket_builder", issue = "0")]
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[cfg(all(test, not(any(target_os = "cloudabi", target_os = "emscripten"))))]
 mod tests {
     use io::ErrorKind;
@@ -1710,4 +1926,66 @@ mod tests {
         let addr = listener.local_addr().unwrap();
         TcpStream::connect_timeout(&addr, Duration::from_secs(2)).unwrap();
     }
+
+    fn new_socket(addr: &SocketAddr) -> TcpSocket {
+        if addr.is_ipv4() {
+            t!(TcpSocket::new_v4())
+        } else {
+            t!(TcpSocket::new_v6())
+        }
+    }
+
+    #[test]
+    fn socket_builder_listen() {
+        each_ip(&mut |addr| {
+            let socket = new_socket(&addr);
+            t!(socket.set_reuseaddr(true));
+            assert!(t!(socket.reuseaddr()));
+            t!(socket.bind(addr));
+            assert_eq!(t!(socket.local_addr()), addr);
+            let listener = t!(socket.listen(16));
+
+            let t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                t!(stream.write(&[99]));
+            });
+
+            let mut stream = t!(listener.accept()).0;
+            let mut buf = [0];
+            t!(stream.read(&mut buf));
+            assert_eq!(buf[0], 99);
+            t.join().unwrap();
+        })
+    }
+
+    #[test]
+    fn socket_builder_bind_before_connect() {
+        each_ip(&mut |addr| {
+            let listener = t!(TcpListener::bind(&addr));
+            let local = if addr.is_ipv4() { next_test_ip4() } else { next_test_ip6() };
+
+            let socket = new_socket(&local);
+            t!(socket.set_nodelay(true));
+            t!(socket.bind(local));
+            let stream = t!(socket.connect(addr));
+            assert_eq!(t!(stream.local_addr()), local);
+            assert!(t!(stream.nodelay()));
+
+            let (_, peer) = t!(listener.accept());
+            assert_eq!(peer, local);
+        })
+    }
+
+    #[test]
+    fn socket_builder_options() {
+        each_ip(&mut |addr| {
+            let socket = new_socket(&addr);
+            t!(socket.set_send_buffer_size(16 * 1024));
+            t!(socket.set_recv_buffer_size(16 * 1024));
+            assert!(t!(socket.send_buffer_size()) > 0);
+            assert!(t!(socket.recv_buffer_size()) > 0);
+            t!(socket.set_keepalive(Some(Duration::from_secs(60))));
+            t!(socket.set_keepalive(None));
+        })
+    }
 }
@@ -816,6 +816,149 @@ impl fmt::Debug for UdpSocket {
     }
 }
 
+/// A UDP socket which has not yet been bound.
+///
+/// Unlike [`UdpSocket::bind`], which creates and binds a socket in one go,
+/// a `UdpSocketBuilder` allows socket options that only take effect before
+/// the socket is bound to be set first. It is turned into a [`UdpSocket`]
+/// with [`bind`].
+///
+/// [`UdpSocket::bind`]: struct.UdpSocket.html#method.bind
+/// [`UdpSocket`]: struct.UdpSocket.html
+/// [`bind`]: #method.bind
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(socket_builder)]
+///
+/// use std::net::UdpSocketBuilder;
+///
+/// fn main() -> std::io::Result<()> {
+///     let builder = UdpSocketBuilder::new_v4()?;
+///     builder.set_reuseaddr(true)?;
+///     builder.set_recv_buffer_size(1 << 20)?;
+///     let socket = builder.bind("0.0.0.0:34254".parse().unwrap())?;
+///
+///     let mut buf = [0; 10];
+///     let (amt, src) = socket.recv_from(&mut buf)?;
+///     socket.send_to(&buf[..amt], &src)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "socket_builder", issue = "0")]
+pub struct UdpSocketBuilder(net_imp::UdpSocketBuilder);
+
+impl UdpSocketBuilder {
+    /// Creates a new IPv4 UDP socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn new_v4() -> io::Result<UdpSocketBuilder> {
+        let addr = SocketAddr::from((Ipv4Addr::new(0, 0, 0, 0), 0));
+        net_imp::UdpSocketBuilder::new(&addr).map(UdpSocketBuilder)
+    }
+
+    /// Creates a new IPv6 UDP socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn new_v6() -> io::Result<UdpSocketBuilder> {
+        let addr = SocketAddr::from((Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 0));
+        net_imp::UdpSocketBuilder::new(&addr).map(UdpSocketBuilder)
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on this socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.0.set_reuseaddr(reuseaddr)
+    }
+
+    /// Gets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// For more information about this option, see [`set_reuseaddr`].
+    ///
+    /// [`set_reuseaddr`]: #method.set_reuseaddr
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.0.reuseaddr()
+    }
+
+    /// Sets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// An error is returned on platforms which do not support this option.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.0.set_reuseport(reuseport)
+    }
+
+    /// Sets the size of the send buffer, the `SO_SNDBUF` option.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.0.set_send_buffer_size(size)
+    }
+
+    /// Gets the size of the send buffer.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        self.0.send_buffer_size()
+    }
+
+    /// Sets the size of the receive buffer, the `SO_RCVBUF` option.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        self.0.set_recv_buffer_size(size)
+    }
+
+    /// Gets the size of the receive buffer.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        self.0.recv_buffer_size()
+    }
+
+    /// Sets the value of the `SO_BROADCAST` option on this socket.
+    ///
+    /// See [`UdpSocket::set_broadcast`] for more information.
+    ///
+    /// [`UdpSocket::set_broadcast`]: struct.UdpSocket.html#method.set_broadcast
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_broadcast(&self, broadcast: bool) -> io::Result<()> {
+        self.0.set_broadcast(broadcast)
+    }
+
+    /// Sets the value of the `IPV6_V6ONLY` option on this socket.
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        self.0.set_only_v6(only_v6)
+    }
+
+    /// Binds the socket to the given address, turning it into a
+    /// [`UdpSocket`].
+    ///
+    /// [`UdpSocket`]: struct.UdpSocket.html
+    #[unstable(feature = "socket_builder", issue = "0")]
+    pub fn bind(self, addr: SocketAddr) -> io::Result<UdpSocket> {
+        self.0.bind(&addr).map(UdpSocket)
+    }
+}
+
+impl AsInner<net_imp::UdpSocketBuilder> for UdpSocketBuilder {
+    fn as_inner(&self) -> &net_imp::UdpSocketBuilder { &self.0 }
+}
+
+impl FromInner<net_imp::UdpSocketBuilder> for UdpSocketBuilder {
+    fn from_inner(inner: net_imp::UdpSocketBuilder) -> UdpSocketBuilder {
+        UdpSocketBuilder(inner)
+    }
+}
+
+impl IntoInner<net_imp::UdpSocketBuilder> for UdpSocketBuilder {
+    fn into_inner(self) -> net_imp::UdpSocketBuilder { self.0 }
+}
+
+#[unstable(feature = "socket_builder", issue = "0")]
+impl fmt::Debug for UdpSocketBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[cfg(all(test, not(any(target_os = "cloudabi", target_os = "emscripten"))))]
 mod tests {
     use io::ErrorKind;
@@ -1160,4 +1303,30 @@ mod tests {
             }
         })
     }
+
+    #[test]
+    fn socket_builder_bind() {
+        each_ip(&mut |server_ip, client_ip| {
+            let builder = t!(if server_ip.is_ipv4() {
+                UdpSocketBuilder::new_v4()
+            } else {
+                UdpSocketBuilder::new_v6()
+            });
+            t!(builder.set_reuseaddr(true));
+            assert!(t!(builder.reuseaddr()));
+            t!(builder.set_recv_buffer_size(16 * 1024));
+            assert!(t!(builder.recv_buffer_size()) > 0);
+            let server = t!(builder.bind(server_ip));
+            assert_eq!(t!(server.local_addr()), server_ip);
+
+            let client = t!(UdpSocket::bind(&client_ip));
+            t!(client.send_to(&[99], &server_ip));
+
+            let mut buf = [0];
+            let (amt, src) = t!(server.recv_from(&mut buf));
+            assert_eq!(amt, 1);
+            assert_eq!(buf[0], 99);
+            assert_eq!(src, client_ip);
+        })
+    }
 }
@@ -91,6 +91,7 @@ use sync::Arc;
 
 use ffi::{OsStr, OsString};
 
+use sys;
 use sys::path::{is_sep_byte, is_verbatim_sep, MAIN_SEP_STR, parse_prefix};
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -1976,6 +1977,121 @@ impl Path {
             .ok_or(StripPrefixError(()))
     }
 
+    /// Normalizes the path lexically, without touching the filesystem.
+    ///
+    /// `.` components and repeated separators are removed, and each `..`
+    /// component removes the normal component preceding it. A `..` directly
+    /// after the root is dropped, as the parent of the root is the root
+    /// itself. Leading `..` components of a relative path, including a path
+    /// relative to a Windows drive such as `C:..\foo`, are kept.
+    ///
+    /// Windows verbatim paths (those starting with `\\?\`) are returned
+    /// unchanged, as the system does not interpret `.` and `..` in them.
+    ///
+    /// Unlike [`canonicalize`], this does not resolve symbolic links, so the
+    /// result may point to a different file than `self` if the path goes
+    /// through a symbolic link followed by `..`.
+    ///
+    /// [`canonicalize`]: #method.canonicalize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_lexical)]
+    ///
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// assert_eq!(Path::new("a/./b/../c").normalize_lexically(), PathBuf::from("a/c"));
+    /// assert_eq!(Path::new("../a/../../b").normalize_lexically(), PathBuf::from("../../b"));
+    /// assert_eq!(Path::new("/../a/b/..").normalize_lexically(), PathBuf::from("/a"));
+    /// assert_eq!(Path::new("a/..").normalize_lexically(), PathBuf::new());
+    /// ```
+    #[unstable(feature = "path_lexical", issue = "0")]
+    pub fn normalize_lexically(&self) -> PathBuf {
+        if self.components().prefix_verbatim() {
+            return self.to_path_buf();
+        }
+
+        let mut stack: Vec<Component> = Vec::new();
+        for component in self.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => match stack.last() {
+                    Some(&Component::Normal(_)) => { stack.pop(); }
+                    Some(&Component::RootDir) => {}
+                    _ => stack.push(component),
+                },
+                _ => stack.push(component),
+            }
+        }
+        stack.iter().collect()
+    }
+
+    /// Returns a relative path which, when joined onto `base`, refers to the
+    /// same location as `self`.
+    ///
+    /// Both paths are first normalized with [`normalize_lexically`], so the
+    /// result may contain `..` components, unlike with [`strip_prefix`]. The
+    /// filesystem is never accessed and components are compared exactly,
+    /// which means that on Windows the comparison is case-sensitive except
+    /// for drive letters.
+    ///
+    /// Returns [`None`] if there is no such path: if only one of the paths
+    /// has a root, if their Windows prefixes differ, or if `base` still
+    /// contains `..` components which can't be undone after normalization.
+    ///
+    /// [`normalize_lexically`]: #method.normalize_lexically
+    /// [`strip_prefix`]: #method.strip_prefix
+    /// [`None`]: ../../std/option/enum.Option.html#variant.None
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(path_lexical)]
+    ///
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let path = Path::new("/usr/lib/rustlib");
+    ///
+    /// assert_eq!(path.relative_to("/usr"), Some(PathBuf::from("lib/rustlib")));
+    /// assert_eq!(path.relative_to("/usr/share/doc"), Some(PathBuf::from("../../lib/rustlib")));
+    /// assert_eq!(path.relative_to("/usr/lib/rustlib"), Some(PathBuf::new()));
+    /// assert_eq!(path.relative_to("usr"), None);
+    /// ```
+    #[unstable(feature = "path_lexical", issue = "0")]
+    pub fn relative_to<P: AsRef<Path>>(&self, base: P) -> Option<PathBuf> {
+        self._relative_to(base.as_ref())
+    }
+
+    fn _relative_to(&self, base: &Path) -> Option<PathBuf> {
+        let path = self.normalize_lexically();
+        let base = base.normalize_lexically();
+        let mut path_comps = path.components().peekable();
+        let mut base_comps = base.components().peekable();
+
+        // Skip the common leading components.
+        while path_comps.peek().is_some() && path_comps.peek() == base_comps.peek() {
+            path_comps.next();
+            base_comps.next();
+        }
+
+        let mut relative = PathBuf::new();
+        for component in base_comps {
+            match component {
+                Component::Normal(_) => relative.push(".."),
+                // A different prefix or root, or a `..` of `base` whose
+                // parent is unknown.
+                _ => return None,
+            }
+        }
+        match path_comps.peek() {
+            Some(&Component::Prefix(_)) | Some(&Component::RootDir) => return None,
+            _ => {}
+        }
+        relative.extend(path_comps);
+        Some(relative)
+    }
+
     /// Determines whether `base` is a prefix of `self`.
     ///
     /// Only considers whole path components to match.
@@ -2688,6 +2804,56 @@ impl_cmp_os_str!(Cow<'a, Path>, OsStr);
 impl_cmp_os_str!(Cow<'a, Path>, &'b OsStr);
 impl_cmp_os_str!(Cow<'a, Path>, OsString);
 
+/// Makes a path absolute without accessing the filesystem.
+///
+/// A relative path is joined onto the current directory. Unlike
+/// [`canonicalize`], symbolic links are not resolved and the path doesn't
+/// need to exist.
+///
+/// # Platform-specific behavior
+///
+/// On Unix, `.` components and repeated separators are removed, but `..`
+/// components are kept, since `a/link/..` is not necessarily the same as `a`
+/// if `link` is a symbolic link. Use [`Path::normalize_lexically`] on the
+/// result to remove them as well.
+///
+/// On Windows, this uses [`GetFullPathNameW`], which also removes `..`
+/// components and handles drive-relative paths such as `C:foo` using the
+/// current directory of that drive.
+///
+/// # Errors
+///
+/// Returns an error if `path` is empty, or if the current directory can't
+/// be determined.
+///
+/// [`canonicalize`]: ../fs/fn.canonicalize.html
+/// [`Path::normalize_lexically`]: struct.Path.html#method.normalize_lexically
+/// [`GetFullPathNameW`]: https://docs.microsoft.com/en-us/windows/desktop/api/fileapi/nf-fileapi-getfullpathnamew
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(path_lexical)]
+///
+/// use std::path::{self, Path};
+///
+/// fn main() -> std::io::Result<()> {
+///     let path = path::absolute("foo/./bar")?;
+///     assert!(path.is_absolute());
+///     assert!(path.ends_with(Path::new("foo/bar")));
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "path_lexical", issue = "0")]
+pub fn absolute<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "cannot make an empty path absolute"));
+    }
+    sys::path::absolute(path)
+}
+
 #[stable(since = "1.7.0", feature = "strip_prefix")]
 impl fmt::Display for StripPrefixError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -4126,4 +4292,78 @@ mod tests {
         assert_eq!(&*rc2, path);
         assert_eq!(&*arc2, path);
     }
+
+    #[test]
+    fn test_normalize_lexically() {
+        macro_rules! tn(
+            ($path:expr, $expected:expr) => ({
+                let actual = Path::new($path).normalize_lexically();
+                assert!(actual == Path::new($expected),
+                        "normalizing {:?}: Expected {:?}, found {:?}",
+                        $path, $expected, actual);
+            })
+        );
+
+        tn!("", "");
+        tn!(".", "");
+        tn!("./a", "a");
+        tn!("a//b/", "a/b");
+        tn!("a/./b/../c", "a/c");
+        tn!("a/b/../..", "");
+        tn!("a/../../b", "../b");
+        tn!("../../a", "../../a");
+        tn!("/..", "/");
+        tn!("/a/../../b/.", "/b");
+
+        if cfg!(windows) {
+            tn!(r"C:\a\..\..\b", r"C:\b");
+            tn!(r"C:a\..\..", r"C:..");
+            tn!(r"\\server\share\a\..\..", r"\\server\share\");
+            tn!(r"\\?\C:\a\..\b", r"\\?\C:\a\..\b");
+        }
+    }
+
+    #[test]
+    fn test_relative_to() {
+        macro_rules! tr(
+            ($path:expr, $base:expr, $expected:expr) => ({
+                let actual = Path::new($path).relative_to($base);
+                let expected: Option<&str> = $expected;
+                assert!(actual.as_ref().map(|p| &**p) == expected.map(Path::new),
+                        "{:?} relative to {:?}: Expected {:?}, found {:?}",
+                        $path, $base, expected, actual);
+            })
+        );
+
+        tr!("/a/b/c", "/a/b", Some("c"));
+        tr!("/a/b/c", "/a/b/c", Some(""));
+        tr!("/a/b/c", "/a/d/e", Some("../../b/c"));
+        tr!("/a", "/a/b/c", Some("../.."));
+        tr!("/a/./b/../c", "/a/c/d/..", Some(""));
+        tr!("a/b", "a/c", Some("../b"));
+        tr!("a", "", Some("a"));
+        tr!("../a", "b", Some("../../a"));
+        tr!("../a", "../b", Some("../a"));
+        tr!("a", "../b", None);
+        tr!("/a", "a", None);
+        tr!("a", "/a", None);
+
+        if cfg!(windows) {
+            tr!(r"C:\a\b", r"c:\a\c", Some(r"..\b"));
+            tr!(r"C:\a", r"D:\a", None);
+            tr!(r"C:a", r"C:\a", None);
+        }
+    }
+
+    #[test]
+    fn test_absolute() {
+        assert!(absolute("").is_err());
+
+        let path = absolute("a/./b").unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("a/b"));
+
+        let root = if cfg!(windows) { r"C:\a\b" } else { "/a/b" };
+        assert_eq!(absolute(root).unwrap(), Path::new(root));
+    }
 }
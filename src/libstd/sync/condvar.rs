@@ -88,10 +88,12 @@ impl WaitTimeoutResult {
 /// Functions in this module will block the current **thread** of execution and
 /// are bindings to system-provided condition variables where possible. Note
 /// that this module places one additional restriction over the system condition
-/// variables: each condvar can be used with precisely one mutex at runtime. Any
-/// attempt to use multiple mutexes on the same condition variable will result
-/// in a runtime panic. If this is not desired, then the unsafe primitives in
-/// `sys` do not have this restriction but may result in undefined behavior.
+/// variables: on most platforms each condvar can be used with precisely one
+/// mutex at runtime. Any attempt to use multiple mutexes on the same condition
+/// variable will result in a runtime panic. If this is not desired, then the
+/// unsafe primitives in `sys` do not have this restriction but may result in
+/// undefined behavior. On Linux, condition variables are built on futexes and
+/// may be used with any number of mutexes.
 ///
 /// # Examples
 ///
@@ -120,7 +122,7 @@ impl WaitTimeoutResult {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Condvar {
-    inner: Box<sys::Condvar>,
+    inner: sys::Condvar,
     mutex: AtomicUsize,
 }
 
@@ -137,14 +139,24 @@ impl Condvar {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Condvar {
-        let mut c = Condvar {
-            inner: box sys::Condvar::new(),
+        Condvar {
+            inner: sys::Condvar::new(),
+            mutex: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a new condition variable in a constant context, so that it
+    /// can be used to initialize a `static`.
+    ///
+    /// This is crate-private: it is only available on platforms where
+    /// condition variables don't need to be heap allocated.
+    #[cfg(target_os = "linux")]
+    #[allow(dead_code)]
+    pub(crate) const fn const_new() -> Condvar {
+        Condvar {
+            inner: sys::Condvar::new_const(),
             mutex: AtomicUsize::new(0),
-        };
-        unsafe {
-            c.inner.init();
         }
-        c
     }
 
     /// Blocks the current thread until this condition variable receives a
@@ -169,10 +181,11 @@ impl Condvar {
     ///
     /// # Panics
     ///
-    /// This function will [`panic!`] if it is used with more than one mutex
-    /// over time. Each condition variable is dynamically bound to exactly one
-    /// mutex to ensure defined behavior across platforms. If this functionality
-    /// is not desired, then unsafe primitives in `sys` are provided.
+    /// Except on Linux, this function will [`panic!`] if it is used with more
+    /// than one mutex over time. Each condition variable is dynamically bound to
+    /// exactly one mutex where the system condition variables require it. If
+    /// this functionality is not desired, then unsafe primitives in `sys` are
+    /// provided.
     ///
     /// [`notify_one`]: #method.notify_one
     /// [`notify_all`]: #method.notify_all
@@ -578,7 +591,8 @@ impl Condvar {
         unsafe { self.inner.notify_all() }
     }
 
-    fn verify(&self, mutex: &sys_mutex::Mutex) {
+    #[cfg(not(target_os = "linux"))]
+    fn verify(&self, mutex: &sys_mutex::MovableMutex) {
         let addr = mutex as *const _ as usize;
         match self.mutex.compare_and_swap(0, addr, Ordering::SeqCst) {
             // If we got out 0, then we have successfully bound the mutex to
@@ -595,6 +609,12 @@ impl Condvar {
                          mutexes"),
         }
     }
+
+    // Futex-based condition variables may be used with any number of
+    // mutexes, which may also be moved between waits, so there is nothing to
+    // check.
+    #[cfg(target_os = "linux")]
+    fn verify(&self, _mutex: &sys_mutex::MovableMutex) {}
 }
 
 #[stable(feature = "std_debug", since = "1.16.0")]
@@ -636,6 +656,16 @@ mod tests {
         c.notify_all();
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn smoke_const() {
+        static M: Mutex<bool> = Mutex::const_new(false);
+        static C: Condvar = Condvar::const_new();
+        C.notify_one();
+        let g = C.wait_timeout(M.lock().unwrap(), Duration::from_millis(1)).unwrap().0;
+        assert!(!*g);
+    }
+
     #[test]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn notify_one() {
@@ -819,6 +849,7 @@ mod tests {
     #[test]
     #[should_panic]
     #[cfg_attr(target_os = "emscripten", ignore)]
+    #[cfg(not(target_os = "linux"))] // futex condvars accept several mutexes
     fn two_mutexes() {
         let m = Arc::new(Mutex::new(()));
         let m2 = m.clone();
@@ -836,4 +867,17 @@ mod tests {
         let m = Mutex::new(());
         let _ = c.wait(m.lock().unwrap()).unwrap();
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn two_mutexes_futex() {
+        let c = Condvar::new();
+        let m = Mutex::new(());
+        let m2 = Mutex::new(());
+
+        let g = c.wait_timeout(m.lock().unwrap(), Duration::from_millis(1)).unwrap().0;
+        drop(g);
+        let g = c.wait_timeout(m2.lock().unwrap(), Duration::from_millis(1)).unwrap().0;
+        drop(g);
+    }
 }
@@ -30,15 +30,19 @@ pub use self::condvar::{Condvar, WaitTimeoutResult};
 pub use self::mutex::{Mutex, MutexGuard};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::once::{Once, OnceState, ONCE_INIT};
+#[unstable(feature = "once_cell", issue = "0")]
+pub use self::once_cell::{Lazy, OnceCell};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use sys_common::poison::{PoisonError, TryLockError, TryLockResult, LockResult};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+pub mod mpmc;
 pub mod mpsc;
 
 mod barrier;
 mod condvar;
 mod mutex;
 mod once;
+mod once_cell;
 mod rwlock;
@@ -0,0 +1,300 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The channel shared by all senders and receivers.
+//!
+//! Unlike `mpsc`, there is a single implementation for every mode of
+//! operation, so a channel never has to be upgraded as handles are cloned or
+//! shared. All state lives behind one lock:
+//!
+//! * Buffered channels (bounded or not) keep messages in a queue. Blocked
+//!   operations register themselves as waiters and are notified when the
+//!   queue changes, after which they simply retry.
+//! * Zero-capacity channels never queue anything. A blocked sender offers
+//!   its message in its waiter entry and a blocked receiver waits for a
+//!   message to be handed to its context; the compare-and-swap which selects
+//!   the other side's context decides who owns the message.
+
+use collections::VecDeque;
+use mem;
+use ptr;
+use sync::{Mutex, MutexGuard};
+use sync::mpsc::{RecvTimeoutError, TryRecvError, TrySendError};
+use super::SendTimeoutError;
+use super::context::{Context, Waiters, ABORTED, FIRST_OPER};
+use time::Instant;
+
+pub struct Channel<T> {
+    state: Mutex<State<T>>,
+    cap: Option<usize>,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    // The number of live senders and receivers. The channel is disconnected
+    // once either drops to zero.
+    senders: usize,
+    receivers: usize,
+    send_waiters: Waiters,
+    recv_waiters: Waiters,
+}
+
+impl<T> Channel<T> {
+    /// Creates a channel holding at most `cap` messages, or an unbounded one
+    /// if `cap` is `None`.
+    pub fn new(cap: Option<usize>) -> Channel<T> {
+        let queue = match cap {
+            Some(cap) => VecDeque::with_capacity(cap),
+            None => VecDeque::new(),
+        };
+        Channel {
+            state: Mutex::new(State {
+                queue,
+                senders: 1,
+                receivers: 1,
+                send_waiters: Waiters::new(),
+                recv_waiters: Waiters::new(),
+            }),
+            cap,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<State<T>> {
+        // Nothing panics while the lock is held, so poisoning can't happen.
+        self.state.lock().unwrap()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.cap == Some(0)
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.cap
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        match self.cap {
+            Some(cap) => self.lock().queue.len() >= cap,
+            None => false,
+        }
+    }
+
+    pub fn acquire_sender(&self) {
+        self.lock().senders += 1;
+    }
+
+    pub fn acquire_receiver(&self) {
+        self.lock().receivers += 1;
+    }
+
+    /// Releases a sender, waking up blocked receivers if it was the last one.
+    pub fn release_sender(&self) {
+        let mut state = self.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.recv_waiters.disconnect();
+        }
+    }
+
+    /// Releases a receiver. Once the last one is gone, blocked senders are
+    /// woken up and the messages which can no longer be received are dropped.
+    pub fn release_receiver(&self) {
+        let queue = {
+            let mut state = self.lock();
+            state.receivers -= 1;
+            if state.receivers != 0 {
+                return;
+            }
+            state.send_waiters.disconnect();
+            mem::replace(&mut state.queue, VecDeque::new())
+        };
+        // Run the destructors outside of the lock.
+        drop(queue);
+    }
+
+    fn try_send_locked(&self, state: &mut State<T>, msg: T) -> Result<(), TrySendError<T>> {
+        if state.receivers == 0 {
+            return Err(TrySendError::Disconnected(msg));
+        }
+        match self.cap {
+            Some(0) => match state.recv_waiters.try_select() {
+                Some(entry) => {
+                    entry.cx.store_packet(Box::into_raw(Box::new(msg)) as *mut u8);
+                    entry.cx.unpark();
+                    Ok(())
+                }
+                None => Err(TrySendError::Full(msg)),
+            },
+            Some(cap) if state.queue.len() >= cap => Err(TrySendError::Full(msg)),
+            _ => {
+                state.queue.push_back(msg);
+                state.recv_waiters.notify_one();
+                Ok(())
+            }
+        }
+    }
+
+    fn try_recv_locked(&self, state: &mut State<T>) -> Result<T, TryRecvError> {
+        let msg = if self.is_zero() {
+            state.send_waiters.try_select().map(|entry| {
+                let msg = unsafe { *Box::from_raw(entry.packet as *mut T) };
+                entry.cx.unpark();
+                msg
+            })
+        } else {
+            let msg = state.queue.pop_front();
+            if msg.is_some() && self.cap.is_some() {
+                state.send_waiters.notify_one();
+            }
+            msg
+        };
+        match msg {
+            Some(msg) => Ok(msg),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.lock();
+        self.try_send_locked(&mut state, msg)
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.lock();
+        self.try_recv_locked(&mut state)
+    }
+
+    /// Sends a message, blocking until `deadline` if the channel is full.
+    pub fn send(&self, msg: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        // `None` while the message is offered to receivers through our waiter
+        // entry, which only happens on zero-capacity channels.
+        let mut msg = Some(msg);
+        loop {
+            let cx = Context::new();
+            let mut packet: *mut T = ptr::null_mut();
+            {
+                let mut state = self.lock();
+                let m = match self.try_send_locked(&mut state, msg.take().unwrap()) {
+                    Ok(()) => return Ok(()),
+                    Err(TrySendError::Disconnected(m)) => {
+                        return Err(SendTimeoutError::Disconnected(m))
+                    }
+                    Err(TrySendError::Full(m)) => m,
+                };
+                if let Some(deadline) = deadline {
+                    if Instant::now() >= deadline {
+                        return Err(SendTimeoutError::Timeout(m));
+                    }
+                }
+                if self.is_zero() {
+                    packet = Box::into_raw(Box::new(m));
+                } else {
+                    msg = Some(m);
+                }
+                state.send_waiters.register(FIRST_OPER, packet as *mut u8, &cx);
+            }
+
+            let sel = cx.wait_until(deadline);
+            if sel == FIRST_OPER {
+                if self.is_zero() {
+                    // A receiver took the message out of our entry.
+                    return Ok(());
+                }
+                // There is space in the buffer now; try again.
+                continue;
+            }
+
+            // Nobody selected us, so a zero-capacity message is still ours.
+            self.lock().send_waiters.unregister(&cx);
+            if self.is_zero() {
+                msg = Some(unsafe { *Box::from_raw(packet) });
+            }
+            if sel == ABORTED {
+                return Err(SendTimeoutError::Timeout(msg.take().unwrap()));
+            }
+            // Disconnected: the next attempt reports it.
+        }
+    }
+
+    /// Receives a message, blocking until `deadline` if the channel is empty.
+    pub fn recv(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        loop {
+            let cx = Context::new();
+            {
+                let mut state = self.lock();
+                match self.try_recv_locked(&mut state) {
+                    Ok(msg) => return Ok(msg),
+                    Err(TryRecvError::Disconnected) => {
+                        return Err(RecvTimeoutError::Disconnected)
+                    }
+                    Err(TryRecvError::Empty) => {}
+                }
+                if let Some(deadline) = deadline {
+                    if Instant::now() >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                }
+                state.recv_waiters.register(FIRST_OPER, ptr::null_mut(), &cx);
+            }
+
+            let sel = cx.wait_until(deadline);
+            if sel == FIRST_OPER {
+                if let Some(msg) = self.take_packet(&cx) {
+                    return Ok(msg);
+                }
+                // A message was pushed; try to get it.
+                continue;
+            }
+
+            self.lock().recv_waiters.unregister(&cx);
+            if sel == ABORTED {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            // Disconnected: the next attempt reports it, after draining any
+            // messages which are still buffered.
+        }
+    }
+
+    /// Returns the message handed to a selected receiver context, which only
+    /// happens on zero-capacity channels.
+    pub fn take_packet(&self, cx: &Context) -> Option<T> {
+        if self.is_zero() {
+            Some(unsafe { *Box::from_raw(cx.wait_packet() as *mut T) })
+        } else {
+            None
+        }
+    }
+
+    /// Registers `cx` as a receiver blocked on this channel, unless a
+    /// receive operation could make progress right away, in which case
+    /// nothing is registered and `true` is returned.
+    pub fn register_recv(&self, oper: usize, cx: &Context) -> bool {
+        let mut state = self.lock();
+        let ready = if self.is_zero() {
+            !state.send_waiters.is_empty()
+        } else {
+            !state.queue.is_empty()
+        };
+        if ready || state.senders == 0 {
+            return true;
+        }
+        state.recv_waiters.register(oper, ptr::null_mut(), cx);
+        false
+    }
+
+    pub fn unregister_recv(&self, cx: &Context) {
+        self.lock().recv_waiters.unregister(cx);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Thread contexts used for blocking channel operations.
+//!
+//! A blocked operation registers a `Context` with every channel it is waiting
+//! on. The context's `select` word starts out as `WAITING` and is moved,
+//! exactly once, to the operation which woke it up (or to `ABORTED` /
+//! `DISCONNECTED`). Whoever wins that compare-and-swap owns the wakeup: for
+//! zero-capacity channels this is also what transfers ownership of the
+//! message being exchanged.
+
+use ptr;
+use sync::Arc;
+use sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use thread::{self, Thread};
+use time::Instant;
+
+/// The context is still waiting to be selected.
+pub const WAITING: usize = 0;
+/// The operation timed out or gave up on being selected.
+pub const ABORTED: usize = 1;
+/// The channel was disconnected while the context was waiting.
+pub const DISCONNECTED: usize = 2;
+/// The value used for the first registered operation; operation `i` of a
+/// select uses `FIRST_OPER + i`.
+pub const FIRST_OPER: usize = 3;
+
+/// A blocked thread, waiting to be selected by some operation.
+#[derive(Clone)]
+pub struct Context {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    select: AtomicUsize,
+    // A message handed over by the selecting thread, for zero-capacity
+    // channels. Written only after winning the `select` CAS.
+    packet: AtomicPtr<u8>,
+    thread: Thread,
+}
+
+impl Context {
+    /// Creates a new context for the current thread.
+    pub fn new() -> Context {
+        Context {
+            inner: Arc::new(Inner {
+                select: AtomicUsize::new(WAITING),
+                packet: AtomicPtr::new(ptr::null_mut()),
+                thread: thread::current(),
+            }),
+        }
+    }
+
+    /// Attempts to select an operation, returning the already selected one
+    /// on failure.
+    #[inline]
+    pub fn try_select(&self, oper: usize) -> Result<(), usize> {
+        match self.inner.select.compare_and_swap(WAITING, oper, Ordering::AcqRel) {
+            WAITING => Ok(()),
+            selected => Err(selected),
+        }
+    }
+
+    /// Returns the selected operation, or `WAITING`.
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.inner.select.load(Ordering::Acquire)
+    }
+
+    /// Hands a message over to the waiting thread.
+    ///
+    /// Must only be called by the thread which selected this context.
+    #[inline]
+    pub fn store_packet(&self, packet: *mut u8) {
+        self.inner.packet.store(packet, Ordering::Release);
+    }
+
+    /// Waits for the message handed over by the selecting thread.
+    pub fn wait_packet(&self) -> *mut u8 {
+        loop {
+            let packet = self.inner.packet.load(Ordering::Acquire);
+            if !packet.is_null() {
+                return packet;
+            }
+            // The selecting thread stores the packet right after winning
+            // the CAS, so this is only ever a very short wait.
+            thread::yield_now();
+        }
+    }
+
+    /// Blocks until the context is selected, or until `deadline` passes in
+    /// which case the context is aborted.
+    ///
+    /// Returns the selected operation.
+    pub fn wait_until(&self, deadline: Option<Instant>) -> usize {
+        loop {
+            let sel = self.selected();
+            if sel != WAITING {
+                return sel;
+            }
+
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return match self.try_select(ABORTED) {
+                            Ok(()) => ABORTED,
+                            Err(sel) => sel,
+                        };
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                None => thread::park(),
+            }
+        }
+    }
+
+    /// Wakes up the thread blocked on this context.
+    #[inline]
+    pub fn unpark(&self) {
+        self.inner.thread.unpark();
+    }
+
+    fn ptr_eq(&self, other: &Context) -> bool {
+        &*self.inner as *const Inner == &*other.inner as *const Inner
+    }
+}
+
+/// An operation blocked on a channel.
+pub struct Entry {
+    /// The operation to select the context with.
+    pub oper: usize,
+    /// The message offered by a blocked sender of a zero-capacity channel.
+    pub packet: *mut u8,
+    /// The blocked thread.
+    pub cx: Context,
+}
+
+// The packet is owned by the blocked sender until its context is selected, and
+// by the selecting thread afterwards; entries are only touched under the
+// channel's lock.
+unsafe impl Send for Entry {}
+
+/// A queue of operations blocked on one side of a channel.
+///
+/// Always accessed under the channel's lock.
+pub struct Waiters {
+    entries: Vec<Entry>,
+}
+
+impl Waiters {
+    pub fn new() -> Waiters {
+        Waiters { entries: Vec::new() }
+    }
+
+    /// Registers a blocked operation.
+    pub fn register(&mut self, oper: usize, packet: *mut u8, cx: &Context) {
+        self.entries.push(Entry { oper, packet, cx: cx.clone() });
+    }
+
+    /// Removes the operation registered with `cx`, if it is still queued.
+    pub fn unregister(&mut self, cx: &Context) -> Option<Entry> {
+        match self.entries.iter().position(|e| e.cx.ptr_eq(cx)) {
+            Some(i) => Some(self.entries.remove(i)),
+            None => None,
+        }
+    }
+
+    /// Selects the oldest operation which is still waiting and removes it
+    /// from the queue. The caller is responsible for waking it up.
+    pub fn try_select(&mut self) -> Option<Entry> {
+        // Contexts which fail to be selected were selected by another channel
+        // or timed out. Their owners will unregister them, so leave them.
+        match self.entries.iter().position(|e| e.cx.try_select(e.oper).is_ok()) {
+            Some(i) => Some(self.entries.remove(i)),
+            None => None,
+        }
+    }
+
+    /// Selects and wakes up one waiting operation, if any.
+    pub fn notify_one(&mut self) {
+        if let Some(entry) = self.try_select() {
+            entry.cx.unpark();
+        }
+    }
+
+    /// Wakes up every waiting operation with `DISCONNECTED`.
+    pub fn disconnect(&mut self) {
+        for entry in self.entries.drain(..) {
+            if entry.cx.try_select(DISCONNECTED).is_ok() {
+                entry.cx.unpark();
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,705 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Multi-producer, multi-consumer FIFO queue communication primitives.
+//!
+//! This module provides message-based communication over channels which,
+//! unlike those of [`mpsc`], may have any number of receivers as well as any
+//! number of senders. Both [`Sender`] and [`Receiver`] can be cloned and
+//! shared between threads, and each message is received by exactly one
+//! receiver.
+//!
+//! Channels come in three flavors:
+//!
+//! 1. An unbounded channel, created by [`unbounded`], on which sending never
+//!    blocks.
+//!
+//! 2. A bounded channel, created by [`bounded`] with a non-zero capacity, on
+//!    which sending blocks while the buffer is full.
+//!
+//! 3. A zero-capacity channel, created by `bounded(0)`, on which every send
+//!    blocks until a receiver takes the message, and vice versa.
+//!
+//! All blocking operations have non-blocking (`try_`) and timeout-based
+//! (`_timeout`) variants, and [`Select`] waits on several receivers at once.
+//!
+//! ## Disconnection
+//!
+//! Once every [`Sender`] of a channel has been dropped, receivers can still
+//! receive the messages left in the channel, after which receiving returns
+//! an error. Once every [`Receiver`] has been dropped, sending returns the
+//! message back in an error.
+//!
+//! [`mpsc`]: ../mpsc/index.html
+//! [`Sender`]: struct.Sender.html
+//! [`Receiver`]: struct.Receiver.html
+//! [`unbounded`]: fn.unbounded.html
+//! [`bounded`]: fn.bounded.html
+//! [`Select`]: struct.Select.html
+//!
+//! # Examples
+//!
+//! ```
+//! #![feature(mpmc_channel)]
+//!
+//! use std::sync::mpmc::bounded;
+//! use std::thread;
+//!
+//! let (tx, rx) = bounded(4);
+//!
+//! let workers: Vec<_> = (0..2).map(|_| {
+//!     let rx = rx.clone();
+//!     thread::spawn(move || rx.iter().sum::<i32>())
+//! }).collect();
+//! drop(rx);
+//!
+//! for i in 1..=10 {
+//!     tx.send(i).unwrap();
+//! }
+//! drop(tx);
+//!
+//! let total: i32 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+//! assert_eq!(total, 55);
+//! ```
+
+#![unstable(feature = "mpmc_channel", issue = "0")]
+
+use error;
+use fmt;
+use sync::Arc;
+use time::{Duration, Instant};
+
+use self::chan::Channel;
+
+pub use sync::mpsc::{SendError, TrySendError, RecvError, TryRecvError, RecvTimeoutError};
+pub use self::select::{Select, SelectedOperation, TrySelectError, SelectTimeoutError};
+
+mod chan;
+mod context;
+mod select;
+
+/// Creates a channel of unbounded capacity.
+///
+/// Sending on the channel never blocks.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+///
+/// use std::sync::mpmc::unbounded;
+///
+/// let (tx, rx) = unbounded();
+/// for i in 0..100 {
+///     tx.send(i).unwrap();
+/// }
+/// assert_eq!(rx.len(), 100);
+/// ```
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    new(None)
+}
+
+/// Creates a channel which holds at most `cap` messages.
+///
+/// Sending blocks while the channel is full. If `cap` is zero, the channel
+/// holds no messages at all: every send blocks until a receiver takes the
+/// message, and every receive blocks until a sender offers one.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+///
+/// use std::sync::mpmc::{bounded, TrySendError};
+///
+/// let (tx, rx) = bounded(1);
+/// tx.send(1).unwrap();
+/// assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+/// assert_eq!(rx.recv(), Ok(1));
+/// ```
+pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    new(Some(cap))
+}
+
+fn new<T>(cap: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    let chan = Arc::new(Channel::new(cap));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+/// The sending side of a channel.
+///
+/// Senders can be cloned and shared between threads. The channel stays
+/// connected for as long as at least one sender is alive.
+pub struct Sender<T> {
+    chan: Arc<Channel<T>>,
+}
+
+/// The receiving side of a channel.
+///
+/// Receivers can be cloned and shared between threads. Each message is
+/// received by only one of them.
+pub struct Receiver<T> {
+    chan: Arc<Channel<T>>,
+}
+
+unsafe impl<T: Send> Send for Sender<T> {}
+unsafe impl<T: Send> Sync for Sender<T> {}
+unsafe impl<T: Send> Send for Receiver<T> {}
+unsafe impl<T: Send> Sync for Receiver<T> {}
+
+/// An error returned from [`Sender::send_timeout`].
+///
+/// Contains the message which could not be sent.
+///
+/// [`Sender::send_timeout`]: struct.Sender.html#method.send_timeout
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendTimeoutError<T> {
+    /// The channel was still full when the timeout elapsed.
+    Timeout(T),
+    /// All receivers of the channel have been dropped.
+    Disconnected(T),
+}
+
+impl<T> Sender<T> {
+    /// Sends a message, blocking while the channel is full.
+    ///
+    /// On a zero-capacity channel, this blocks until a receiver takes the
+    /// message. An error is returned, with the message, if all receivers
+    /// have been dropped.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        self.chan.send(msg, None).map_err(|err| match err {
+            SendTimeoutError::Disconnected(msg) => SendError(msg),
+            SendTimeoutError::Timeout(_) => unreachable!(),
+        })
+    }
+
+    /// Attempts to send a message without blocking.
+    ///
+    /// On a zero-capacity channel, this only succeeds if a receiver is
+    /// currently blocked waiting for a message.
+    pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        self.chan.try_send(msg)
+    }
+
+    /// Sends a message, blocking for at most `timeout` while the channel is
+    /// full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    ///
+    /// use std::sync::mpmc::{bounded, SendTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (tx, _rx) = bounded(0);
+    /// assert_eq!(tx.send_timeout(1, Duration::from_millis(10)),
+    ///            Err(SendTimeoutError::Timeout(1)));
+    /// ```
+    pub fn send_timeout(&self, msg: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.chan.send(msg, Some(Instant::now() + timeout))
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    /// Returns `true` if the channel holds no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if sending would block because the channel is full.
+    ///
+    /// Zero-capacity channels are always full.
+    pub fn is_full(&self) -> bool {
+        self.chan.is_full()
+    }
+
+    /// Returns the capacity of the channel, or `None` if it is unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.chan.capacity()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        self.chan.acquire_sender();
+        Sender { chan: self.chan.clone() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.release_sender();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Sender { .. }")
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a message, blocking while the channel is empty.
+    ///
+    /// An error is returned once the channel is empty and all senders have
+    /// been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.chan.recv(None).map_err(|err| match err {
+            RecvTimeoutError::Disconnected => RecvError,
+            RecvTimeoutError::Timeout => unreachable!(),
+        })
+    }
+
+    /// Attempts to receive a message without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.chan.try_recv()
+    }
+
+    /// Receives a message, blocking for at most `timeout` while the channel
+    /// is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    ///
+    /// use std::sync::mpmc::{unbounded, RecvTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = unbounded::<i32>();
+    /// assert_eq!(rx.recv_timeout(Duration::from_millis(10)),
+    ///            Err(RecvTimeoutError::Timeout));
+    /// drop(tx);
+    /// assert_eq!(rx.recv_timeout(Duration::from_millis(10)),
+    ///            Err(RecvTimeoutError::Disconnected));
+    /// ```
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.chan.recv(Some(Instant::now() + timeout))
+    }
+
+    /// Returns an iterator which blocks waiting for messages, until the
+    /// channel is disconnected.
+    pub fn iter(&self) -> Iter<T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator over the messages currently in the channel, which
+    /// never blocks.
+    pub fn try_iter(&self) -> TryIter<T> {
+        TryIter { rx: self }
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.chan.len()
+    }
+
+    /// Returns `true` if the channel holds no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the channel is full.
+    ///
+    /// Zero-capacity channels are always full.
+    pub fn is_full(&self) -> bool {
+        self.chan.is_full()
+    }
+
+    /// Returns the capacity of the channel, or `None` if it is unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.chan.capacity()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        self.chan.acquire_receiver();
+        Receiver { chan: self.chan.clone() }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.release_receiver();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Receiver { .. }")
+    }
+}
+
+/// A blocking iterator over the messages of a [`Receiver`], created by
+/// [`Receiver::iter`].
+///
+/// [`Receiver`]: struct.Receiver.html
+/// [`Receiver::iter`]: struct.Receiver.html#method.iter
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// A non-blocking iterator over the messages of a [`Receiver`], created by
+/// [`Receiver::try_iter`].
+///
+/// [`Receiver`]: struct.Receiver.html
+/// [`Receiver::try_iter`]: struct.Receiver.html#method.try_iter
+#[derive(Debug)]
+pub struct TryIter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning blocking iterator over the messages of a [`Receiver`].
+///
+/// [`Receiver`]: struct.Receiver.html
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> { self.rx.recv().ok() }
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> { self.rx.try_recv().ok() }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> { self.rx.recv().ok() }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> { self.iter() }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> { IntoIter { rx: self } }
+}
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(..) => "Timeout(..)".fmt(f),
+            SendTimeoutError::Disconnected(..) => "Disconnected(..)".fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(..) => "timed out waiting on send operation".fmt(f),
+            SendTimeoutError::Disconnected(..) => "sending on a closed channel".fmt(f),
+        }
+    }
+}
+
+impl<T: Send> error::Error for SendTimeoutError<T> {
+    fn description(&self) -> &str {
+        match *self {
+            SendTimeoutError::Timeout(..) => "timed out waiting on send operation",
+            SendTimeoutError::Disconnected(..) => "sending on a closed channel",
+        }
+    }
+}
+
+impl<T> From<SendError<T>> for SendTimeoutError<T> {
+    fn from(err: SendError<T>) -> SendTimeoutError<T> {
+        SendTimeoutError::Disconnected(err.0)
+    }
+}
+
+impl error::Error for TrySelectError {
+    fn description(&self) -> &str {
+        "all operations in select would block"
+    }
+}
+
+impl error::Error for SelectTimeoutError {
+    fn description(&self) -> &str {
+        "timed out waiting on select"
+    }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use super::*;
+    use sync::Barrier;
+    use sync::atomic::{AtomicUsize, Ordering};
+    use thread;
+    use time::Duration;
+
+    #[test]
+    fn smoke() {
+        let (tx, rx) = unbounded();
+        tx.send(1).unwrap();
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn bounded_full() {
+        let (tx, rx) = bounded(2);
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert!(tx.is_full());
+        assert_eq!(tx.try_send(3), Err(TrySendError::Full(3)));
+        assert_eq!(rx.recv(), Ok(1));
+        tx.try_send(3).unwrap();
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [2, 3]);
+    }
+
+    #[test]
+    fn zero_capacity_try_send() {
+        let (tx, rx) = bounded(0);
+        assert_eq!(tx.try_send(1), Err(TrySendError::Full(1)));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        assert!(tx.is_full());
+        assert_eq!(tx.capacity(), Some(0));
+    }
+
+    #[test]
+    fn zero_capacity_rendezvous() {
+        let (tx, rx) = bounded(0);
+        let t = thread::spawn(move || {
+            for i in 0..100 {
+                tx.send(i).unwrap();
+            }
+        });
+        for i in 0..100 {
+            assert_eq!(rx.recv(), Ok(i));
+        }
+        t.join().unwrap();
+        assert_eq!(rx.recv(), Err(RecvError));
+    }
+
+    #[test]
+    fn disconnect_senders() {
+        let (tx, rx) = unbounded();
+        tx.send(1).unwrap();
+        drop(tx.clone());
+        drop(tx);
+        // Buffered messages are still delivered after disconnection.
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.recv(), Err(RecvError));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn disconnect_receivers() {
+        let (tx, rx) = bounded(1);
+        let rx2 = rx.clone();
+        drop(rx);
+        tx.send(1).unwrap();
+        drop(rx2);
+        assert_eq!(tx.send(2), Err(SendError(2)));
+        assert_eq!(tx.try_send(3), Err(TrySendError::Disconnected(3)));
+    }
+
+    #[test]
+    fn disconnect_wakes_blocked() {
+        let (tx, rx) = bounded::<i32>(0);
+        let t = thread::spawn(move || rx.recv());
+        thread::sleep(Duration::from_millis(50));
+        drop(tx);
+        assert_eq!(t.join().unwrap(), Err(RecvError));
+
+        let (tx, rx) = bounded(0);
+        let t = thread::spawn(move || tx.send(1));
+        thread::sleep(Duration::from_millis(50));
+        drop(rx);
+        assert_eq!(t.join().unwrap(), Err(SendError(1)));
+    }
+
+    #[test]
+    fn timeouts() {
+        let (tx, rx) = bounded(1);
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)),
+                   Err(RecvTimeoutError::Timeout));
+        tx.send(1).unwrap();
+        assert_eq!(tx.send_timeout(2, Duration::from_millis(10)),
+                   Err(SendTimeoutError::Timeout(2)));
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Ok(1));
+        drop(rx);
+        assert_eq!(tx.send_timeout(3, Duration::from_millis(10)),
+                   Err(SendTimeoutError::Disconnected(3)));
+    }
+
+    #[test]
+    fn drops_undelivered() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct D;
+        impl Drop for D {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let (tx, rx) = unbounded();
+        for _ in 0..10 {
+            tx.send(D).unwrap();
+        }
+        drop(rx.recv().unwrap());
+        drop(rx);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 10);
+        drop(tx);
+    }
+
+    #[test]
+    fn mpmc_stress() {
+        const THREADS: usize = 4;
+        const COUNT: usize = 10_000;
+
+        for &cap in &[Some(0), Some(1), Some(16), None] {
+            let (tx, rx) = match cap {
+                Some(cap) => bounded(cap),
+                None => unbounded(),
+            };
+            let received = Arc::new(AtomicUsize::new(0));
+            let barrier = Arc::new(Barrier::new(THREADS * 2));
+            let mut threads = Vec::new();
+            for _ in 0..THREADS {
+                let (tx, barrier) = (tx.clone(), barrier.clone());
+                threads.push(thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..COUNT {
+                        tx.send(i).unwrap();
+                    }
+                }));
+                let (rx, barrier, received) = (rx.clone(), barrier.clone(), received.clone());
+                threads.push(thread::spawn(move || {
+                    barrier.wait();
+                    for _ in rx.iter() {
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }));
+            }
+            drop((tx, rx));
+            for t in threads {
+                t.join().unwrap();
+            }
+            assert_eq!(received.load(Ordering::SeqCst), THREADS * COUNT);
+        }
+    }
+
+    #[test]
+    fn select_ready() {
+        let (tx1, rx1) = unbounded::<i32>();
+        let (tx2, rx2) = unbounded();
+        tx2.send("two").unwrap();
+
+        let mut sel = Select::new();
+        let op1 = sel.recv(&rx1);
+        let op2 = sel.recv(&rx2);
+        let oper = sel.try_select().unwrap();
+        assert_eq!(oper.index(), op2);
+        assert_eq!(oper.recv(&rx2), Ok("two"));
+
+        assert_eq!(sel.try_select().unwrap_err(), TrySelectError);
+        drop(tx1);
+        let oper = sel.select();
+        assert_eq!(oper.index(), op1);
+        assert_eq!(oper.recv(&rx1), Err(RecvError));
+    }
+
+    #[test]
+    fn select_blocking() {
+        for &cap in &[Some(0), Some(1), None] {
+            let (tx1, rx1) = match cap {
+                Some(cap) => bounded(cap),
+                None => unbounded(),
+            };
+            let (_tx2, rx2) = unbounded::<i32>();
+            let t = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                tx1.send(7).unwrap();
+            });
+
+            let mut sel = Select::new();
+            let op1 = sel.recv(&rx1);
+            sel.recv(&rx2);
+            let oper = sel.select();
+            assert_eq!(oper.index(), op1);
+            assert_eq!(oper.recv(&rx1), Ok(7));
+            t.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn select_timeout() {
+        let (_tx, rx) = bounded::<i32>(0);
+        let mut sel = Select::new();
+        sel.recv(&rx);
+        assert_eq!(sel.select_timeout(Duration::from_millis(10)).unwrap_err(),
+                   SelectTimeoutError);
+    }
+
+    #[test]
+    fn select_many_receivers() {
+        const COUNT: usize = 1_000;
+
+        let (tx, rx) = bounded(0);
+        let rx2 = rx.clone();
+        let t = thread::spawn(move || {
+            for i in 0..COUNT {
+                tx.send(i).unwrap();
+            }
+        });
+        let mut got = 0;
+        loop {
+            let mut sel = Select::new();
+            let op1 = sel.recv(&rx);
+            sel.recv(&rx2);
+            let oper = sel.select();
+            let res = if oper.index() == op1 { oper.recv(&rx) } else { oper.recv(&rx2) };
+            match res {
+                Ok(i) => {
+                    assert_eq!(i, got);
+                    got += 1;
+                }
+                Err(RecvError) => break,
+            }
+        }
+        assert_eq!(got, COUNT);
+        t.join().unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_wrong_receiver() {
+        let (tx, rx) = unbounded();
+        let rx2 = rx.clone();
+        tx.send(1).unwrap();
+        let mut sel = Select::new();
+        sel.recv(&rx);
+        let oper = sel.select();
+        let _ = oper.recv(&rx2);
+    }
+}
@@ -0,0 +1,361 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Waiting on several receivers at once.
+//!
+//! A select first tries every receiver in turn, starting at a random one so
+//! that no receiver is favoured. If none is ready, a single `Context` is
+//! registered with all the channels and the thread goes to sleep until one of
+//! them selects it. The message is then received on the selected channel
+//! before `select` returns, so completing the operation can never block.
+
+use cell::Cell;
+use fmt;
+use marker::PhantomData;
+use ptr;
+use sync::mpsc::{RecvError, TryRecvError};
+use super::Receiver;
+use super::context::{Context, ABORTED, DISCONNECTED, FIRST_OPER};
+use time::{Duration, Instant};
+
+/// A receiver whose message type has been erased.
+trait SelectHandle {
+    /// Receives a message without blocking, if one is available or the
+    /// channel is disconnected.
+    fn try_recv(&self) -> Option<Packet>;
+
+    /// Registers `cx` with the channel. Returns `true` instead if the receive
+    /// operation could make progress right away.
+    fn register(&self, oper: usize, cx: &Context) -> bool;
+
+    fn unregister(&self, cx: &Context);
+
+    /// Completes a receive after the channel selected `cx`.
+    fn complete(&self, cx: &Context) -> Option<Packet>;
+
+    /// The address of the receiver, used to check that the operation is
+    /// completed with the receiver it was registered with.
+    fn addr(&self) -> usize;
+}
+
+/// A received `Result<T, RecvError>`, boxed and type-erased.
+struct Packet {
+    ptr: *mut u8,
+    drop: unsafe fn(*mut u8),
+}
+
+unsafe fn drop_packet<T>(ptr: *mut u8) {
+    drop(Box::from_raw(ptr as *mut Result<T, RecvError>));
+}
+
+impl Packet {
+    fn new<T>(res: Result<T, RecvError>) -> Packet {
+        Packet {
+            ptr: Box::into_raw(Box::new(res)) as *mut u8,
+            drop: drop_packet::<T>,
+        }
+    }
+}
+
+impl Drop for Packet {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { (self.drop)(self.ptr) }
+        }
+    }
+}
+
+impl<T> SelectHandle for Receiver<T> {
+    fn try_recv(&self) -> Option<Packet> {
+        match self.chan.try_recv() {
+            Ok(msg) => Some(Packet::new(Ok(msg))),
+            Err(TryRecvError::Disconnected) => Some(Packet::new::<T>(Err(RecvError))),
+            Err(TryRecvError::Empty) => None,
+        }
+    }
+
+    fn register(&self, oper: usize, cx: &Context) -> bool {
+        self.chan.register_recv(oper, cx)
+    }
+
+    fn unregister(&self, cx: &Context) {
+        self.chan.unregister_recv(cx)
+    }
+
+    fn complete(&self, cx: &Context) -> Option<Packet> {
+        match self.chan.take_packet(cx) {
+            Some(msg) => Some(Packet::new(Ok(msg))),
+            None => SelectHandle::try_recv(self),
+        }
+    }
+
+    fn addr(&self) -> usize {
+        self as *const Receiver<T> as usize
+    }
+}
+
+/// Waits on several receive operations at once.
+///
+/// Receivers are added to a `Select` with [`recv`], which returns the index
+/// of the operation. [`select`] then blocks until one of the operations
+/// completes and returns a [`SelectedOperation`] describing it, from which
+/// the received message is retrieved by passing the same receiver to
+/// [`SelectedOperation::recv`].
+///
+/// If several operations are ready at the same time, one of them is chosen
+/// at random. A receiver whose channel is disconnected is always ready: its
+/// operation completes with [`RecvError`].
+///
+/// [`recv`]: #method.recv
+/// [`select`]: #method.select
+/// [`SelectedOperation`]: struct.SelectedOperation.html
+/// [`SelectedOperation::recv`]: struct.SelectedOperation.html#method.recv
+/// [`RecvError`]: ../mpsc/struct.RecvError.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+///
+/// use std::sync::mpmc::{unbounded, Select};
+/// use std::thread;
+///
+/// let (tx1, rx1) = unbounded();
+/// let (tx2, rx2) = unbounded::<&str>();
+///
+/// thread::spawn(move || tx1.send(10).unwrap());
+/// drop(tx2);
+///
+/// let mut sel = Select::new();
+/// let op1 = sel.recv(&rx1);
+/// let op2 = sel.recv(&rx2);
+///
+/// let oper = sel.select();
+/// let index = oper.index();
+/// if index == op1 {
+///     assert_eq!(oper.recv(&rx1), Ok(10));
+/// } else if index == op2 {
+///     assert!(oper.recv(&rx2).is_err());
+/// }
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "0")]
+pub struct Select<'a> {
+    handles: Vec<&'a dyn SelectHandle>,
+}
+
+/// A receive operation completed by a [`Select`].
+///
+/// The result of the operation must be retrieved with [`recv`], passing the
+/// receiver which was registered for it. Dropping a `SelectedOperation`
+/// drops the received message.
+///
+/// [`Select`]: struct.Select.html
+/// [`recv`]: #method.recv
+#[must_use = "the received message is lost unless `recv` is called"]
+#[unstable(feature = "mpmc_channel", issue = "0")]
+pub struct SelectedOperation<'a> {
+    index: usize,
+    addr: usize,
+    packet: Packet,
+    _marker: PhantomData<&'a ()>,
+}
+
+/// An error returned from [`Select::try_select`] when no operation is ready.
+///
+/// [`Select::try_select`]: struct.Select.html#method.try_select
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[unstable(feature = "mpmc_channel", issue = "0")]
+pub struct TrySelectError;
+
+/// An error returned from [`Select::select_timeout`] when no operation became
+/// ready before the timeout.
+///
+/// [`Select::select_timeout`]: struct.Select.html#method.select_timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[unstable(feature = "mpmc_channel", issue = "0")]
+pub struct SelectTimeoutError;
+
+impl<'a> Select<'a> {
+    /// Creates an empty list of operations.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn new() -> Select<'a> {
+        Select { handles: Vec::new() }
+    }
+
+    /// Adds a receive operation, returning its index.
+    ///
+    /// The same receiver may be added more than once.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn recv<T>(&mut self, r: &'a Receiver<T>) -> usize {
+        self.handles.push(r);
+        self.handles.len() - 1
+    }
+
+    /// Completes one of the operations if it is ready, without blocking.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn try_select(&mut self) -> Result<SelectedOperation<'a>, TrySelectError> {
+        self.run(Some(Instant::now())).ok_or(TrySelectError)
+    }
+
+    /// Blocks until one of the operations completes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added, as this would block forever.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn select(&mut self) -> SelectedOperation<'a> {
+        assert!(!self.handles.is_empty(), "no operations have been added to `Select`");
+        self.run(None).unwrap()
+    }
+
+    /// Blocks until one of the operations completes, or until `timeout`
+    /// elapses.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn select_timeout(&mut self, timeout: Duration)
+                          -> Result<SelectedOperation<'a>, SelectTimeoutError> {
+        self.run(Some(Instant::now() + timeout)).ok_or(SelectTimeoutError)
+    }
+
+    fn run(&self, deadline: Option<Instant>) -> Option<SelectedOperation<'a>> {
+        let n = self.handles.len();
+        loop {
+            // Try every operation once, starting from a random one.
+            let start = if n == 0 { 0 } else { random(n) };
+            for i in (start..n).chain(0..start) {
+                if let Some(packet) = self.handles[i].try_recv() {
+                    return Some(self.selected(i, packet));
+                }
+            }
+
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    return None;
+                }
+            }
+
+            // Nothing is ready: block on all the channels at once.
+            let cx = Context::new();
+            let mut registered = 0;
+            let mut ready = false;
+            for (i, handle) in self.handles.iter().enumerate() {
+                if handle.register(FIRST_OPER + i, &cx) {
+                    ready = true;
+                    break;
+                }
+                registered += 1;
+            }
+
+            let sel = if ready {
+                // Something became ready while registering, so don't sleep.
+                // A channel may still have selected us in the meantime.
+                match cx.try_select(ABORTED) {
+                    Ok(()) => ABORTED,
+                    Err(sel) => sel,
+                }
+            } else {
+                cx.wait_until(deadline)
+            };
+
+            for handle in &self.handles[..registered] {
+                handle.unregister(&cx);
+            }
+
+            match sel {
+                // Timed out, or an operation became ready while registering.
+                // Either way, the next round tries everything again.
+                ABORTED | DISCONNECTED => {}
+                oper => {
+                    let i = oper - FIRST_OPER;
+                    if let Some(packet) = self.handles[i].complete(&cx) {
+                        return Some(self.selected(i, packet));
+                    }
+                    // Another receiver got to the message first.
+                }
+            }
+        }
+    }
+
+    fn selected(&self, index: usize, packet: Packet) -> SelectedOperation<'a> {
+        SelectedOperation {
+            index,
+            addr: self.handles[index].addr(),
+            packet,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "0")]
+impl<'a> fmt::Debug for Select<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Select { .. }")
+    }
+}
+
+impl<'a> SelectedOperation<'a> {
+    /// Returns the index of the completed operation.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the result of the completed receive operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r` is not the receiver which was added to the `Select` for
+    /// this operation.
+    #[unstable(feature = "mpmc_channel", issue = "0")]
+    pub fn recv<T>(mut self, r: &Receiver<T>) -> Result<T, RecvError> {
+        assert!(r as *const Receiver<T> as usize == self.addr,
+                "passed a receiver that wasn't selected");
+        let ptr = self.packet.ptr as *mut Result<T, RecvError>;
+        self.packet.ptr = ptr::null_mut();
+        unsafe { *Box::from_raw(ptr) }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "0")]
+impl<'a> fmt::Debug for SelectedOperation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SelectedOperation").field("index", &self.index).finish()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "0")]
+impl fmt::Display for TrySelectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "all operations in select would block".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "0")]
+impl fmt::Display for SelectTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "timed out waiting on select".fmt(f)
+    }
+}
+
+/// Returns a pseudo-random number in `0..n`, used to pick the first
+/// operation to try.
+fn random(n: usize) -> usize {
+    thread_local! {
+        static RNG: Cell<u32> = Cell::new(0x53db_1ca7);
+    }
+    RNG.with(|rng| {
+        // A xorshift generator is plenty to avoid starving any operation.
+        let mut x = rng.get();
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        rng.set(x);
+        (x as usize) % n
+    })
+}
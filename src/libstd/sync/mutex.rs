@@ -119,12 +119,13 @@ use sys_common::poison::{self, TryLockError, TryLockResult, LockResult};
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Mutex<T: ?Sized> {
-    // Note that this mutex is in a *box*, not inlined into the struct itself.
-    // Once a native mutex has been used once, its address can never change (it
-    // can't be moved). This mutex type can be safely moved at any time, so to
-    // ensure that the native mutex is used correctly we box the inner mutex to
-    // give it a constant address.
-    inner: Box<sys::Mutex>,
+    // Note that on most platforms this mutex is in a *box*, not inlined into
+    // the struct itself. Once a native mutex has been used once, its address
+    // can never change (it can't be moved). This mutex type can be safely
+    // moved at any time, so to ensure that the native mutex is used correctly
+    // `MovableMutex` boxes it to give it a constant address. Where the native
+    // mutex is a plain atomic (futexes on Linux) it is stored inline.
+    inner: sys::MovableMutex,
     poison: poison::Flag,
     data: UnsafeCell<T>,
 }
@@ -176,15 +177,26 @@ impl<T> Mutex<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new(t: T) -> Mutex<T> {
-        let mut m = Mutex {
-            inner: box sys::Mutex::new(),
+        Mutex {
+            inner: sys::MovableMutex::new(),
+            poison: poison::Flag::new(),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Creates a new mutex in an unlocked state ready for use, in a constant
+    /// context so that it can be used to initialize a `static`.
+    ///
+    /// This is crate-private: it is only available on platforms where mutexes
+    /// don't need to be heap allocated, so it can't be part of the public API.
+    #[cfg(target_os = "linux")]
+    #[allow(dead_code)]
+    pub(crate) const fn const_new(t: T) -> Mutex<T> {
+        Mutex {
+            inner: sys::MovableMutex::new_const(),
             poison: poison::Flag::new(),
             data: UnsafeCell::new(t),
-        };
-        unsafe {
-            m.inner.init();
         }
-        m
     }
 }
 
@@ -475,7 +487,7 @@ impl<'a, T: ?Sized + fmt::Display> fmt::Display for MutexGuard<'a, T> {
     }
 }
 
-pub fn guard_lock<'a, T: ?Sized>(guard: &MutexGuard<'a, T>) -> &'a sys::Mutex {
+pub fn guard_lock<'a, T: ?Sized>(guard: &MutexGuard<'a, T>) -> &'a sys::MovableMutex {
     &guard.__lock.inner
 }
 
@@ -502,6 +514,23 @@ mod tests {
         drop(m.lock().unwrap());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn smoke_const() {
+        static M: Mutex<u32> = Mutex::const_new(0);
+        *M.lock().unwrap() += 1;
+        assert_eq!(*M.try_lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn moved_between_locks() {
+        let m = Mutex::new(1);
+        *m.lock().unwrap() += 1;
+        let m = Box::new(m);
+        *m.lock().unwrap() += 1;
+        assert_eq!(m.into_inner().unwrap(), 3);
+    }
+
     #[test]
     fn lots_and_lots() {
         const J: u32 = 1000;
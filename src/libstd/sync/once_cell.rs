@@ -0,0 +1,439 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lazily-initialized, thread-safe cells.
+//!
+//! Both types in this module are built on top of [`Once`]: the `Once` guards
+//! the single write to the cell and provides the happens-before edge between
+//! the initializing thread and every thread which later reads the value.
+//!
+//! [`Once`]: struct.Once.html
+
+use cell::{Cell, UnsafeCell};
+use fmt;
+use ops::Deref;
+use panic::{RefUnwindSafe, UnwindSafe};
+use sync::Once;
+
+/// A synchronization primitive which can be written to only once.
+///
+/// This type is a thread-safe version of [`std::cell::OnceCell`]: it can be
+/// placed in a `static` and initialized from any thread. If several threads
+/// race to initialize the cell, exactly one initializer runs and the others
+/// block until it has finished.
+///
+/// [`std::cell::OnceCell`]: ../cell/struct.OnceCell.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(once_cell)]
+///
+/// use std::sync::OnceCell;
+///
+/// static CELL: OnceCell<String> = OnceCell::new();
+/// assert!(CELL.get().is_none());
+///
+/// std::thread::spawn(|| {
+///     let value: &String = CELL.get_or_init(|| {
+///         "Hello, World!".to_string()
+///     });
+///     assert_eq!(value, "Hello, World!");
+/// }).join().unwrap();
+///
+/// let value: Option<&String> = CELL.get();
+/// assert!(value.is_some());
+/// assert_eq!(value.unwrap().as_str(), "Hello, World!");
+/// ```
+#[unstable(feature = "once_cell", issue = "0")]
+pub struct OnceCell<T> {
+    once: Once,
+    // Written to exactly once, by the thread which runs the `Once`. Only read
+    // after `once` has been observed to be complete.
+    value: UnsafeCell<Option<T>>,
+}
+
+// Sharing a `OnceCell` lets any thread initialize it (hence `Send`) and any
+// thread read the value (hence `Sync`).
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T: Sync + Send> Sync for OnceCell<T> {}
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T: Send> Send for OnceCell<T> {}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: RefUnwindSafe + UnwindSafe> RefUnwindSafe for OnceCell<T> {}
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: UnwindSafe> UnwindSafe for OnceCell<T> {}
+
+impl<T> OnceCell<T> {
+    /// Creates a new, uninitialized cell.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub const fn new() -> OnceCell<T> {
+        OnceCell {
+            once: Once::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Returns a reference to the underlying value, or `None` if the cell is
+    /// empty or being initialized.
+    ///
+    /// This method never blocks.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get(&self) -> Option<&T> {
+        if self.once.is_completed() {
+            // Safe because the value is never written to again once the
+            // `Once` has completed.
+            unsafe { (*self.value.get()).as_ref() }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the underlying value, or `None` if the
+    /// cell is empty.
+    ///
+    /// This call borrows the cell mutably, so no other thread can be
+    /// initializing it concurrently.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        unsafe { (*self.value.get()).as_mut() }
+    }
+
+    /// Sets the contents of the cell to `value`.
+    ///
+    /// If another thread is currently initializing the cell, this call blocks
+    /// until it has finished. Returns `Err(value)` if the cell was already
+    /// initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceCell;
+    ///
+    /// static CELL: OnceCell<i32> = OnceCell::new();
+    ///
+    /// assert!(CELL.get().is_none());
+    /// assert_eq!(CELL.set(92), Ok(()));
+    /// assert_eq!(CELL.set(62), Err(62));
+    /// assert_eq!(CELL.get(), Some(&92));
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Gets the contents of the cell, initializing it with `f` if the cell was
+    /// empty.
+    ///
+    /// Many threads may call `get_or_init` concurrently with different
+    /// initializing functions, but it is guaranteed that only one function
+    /// will be executed. The other callers block until the value is available.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics, the panic is propagated to the caller and the cell
+    /// remains uninitialized; a later call may attempt initialization again.
+    /// Unlike [`Once::call_once`], a panicking initializer does not poison the
+    /// cell.
+    ///
+    /// It is an error to reentrantly initialize the cell from `f`. The exact
+    /// outcome is unspecified, but currently results in a deadlock.
+    ///
+    /// [`Once::call_once`]: struct.Once.html#method.call_once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::OnceCell;
+    ///
+    /// let cell = OnceCell::new();
+    /// let value = cell.get_or_init(|| 92);
+    /// assert_eq!(value, &92);
+    /// let value = cell.get_or_init(|| unreachable!());
+    /// assert_eq!(value, &92);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn get_or_init<F>(&self, f: F) -> &T where F: FnOnce() -> T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        let slot = &self.value;
+        // `call_once_force` rather than `call_once` so that a panicking
+        // initializer leaves the cell empty instead of poisoning it forever.
+        self.once.call_once_force(|_| {
+            let value = f();
+            unsafe { *slot.get() = Some(value); }
+        });
+
+        // The `Once` has completed, so the value is present.
+        self.get().unwrap()
+    }
+
+    /// Consumes the cell, returning the wrapped value.
+    ///
+    /// Returns `None` if the cell was empty.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> Default for OnceCell<T> {
+    fn default() -> OnceCell<T> {
+        OnceCell::new()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for OnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(v) => f.debug_tuple("OnceCell").field(v).finish(),
+            None => f.write_str("OnceCell(Uninit)"),
+        }
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Clone> Clone for OnceCell<T> {
+    fn clone(&self) -> OnceCell<T> {
+        let res = OnceCell::new();
+        if let Some(value) = self.get() {
+            let _ = res.set(value.clone());
+        }
+        res
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T> From<T> for OnceCell<T> {
+    fn from(value: T) -> OnceCell<T> {
+        let cell = OnceCell::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: PartialEq> PartialEq for OnceCell<T> {
+    fn eq(&self, other: &OnceCell<T>) -> bool {
+        self.get() == other.get()
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Eq> Eq for OnceCell<T> {}
+
+/// A value which is initialized on the first access.
+///
+/// This type is a thread-safe replacement for the `lazy_static!` macro: the
+/// initialization function is run exactly once, on the first dereference,
+/// and every later access observes the computed value.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(once_cell)]
+///
+/// use std::collections::HashMap;
+/// use std::sync::Lazy;
+///
+/// static HASHMAP: Lazy<HashMap<i32, String>> = Lazy::new(|| {
+///     println!("initializing");
+///     let mut m = HashMap::new();
+///     m.insert(13, "Spica".to_string());
+///     m.insert(74, "Hoyten".to_string());
+///     m
+/// });
+///
+/// fn main() {
+///     println!("ready");
+///     std::thread::spawn(|| {
+///         println!("{:?}", HASHMAP.get(&13));
+///     }).join().unwrap();
+///     println!("{:?}", HASHMAP.get(&74));
+/// }
+/// ```
+#[unstable(feature = "once_cell", issue = "0")]
+pub struct Lazy<T, F = fn() -> T> {
+    cell: OnceCell<T>,
+    init: Cell<Option<F>>,
+}
+
+// `init` is only ever touched by the thread which runs the `OnceCell`
+// initializer, so sharing a `Lazy` only requires the function to be sendable.
+#[unstable(feature = "once_cell", issue = "0")]
+unsafe impl<T, F: Send> Sync for Lazy<T, F> where OnceCell<T>: Sync {}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T, F: UnwindSafe> RefUnwindSafe for Lazy<T, F> where OnceCell<T>: RefUnwindSafe {}
+
+impl<T, F> Lazy<T, F> {
+    /// Creates a new lazy value with the given initializing function.
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub const fn new(f: F) -> Lazy<T, F> {
+        Lazy { cell: OnceCell::new(), init: Cell::new(Some(f)) }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Forces the evaluation of this lazy value and returns a reference to the
+    /// result.
+    ///
+    /// This is equivalent to the `Deref` impl, but is explicit.
+    ///
+    /// # Panics
+    ///
+    /// If the initializing function panicked on a previous access, the
+    /// function is gone and this call panics as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(once_cell)]
+    ///
+    /// use std::sync::Lazy;
+    ///
+    /// let lazy = Lazy::new(|| 92);
+    ///
+    /// assert_eq!(Lazy::force(&lazy), &92);
+    /// assert_eq!(&*lazy, &92);
+    /// ```
+    #[unstable(feature = "once_cell", issue = "0")]
+    pub fn force(this: &Lazy<T, F>) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(f) => f(),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: Default> Default for Lazy<T> {
+    /// Creates a new lazy value using `Default` as the initializing function.
+    fn default() -> Lazy<T> {
+        Lazy::new(T::default)
+    }
+}
+
+#[unstable(feature = "once_cell", issue = "0")]
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Lazy").field("cell", &self.cell).field("init", &"..").finish()
+    }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use panic;
+    use sync::atomic::{AtomicUsize, Ordering};
+    use sync::mpsc::channel;
+    use thread;
+    use super::{Lazy, OnceCell};
+
+    #[test]
+    fn smoke_once_cell() {
+        static O: OnceCell<i32> = OnceCell::new();
+        assert!(O.get().is_none());
+        assert_eq!(*O.get_or_init(|| 92), 92);
+        assert_eq!(*O.get_or_init(|| panic!()), 92);
+        assert_eq!(O.set(62), Err(62));
+        assert_eq!(O.get(), Some(&92));
+    }
+
+    #[test]
+    fn stampede_once_cell() {
+        static O: OnceCell<usize> = OnceCell::new();
+        static RUN: AtomicUsize = AtomicUsize::new(0);
+
+        let (tx, rx) = channel();
+        for i in 0..10 {
+            let tx = tx.clone();
+            thread::spawn(move|| {
+                for _ in 0..4 { thread::yield_now() }
+                let v = O.get_or_init(|| {
+                    RUN.fetch_add(1, Ordering::SeqCst);
+                    i
+                });
+                tx.send(*v).unwrap();
+            });
+        }
+
+        let first = rx.recv().unwrap();
+        for _ in 1..10 {
+            assert_eq!(rx.recv().unwrap(), first);
+        }
+        assert_eq!(RUN.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn once_cell_init_panic_does_not_poison() {
+        let cell: OnceCell<i32> = OnceCell::new();
+        let t = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            cell.get_or_init(|| panic!());
+        }));
+        assert!(t.is_err());
+        assert!(cell.get().is_none());
+        assert_eq!(*cell.get_or_init(|| 1), 1);
+    }
+
+    #[test]
+    fn once_cell_into_inner() {
+        let cell: OnceCell<String> = OnceCell::new();
+        assert_eq!(cell.into_inner(), None);
+        let cell = OnceCell::new();
+        cell.set("hello".to_string()).unwrap();
+        assert_eq!(cell.into_inner(), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn lazy_static_init() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static XS: Lazy<Vec<i32>> = Lazy::new(|| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            vec![1, 2, 3]
+        });
+
+        let t = thread::spawn(|| XS.len());
+        assert_eq!(XS.iter().sum::<i32>(), 6);
+        assert_eq!(t.join().unwrap(), 3);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn lazy_poisoned() {
+        let x: Lazy<String> = Lazy::new(|| panic!("kaboom"));
+        for _ in 0..2 {
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| x.len()));
+            assert!(res.is_err());
+        }
+    }
+}
@@ -75,7 +75,7 @@ use sys_common::rwlock as sys;
 /// [`Mutex`]: struct.Mutex.html
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct RwLock<T: ?Sized> {
-    inner: Box<sys::RWLock>,
+    inner: sys::MovableRWLock,
     poison: poison::Flag,
     data: UnsafeCell<T>,
 }
@@ -141,7 +141,22 @@ impl<T> RwLock<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new(t: T) -> RwLock<T> {
         RwLock {
-            inner: box sys::RWLock::new(),
+            inner: sys::MovableRWLock::new(),
+            poison: poison::Flag::new(),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Creates a new instance of an `RwLock<T>` which is unlocked, in a
+    /// constant context so that it can be used to initialize a `static`.
+    ///
+    /// This is crate-private: it is only available on platforms where
+    /// reader-writer locks don't need to be heap allocated.
+    #[cfg(target_os = "linux")]
+    #[allow(dead_code)]
+    pub(crate) const fn const_new(t: T) -> RwLock<T> {
+        RwLock {
+            inner: sys::MovableRWLock::new_const(),
             poison: poison::Flag::new(),
             data: UnsafeCell::new(t),
         }
@@ -583,6 +598,16 @@ mod tests {
         drop(l.write().unwrap());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn smoke_const() {
+        static L: RwLock<u32> = RwLock::const_new(0);
+        *L.write().unwrap() += 1;
+        drop((L.read().unwrap(), L.read().unwrap()));
+        assert_eq!(*L.try_read().unwrap(), 1);
+        assert_eq!(*L.try_write().unwrap(), 1);
+    }
+
     #[test]
     fn frob() {
         const N: u32 = 10;
@@ -25,6 +25,8 @@ pub struct RWLock {
     lock: UnsafeCell<AtomicU32>,
 }
 
+pub type MovableRWLock = Box<RWLock>;
+
 pub unsafe fn raw(r: &RWLock) -> *mut AtomicU32 {
     r.lock.get()
 }
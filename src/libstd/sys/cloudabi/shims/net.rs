@@ -282,6 +282,134 @@ impl fmt::Debug for UdpSocket {
     }
 }
 
+pub struct TcpSocket(Void);
+
+impl TcpSocket {
+    pub fn new(_: &SocketAddr) -> io::Result<TcpSocket> {
+        unsupported()
+    }
+
+    pub fn set_reuseaddr(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        match self.0 {}
+    }
+
+    pub fn set_reuseport(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn set_send_buffer_size(&self, _: usize) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn set_keepalive(&self, _: Option<Duration>) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn set_nodelay(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn set_only_v6(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn bind(&self, _: &SocketAddr) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+        match self.0 {}
+    }
+
+    pub fn listen(self, _: u32) -> io::Result<TcpListener> {
+        match self.0 {}
+    }
+
+    pub fn connect(self, _: &SocketAddr) -> io::Result<TcpStream> {
+        match self.0 {}
+    }
+
+    pub fn connect_timeout(self, _: &SocketAddr, _: Duration) -> io::Result<TcpStream> {
+        match self.0 {}
+    }
+}
+
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {}
+    }
+}
+
+pub struct UdpSocketBuilder(Void);
+
+impl UdpSocketBuilder {
+    pub fn new(_: &SocketAddr) -> io::Result<UdpSocketBuilder> {
+        unsupported()
+    }
+
+    pub fn set_reuseaddr(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        match self.0 {}
+    }
+
+    pub fn set_reuseport(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn set_send_buffer_size(&self, _: usize) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn set_broadcast(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn set_only_v6(&self, _: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn bind(self, _: &SocketAddr) -> io::Result<UdpSocket> {
+        match self.0 {}
+    }
+}
+
+impl fmt::Debug for UdpSocketBuilder {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {}
+    }
+}
+
 pub struct LookupHost(Void);
 
 impl Iterator for LookupHost {
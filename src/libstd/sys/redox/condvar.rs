@@ -21,6 +21,8 @@ pub struct Condvar {
     seq: UnsafeCell<i32>
 }
 
+pub type MovableCondvar = Box<Condvar>;
+
 impl Condvar {
     pub const fn new() -> Condvar {
         Condvar {
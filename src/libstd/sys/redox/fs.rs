@@ -20,6 +20,8 @@ use sys::time::SystemTime;
 use sys::{cvt, syscall};
 use sys_common::{AsInner, FromInner};
 
+pub use sys_common::fs::WalkDir;
+
 pub struct File(FileDesc);
 
 #[derive(Clone)]
@@ -316,6 +318,26 @@ impl File {
         set_perm(&self.path()?, perm)
     }
 
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
     pub fn path(&self) -> io::Result<PathBuf> {
         let mut buf: [u8; 4096] = [0; 4096];
         let count = cvt(syscall::fpath(*self.fd().as_inner() as usize, &mut buf))?;
@@ -466,6 +488,20 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     file.path()
 }
 
+pub fn tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+pub fn link_tmpfile(_file: &File, _dst: &Path) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Other, "unnamed temporary files are not supported"))
+}
+
+pub fn fsync_dir(p: &Path) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    File::open(p, &opts)?.fsync()
+}
+
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
@@ -59,6 +59,8 @@ pub struct Mutex {
     pub lock: UnsafeCell<i32>,
 }
 
+pub type MovableMutex = Box<Mutex>;
+
 impl Mutex {
     /// Create a new mutex.
     pub const fn new() -> Self {
@@ -20,8 +20,8 @@ use vec::{IntoIter, Vec};
 
 use self::dns::{Dns, DnsQuery};
 
-pub use self::tcp::{TcpStream, TcpListener};
-pub use self::udp::UdpSocket;
+pub use self::tcp::{TcpStream, TcpListener, TcpSocket};
+pub use self::udp::{UdpSocket, UdpSocketBuilder};
 
 pub mod netc;
 
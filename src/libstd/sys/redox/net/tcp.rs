@@ -251,3 +251,72 @@ impl FromInner<File> for TcpListener {
 impl IntoInner<File> for TcpListener {
     fn into_inner(self) -> File { self.0 }
 }
+
+#[derive(Debug)]
+pub struct TcpSocket(());
+
+impl TcpSocket {
+    pub fn new(_: &SocketAddr) -> Result<TcpSocket> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::new not implemented"))
+    }
+
+    pub fn set_reuseaddr(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_reuseaddr not implemented"))
+    }
+
+    pub fn reuseaddr(&self) -> Result<bool> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::reuseaddr not implemented"))
+    }
+
+    pub fn set_reuseport(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_reuseport not implemented"))
+    }
+
+    pub fn set_send_buffer_size(&self, _: usize) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_send_buffer_size not implemented"))
+    }
+
+    pub fn send_buffer_size(&self) -> Result<usize> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::send_buffer_size not implemented"))
+    }
+
+    pub fn set_recv_buffer_size(&self, _: usize) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_recv_buffer_size not implemented"))
+    }
+
+    pub fn recv_buffer_size(&self) -> Result<usize> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::recv_buffer_size not implemented"))
+    }
+
+    pub fn set_keepalive(&self, _: Option<Duration>) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_keepalive not implemented"))
+    }
+
+    pub fn set_nodelay(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_nodelay not implemented"))
+    }
+
+    pub fn set_only_v6(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::set_only_v6 not implemented"))
+    }
+
+    pub fn bind(&self, _: &SocketAddr) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::bind not implemented"))
+    }
+
+    pub fn socket_addr(&self) -> Result<SocketAddr> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::socket_addr not implemented"))
+    }
+
+    pub fn listen(self, _: u32) -> Result<TcpListener> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::listen not implemented"))
+    }
+
+    pub fn connect(self, _: &SocketAddr) -> Result<TcpStream> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::connect not implemented"))
+    }
+
+    pub fn connect_timeout(self, _: &SocketAddr, _: Duration) -> Result<TcpStream> {
+        Err(Error::new(ErrorKind::Other, "TcpSocket::connect_timeout not implemented"))
+    }
+}
@@ -240,3 +240,52 @@ impl FromInner<File> for UdpSocket {
 impl IntoInner<File> for UdpSocket {
     fn into_inner(self) -> File { self.0 }
 }
+
+#[derive(Debug)]
+pub struct UdpSocketBuilder(());
+
+impl UdpSocketBuilder {
+    pub fn new(_: &SocketAddr) -> Result<UdpSocketBuilder> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::new not implemented"))
+    }
+
+    pub fn set_reuseaddr(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_reuseaddr not implemented"))
+    }
+
+    pub fn reuseaddr(&self) -> Result<bool> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::reuseaddr not implemented"))
+    }
+
+    pub fn set_reuseport(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_reuseport not implemented"))
+    }
+
+    pub fn set_send_buffer_size(&self, _: usize) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_send_buffer_size not implemented"))
+    }
+
+    pub fn send_buffer_size(&self) -> Result<usize> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::send_buffer_size not implemented"))
+    }
+
+    pub fn set_recv_buffer_size(&self, _: usize) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_recv_buffer_size not implemented"))
+    }
+
+    pub fn recv_buffer_size(&self) -> Result<usize> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::recv_buffer_size not implemented"))
+    }
+
+    pub fn set_broadcast(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_broadcast not implemented"))
+    }
+
+    pub fn set_only_v6(&self, _: bool) -> Result<()> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::set_only_v6 not implemented"))
+    }
+
+    pub fn bind(self, _: &SocketAddr) -> Result<UdpSocket> {
+        Err(Error::new(ErrorKind::Other, "UdpSocketBuilder::bind not implemented"))
+    }
+}
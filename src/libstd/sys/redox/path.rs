@@ -8,8 +8,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use env;
 use ffi::OsStr;
-use path::Prefix;
+use io;
+use path::{Path, PathBuf, Prefix};
 
 #[inline]
 pub fn is_sep_byte(b: u8) -> bool {
@@ -33,3 +35,17 @@ pub fn parse_prefix(path: &OsStr) -> Option<Prefix> {
 
 pub const MAIN_SEP_STR: &'static str = "/";
 pub const MAIN_SEP: char = '/';
+
+/// Makes `path` absolute by joining it onto the current directory.
+///
+/// `.` components and repeated separators are removed, but `..` components
+/// are kept, as they can't be resolved without following symbolic links.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let mut absolute = if path.is_absolute() {
+        PathBuf::new()
+    } else {
+        env::current_dir()?
+    };
+    absolute.extend(path.components());
+    Ok(absolute)
+}
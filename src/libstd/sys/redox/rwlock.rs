@@ -14,6 +14,8 @@ pub struct RWLock {
     mutex: Mutex
 }
 
+pub type MovableRWLock = Box<RWLock>;
+
 unsafe impl Send for RWLock {}
 unsafe impl Sync for RWLock {}
 
@@ -396,6 +396,38 @@ impl DirEntry {
     fn name_bytes(&self) -> &[u8] {
         &*self.name
     }
+
+    // The type of the entry if `d_type` gives it away. Unlike `file_type`,
+    // this never falls back to looking the entry up by path, which the
+    // `*at` based functions below must not do.
+    #[cfg(not(any(target_os = "macos",
+                  target_os = "ios",
+                  target_os = "solaris",
+                  target_os = "haiku",
+                  target_os = "hermit")))]
+    fn file_type_hint(&self) -> Option<FileType> {
+        let mode = match self.entry.d_type {
+            libc::DT_CHR => libc::S_IFCHR,
+            libc::DT_FIFO => libc::S_IFIFO,
+            libc::DT_LNK => libc::S_IFLNK,
+            libc::DT_REG => libc::S_IFREG,
+            libc::DT_SOCK => libc::S_IFSOCK,
+            libc::DT_DIR => libc::S_IFDIR,
+            libc::DT_BLK => libc::S_IFBLK,
+            _ => return None,
+        };
+        Some(FileType { mode })
+    }
+
+    #[cfg(any(target_os = "solaris", target_os = "haiku", target_os = "hermit"))]
+    fn file_type_hint(&self) -> Option<FileType> {
+        None
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    fn name_cstring(&self) -> io::Result<CString> {
+        Ok(CString::new(self.name_bytes())?)
+    }
 }
 
 impl OpenOptions {
@@ -604,6 +636,69 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.flock(FileLock::Exclusive, true)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.flock(FileLock::Shared, true)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.flock(FileLock::Exclusive, false)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.flock(FileLock::Shared, false)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        self.flock(FileLock::Unlock, false)
+    }
+
+    #[cfg(not(target_os = "solaris"))]
+    fn flock(&self, lock: FileLock, block: bool) -> io::Result<()> {
+        let mut operation = match lock {
+            FileLock::Shared => libc::LOCK_SH,
+            FileLock::Exclusive => libc::LOCK_EX,
+            FileLock::Unlock => libc::LOCK_UN,
+        };
+        if !block {
+            operation |= libc::LOCK_NB;
+        }
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), operation) })?;
+        Ok(())
+    }
+
+    // Solaris has no `flock`, so lock the whole file with `fcntl` instead.
+    // These locks belong to the process rather than to the open file.
+    #[cfg(target_os = "solaris")]
+    fn flock(&self, lock: FileLock, block: bool) -> io::Result<()> {
+        let mut flock: libc::flock = unsafe { mem::zeroed() };
+        flock.l_type = match lock {
+            FileLock::Shared => libc::F_RDLCK,
+            FileLock::Exclusive => libc::F_WRLCK,
+            FileLock::Unlock => libc::F_UNLCK,
+        } as libc::c_short;
+        flock.l_whence = libc::SEEK_SET as libc::c_short;
+        let cmd = if block { libc::F_SETLKW } else { libc::F_SETLK };
+        match cvt_r(|| unsafe { libc::fcntl(self.0.raw(), cmd, &flock) }) {
+            Ok(_) => Ok(()),
+            // A conflicting lock is reported as either EACCES or EAGAIN.
+            Err(ref e) if e.raw_os_error() == Some(libc::EACCES) => {
+                Err(Error::from_raw_os_error(libc::EAGAIN))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum FileLock {
+    Shared,
+    Exclusive,
+    Unlock,
 }
 
 impl DirBuilder {
@@ -740,6 +835,207 @@ pub fn rmdir(p: &Path) -> io::Result<()> {
     Ok(())
 }
 
+// `remove_dir_all` and `WalkDir` open every directory relative to its
+// already opened parent, with `O_NOFOLLOW` unless links are to be followed.
+// A path looked up again later could have been swapped for a symbolic link
+// in the meantime, which would make `remove_dir_all` delete files outside
+// of the tree it was given.
+//
+// macOS and iOS only have these functions since 10.10 and 8.0, so they keep
+// using paths for now.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub use self::dir_at::{remove_dir_all, WalkDir};
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub use sys_common::fs::WalkDir;
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod dir_at {
+    use ffi::{CStr, CString};
+    use fmt;
+    use io;
+    use libc::{self, c_int};
+    use mem;
+    use path::{Path, PathBuf};
+    use sync::Arc;
+    use sys::{cvt, cvt_r};
+    use sys::fd::FileDesc;
+    use sys_common::fs::loop_error;
+    use super::{cstr, lstat, unlink, fstat64, stat64, Dir, DirEntry, InnerReadDir, ReadDir};
+
+    fn is_not_dir(err: &io::Error) -> bool {
+        match err.raw_os_error() {
+            // `O_DIRECTORY` fails with `ENOTDIR`, and `O_NOFOLLOW` with
+            // `ELOOP` when the entry is a symbolic link.
+            Some(libc::ENOTDIR) | Some(libc::ELOOP) => true,
+            _ => false,
+        }
+    }
+
+    /// Opens the directory `name` relative to `parent`, or to the current
+    /// directory if `parent` is `None`.
+    fn open_dir(parent: Option<c_int>, name: &CStr, follow: bool) -> io::Result<FileDesc> {
+        let mut flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC;
+        if !follow {
+            flags |= libc::O_NOFOLLOW;
+        }
+        let parent = parent.unwrap_or(libc::AT_FDCWD);
+        let fd = cvt_r(|| unsafe { libc::openat(parent, name.as_ptr(), flags) })?;
+        Ok(FileDesc::new(fd))
+    }
+
+    /// Starts reading the directory open as `fd`. The returned descriptor
+    /// belongs to the `ReadDir` and is only valid as long as it is alive.
+    fn read_dir(fd: FileDesc, root: PathBuf) -> io::Result<(ReadDir, c_int)> {
+        let ptr = unsafe { libc::fdopendir(fd.raw()) };
+        if ptr.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        // The stream now owns the descriptor.
+        let fd = fd.into_raw();
+        let inner = InnerReadDir { dirp: Dir(ptr), root };
+        Ok((ReadDir { inner: Arc::new(inner), end_of_stream: false }, fd))
+    }
+
+    fn unlinkat(parent: Option<c_int>, name: &CStr, flags: c_int) -> io::Result<()> {
+        let parent = parent.unwrap_or(libc::AT_FDCWD);
+        cvt(unsafe { libc::unlinkat(parent, name.as_ptr(), flags) })?;
+        Ok(())
+    }
+
+    fn remove_dir_all_recursive(parent: Option<c_int>, name: &CStr) -> io::Result<()> {
+        let fd = match open_dir(parent, name, false) {
+            Ok(fd) => fd,
+            // Not a directory (anymore), so don't descend into it. The root
+            // itself is checked by `remove_dir_all`, and must not be removed
+            // if it was swapped for something else.
+            Err(ref e) if is_not_dir(e) && parent.is_some() => {
+                return unlinkat(parent, name, 0);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (dir, fd) = read_dir(fd, PathBuf::new())?;
+        for child in dir {
+            let child = child?;
+            let child_name = child.name_cstring()?;
+            match child.file_type_hint() {
+                Some(ref t) if !t.is_dir() => unlinkat(Some(fd), &child_name, 0)?,
+                _ => remove_dir_all_recursive(Some(fd), &child_name)?,
+            }
+        }
+        unlinkat(parent, name, libc::AT_REMOVEDIR)
+    }
+
+    pub fn remove_dir_all(path: &Path) -> io::Result<()> {
+        // A symbolic link passed as the root is removed rather than
+        // followed. If it is swapped for a link after this check,
+        // `remove_dir_all_recursive` still won't follow it.
+        if lstat(path)?.file_type().is_symlink() {
+            unlink(path)
+        } else {
+            remove_dir_all_recursive(None, &cstr(path)?)
+        }
+    }
+
+    struct Level {
+        dir: ReadDir,
+        fd: c_int,
+        // The device and inode numbers of the directory, compared to detect
+        // loops when following links.
+        id: (u64, u64),
+    }
+
+    pub struct WalkDir {
+        // The directories being read, from the root down.
+        stack: Vec<Level>,
+        // A directory returned by the last call to `next`, to be opened
+        // relative to the directory at the top of the stack by the following
+        // one.
+        pending: Option<(CString, PathBuf)>,
+        follow_links: bool,
+    }
+
+    impl WalkDir {
+        pub fn new(root: &Path) -> io::Result<WalkDir> {
+            let mut walk = WalkDir { stack: Vec::new(), pending: None, follow_links: false };
+            walk.push(None, &cstr(root)?, root.to_path_buf(), true)?;
+            Ok(walk)
+        }
+
+        pub fn set_follow_links(&mut self, follow_links: bool) {
+            self.follow_links = follow_links;
+        }
+
+        fn push(&mut self, parent: Option<c_int>, name: &CStr, path: PathBuf, follow: bool)
+                -> io::Result<()> {
+            let fd = open_dir(parent, name, follow)?;
+            let mut stat: stat64 = unsafe { mem::zeroed() };
+            cvt(unsafe { fstat64(fd.raw(), &mut stat) })?;
+            let id = (stat.st_dev as u64, stat.st_ino as u64);
+            if self.stack.iter().any(|level| level.id == id) {
+                return Err(loop_error(&path));
+            }
+            let (dir, fd) = read_dir(fd, path)?;
+            self.stack.push(Level { dir, fd, id });
+            Ok(())
+        }
+
+        fn should_descend(&self, entry: &DirEntry) -> bool {
+            match entry.file_type_hint() {
+                Some(t) => t.is_dir() || (t.is_symlink() && self.follow_links),
+                None => true,
+            }
+        }
+    }
+
+    impl Iterator for WalkDir {
+        type Item = io::Result<DirEntry>;
+
+        fn next(&mut self) -> Option<io::Result<DirEntry>> {
+            if let Some((name, path)) = self.pending.take() {
+                let parent = self.stack.last().map(|level| level.fd);
+                let follow = self.follow_links;
+                match self.push(parent, &name, path, follow) {
+                    Ok(()) => {}
+                    // Not a directory after all, or a dangling link.
+                    Err(ref e) if is_not_dir(e) => {}
+                    Err(ref e) if e.raw_os_error() == Some(libc::ENOENT) => {}
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            loop {
+                let next = match self.stack.last_mut() {
+                    Some(level) => level.dir.next(),
+                    None => return None,
+                };
+                match next {
+                    Some(Ok(entry)) => {
+                        if self.should_descend(&entry) {
+                            match entry.name_cstring() {
+                                Ok(name) => self.pending = Some((name, entry.path())),
+                                Err(e) => return Some(Err(e)),
+                            }
+                        }
+                        return Some(Ok(entry));
+                    }
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => { self.stack.pop(); }
+                }
+            }
+        }
+    }
+
+    impl fmt::Debug for WalkDir {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("WalkDir")
+                .field("follow_links", &self.follow_links)
+                .finish()
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     let filetype = lstat(path)?.file_type();
     if filetype.is_symlink() {
@@ -749,6 +1045,7 @@ pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     }
 }
 
+#[cfg(any(target_os = "macos", target_os = "ios"))]
 fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
     for child in readdir(path)? {
         let child = child?;
@@ -833,6 +1130,52 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     Ok(PathBuf::from(OsString::from_vec(buf)))
 }
 
+/// Creates an unnamed temporary file in `dir`, if the platform and the
+/// filesystem support it.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn tmpfile(dir: &Path) -> io::Result<Option<File>> {
+    let dir = cstr(dir)?;
+    let flags = libc::O_TMPFILE | libc::O_RDWR | libc::O_CLOEXEC;
+    match cvt_r(|| unsafe { open64(dir.as_ptr(), flags, 0o600) }) {
+        Ok(fd) => Ok(Some(File(FileDesc::new(fd)))),
+        // Kernels older than 3.11 ignore the unknown flag and fail to open
+        // the directory for writing, and some filesystems don't support it.
+        Err(ref e) if e.raw_os_error() == Some(libc::EISDIR) ||
+                      e.raw_os_error() == Some(libc::EOPNOTSUPP) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+/// Gives a name to a file created by `tmpfile`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn link_tmpfile(file: &File, dst: &Path) -> io::Result<()> {
+    let src = CString::new(format!("/proc/self/fd/{}", file.0.raw()))?;
+    let dst = cstr(dst)?;
+    cvt(unsafe {
+        libc::linkat(libc::AT_FDCWD, src.as_ptr(), libc::AT_FDCWD, dst.as_ptr(),
+                     libc::AT_SYMLINK_FOLLOW)
+    })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn link_tmpfile(_file: &File, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "unnamed temporary files are not supported"))
+}
+
+/// Flushes the entries of the directory `p` to disk, making the creation,
+/// removal or renaming of files in it durable.
+pub fn fsync_dir(p: &Path) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.read(true);
+    File::open(p, &opts)?.fsync()
+}
+
 #[cfg(not(any(target_os = "linux", target_os = "android")))]
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     use fs::File;
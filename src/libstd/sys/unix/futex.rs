@@ -0,0 +1,84 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Thin wrappers around the Linux `futex` system call.
+//!
+//! All futexes used by the standard library are process-private, so every
+//! operation passes `FUTEX_PRIVATE_FLAG` which lets the kernel skip the
+//! shared-mapping lookup.
+
+use libc;
+use ptr;
+use sync::atomic::{AtomicU32, Ordering};
+use sys::os::errno;
+use time::Duration;
+
+/// Waits for a `futex_wake` operation to wake us.
+///
+/// Returns directly if the futex doesn't hold the expected value.
+///
+/// Returns false on timeout, and true in all other cases. Like all futex
+/// waits, this may return spuriously (e.g. when interrupted by a signal), so
+/// callers must re-check their condition in a loop.
+pub fn futex_wait(futex: &AtomicU32, expected: u32, timeout: Option<Duration>) -> bool {
+    // Durations which don't fit in a `timespec` are treated as "forever".
+    let timespec = timeout.and_then(|dur| {
+        if dur.as_secs() > <libc::time_t>::max_value() as u64 {
+            None
+        } else {
+            Some(libc::timespec {
+                tv_sec: dur.as_secs() as libc::time_t,
+                tv_nsec: dur.subsec_nanos() as _,
+            })
+        }
+    });
+
+    if futex.load(Ordering::Relaxed) != expected {
+        return true;
+    }
+
+    let r = unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            timespec.as_ref().map_or(ptr::null(), |t| t as *const libc::timespec),
+        )
+    };
+    !(r < 0 && errno() == libc::ETIMEDOUT)
+}
+
+/// Wakes up one thread that's blocked on `futex_wait` on this futex.
+///
+/// Returns true if this actually woke up such a thread, or false if no thread
+/// was waiting on this futex.
+pub fn futex_wake(futex: &AtomicU32) -> bool {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            1,
+        ) > 0
+    }
+}
+
+/// Wakes up all threads that are waiting on `futex_wait` on this futex.
+pub fn futex_wake_all(futex: &AtomicU32) {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            i32::max_value(),
+        );
+    }
+}
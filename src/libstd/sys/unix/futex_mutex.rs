@@ -0,0 +1,215 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A mutex built on a single atomic word and the `futex` system call.
+//!
+//! Unlike a `pthread_mutex_t`, this mutex holds no OS state and may be moved
+//! freely, so it does not need to be boxed and can be created in a `const fn`.
+//!
+//! The state word takes one of five values:
+//!
+//! * `UNLOCKED` - nobody holds the lock.
+//! * `LOCKED` - somebody holds the lock and nobody is waiting for it.
+//! * `CONTENDED` - somebody holds the lock and there may be threads asleep in
+//!                 `futex_wait`, so the unlocking thread has to wake one.
+//! * `STARVING` - like `CONTENDED`, but a waiter has been woken up and then
+//!                lost the race for the lock to a newly arriving thread too
+//!                many times. The next unlock hands the lock over directly.
+//! * `HANDOFF` - the previous owner released the lock on behalf of the thread
+//!               it just woke up. Only a thread returning from `futex_wait` may
+//!               take the lock in this state; new arrivals must queue up.
+//!
+//! The handoff gives the mutex eventual fairness: threads barging in on the
+//! fast path can win the lock repeatedly, but never indefinitely, because a
+//! waiter which keeps losing forces the next unlock to go to a sleeping
+//! thread instead.
+
+use sync::atomic::{AtomicU32, Ordering, spin_loop_hint};
+use sys::futex::{futex_wait, futex_wake};
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+const CONTENDED: u32 = 2;
+const STARVING: u32 = 3;
+const HANDOFF: u32 = 4;
+
+// How many times a waiter may be woken up without acquiring the lock before
+// it asks for the lock to be handed to a sleeping thread.
+const STARVATION_LIMIT: u32 = 2;
+
+// How many times to spin on a held lock before going to sleep.
+const SPIN_LIMIT: u32 = 100;
+
+pub struct Mutex {
+    futex: AtomicU32,
+}
+
+// The futex mutex has no address-sensitive state, so it can live inline.
+pub type MovableMutex = Mutex;
+
+impl Mutex {
+    #[inline]
+    pub const fn new() -> Mutex {
+        Mutex { futex: AtomicU32::new(UNLOCKED) }
+    }
+
+    #[inline]
+    pub unsafe fn init(&mut self) {}
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        self.futex.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok()
+    }
+
+    #[inline]
+    pub unsafe fn lock(&self) {
+        if self.futex.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            self.lock_contended();
+        }
+    }
+
+    #[cold]
+    fn lock_contended(&self) {
+        // Spin first to speed things up if the lock is released quickly.
+        let mut state = self.spin();
+
+        // If it's unlocked now, attempt to take the lock
+        // without marking it as contended.
+        if state == UNLOCKED {
+            match self.futex.compare_exchange(UNLOCKED, LOCKED,
+                                              Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return, // Locked!
+                Err(s) => state = s,
+            }
+        }
+
+        // Whether we have been woken up by an unlocking thread, which entitles
+        // us to take a lock which is being handed off.
+        let mut woken = false;
+        let mut times_lost = 0;
+
+        loop {
+            // Work out which state to move to if we manage to take the lock,
+            // or which state to sleep on if we don't. We always leave the
+            // lock as CONTENDED when we take it: there may be other waiters
+            // asleep, and we can't know there aren't.
+            let (acquire, sleep_on) = match state {
+                UNLOCKED => (true, state),
+                HANDOFF if woken => (true, state),
+                LOCKED | CONTENDED if times_lost >= STARVATION_LIMIT => (false, STARVING),
+                LOCKED => (false, CONTENDED),
+                _ => (false, state),
+            };
+
+            if acquire {
+                match self.futex.compare_exchange(state, CONTENDED,
+                                                  Ordering::Acquire, Ordering::Relaxed) {
+                    Ok(_) => return, // Locked!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            if sleep_on != state {
+                if let Err(s) = self.futex.compare_exchange(state, sleep_on,
+                                                            Ordering::Relaxed,
+                                                            Ordering::Relaxed) {
+                    state = s;
+                    continue;
+                }
+            }
+
+            if woken {
+                times_lost += 1;
+            }
+
+            // Wait for the futex to change state, assuming it is still
+            // `sleep_on`.
+            futex_wait(&self.futex, sleep_on, None);
+            woken = true;
+
+            // Spin again after waking up.
+            state = self.spin();
+        }
+    }
+
+    fn spin(&self) -> u32 {
+        let mut spin = SPIN_LIMIT;
+        loop {
+            // We only use `load` (and not `swap` or `compare_exchange`)
+            // while spinning, to be easier on the caches.
+            let state = self.futex.load(Ordering::Relaxed);
+
+            // We stop spinning when the mutex is unlocked or being handed
+            // off, but also when it's contended.
+            if state != LOCKED || spin == 0 {
+                return state;
+            }
+
+            spin_loop_hint();
+            spin -= 1;
+        }
+    }
+
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        if self.futex.compare_exchange(LOCKED, UNLOCKED, Ordering::Release, Ordering::Relaxed)
+            .is_err()
+        {
+            // We only wake up one thread. When that thread locks the mutex, it
+            // will mark the mutex as CONTENDED (see `lock_contended` above),
+            // which makes sure that any other waiting threads will also be
+            // woken up eventually.
+            self.unlock_contended();
+        }
+    }
+
+    #[cold]
+    fn unlock_contended(&self) {
+        // While we hold the lock, waiters only ever move the state forward
+        // from LOCKED to CONTENDED to STARVING, so retry until we observe
+        // the final value.
+        let mut state = self.futex.load(Ordering::Relaxed);
+        loop {
+            let new = if state == STARVING { HANDOFF } else { UNLOCKED };
+            match self.futex.compare_exchange(state, new, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => break,
+                Err(s) => state = s,
+            }
+        }
+
+        if state != STARVING {
+            futex_wake(&self.futex);
+            return;
+        }
+
+        if futex_wake(&self.futex) {
+            // The woken thread will take over the lock.
+            return;
+        }
+
+        // Nobody was asleep to receive the lock (the starving waiter is on
+        // its way back to `futex_wait`). Release it normally instead, unless
+        // a previously woken waiter has already picked it up.
+        if self.futex.compare_exchange(HANDOFF, UNLOCKED, Ordering::Release, Ordering::Relaxed)
+            .is_ok()
+        {
+            // Somebody may have gone to sleep on HANDOFF in the meantime.
+            futex_wake(&self.futex);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn destroy(&self) {}
+}
@@ -0,0 +1,419 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Zero-copy implementation of `io::copy` for file descriptors.
+//!
+//! When both ends of an `io::copy` are types known to wrap a file descriptor, the data can be
+//! moved by the kernel instead of going through a userspace buffer:
+//!
+//! * `copy_file_range` between two regular files,
+//! * `sendfile` from a regular file to anything,
+//! * `splice` when either end is a pipe.
+//!
+//! Each of these is only attempted when the types involved make it applicable, and all of
+//! them fall back to the generic read/write loop if the kernel turns out not to support
+//! them (too old, a seccomp filter, an unsupported file system, ...). Only unbuffered types
+//! participate: a `BufReader` or `Stdin` may hold data the kernel doesn't know about.
+
+use fs::File;
+use io::{self, Read, Write};
+use io::util::generic_copy;
+use net::TcpStream;
+use os::unix::fs::FileTypeExt;
+use os::unix::io::{AsRawFd, RawFd};
+use os::unix::net::UnixStream;
+use process::{ChildStdin, ChildStdout, ChildStderr};
+use ptr;
+use sync::atomic::{AtomicBool, Ordering};
+use sys::cvt;
+
+use libc;
+
+pub fn copy_spec<R: Read + ?Sized, W: Write + ?Sized>(read: &mut R, write: &mut W)
+                                                      -> io::Result<u64> {
+    Copier { read, write }.copy()
+}
+
+/// What is known about a file descriptor, used to pick a syscall.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum FdKind {
+    File,
+    Pipe,
+    Socket,
+    Other,
+}
+
+impl FdKind {
+    fn of_file(file: &File) -> FdKind {
+        match file.metadata() {
+            Ok(ref meta) if meta.file_type().is_file() => FdKind::File,
+            Ok(ref meta) if meta.file_type().is_fifo() => FdKind::Pipe,
+            Ok(ref meta) if meta.file_type().is_socket() => FdKind::Socket,
+            _ => FdKind::Other,
+        }
+    }
+}
+
+/// A readable type wrapping a file descriptor without any buffering of its own.
+trait CopyRead: Read {
+    fn properties(&self) -> (RawFd, FdKind);
+}
+
+/// A writable type wrapping a file descriptor without any buffering of its own.
+trait CopyWrite: Write {
+    fn properties(&self) -> (RawFd, FdKind);
+}
+
+impl CopyRead for File {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::of_file(self))
+    }
+}
+
+impl<'a> CopyRead for &'a File {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::of_file(self))
+    }
+}
+
+impl CopyWrite for File {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::of_file(self))
+    }
+}
+
+impl<'a> CopyWrite for &'a File {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::of_file(self))
+    }
+}
+
+macro_rules! impl_copy_fd {
+    ($kind:expr => $($t:ty),*) => {$(
+        impl CopyRead for $t {
+            fn properties(&self) -> (RawFd, FdKind) {
+                (self.as_raw_fd(), $kind)
+            }
+        }
+
+        impl CopyWrite for $t {
+            fn properties(&self) -> (RawFd, FdKind) {
+                (self.as_raw_fd(), $kind)
+            }
+        }
+    )*}
+}
+
+impl_copy_fd!(FdKind::Socket => TcpStream, UnixStream);
+
+impl<'a> CopyRead for &'a TcpStream {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Socket)
+    }
+}
+
+impl<'a> CopyWrite for &'a TcpStream {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Socket)
+    }
+}
+
+impl<'a> CopyRead for &'a UnixStream {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Socket)
+    }
+}
+
+impl<'a> CopyWrite for &'a UnixStream {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Socket)
+    }
+}
+
+impl CopyWrite for ChildStdin {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Pipe)
+    }
+}
+
+impl CopyRead for ChildStdout {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Pipe)
+    }
+}
+
+impl CopyRead for ChildStderr {
+    fn properties(&self) -> (RawFd, FdKind) {
+        (self.as_raw_fd(), FdKind::Pipe)
+    }
+}
+
+struct Copier<'a, 'b, R: Read + ?Sized + 'a, W: Write + ?Sized + 'b> {
+    read: &'a mut R,
+    write: &'b mut W,
+}
+
+trait SpecCopy {
+    fn copy(self) -> io::Result<u64>;
+}
+
+impl<'a, 'b, R: Read + ?Sized, W: Write + ?Sized> SpecCopy for Copier<'a, 'b, R, W> {
+    default fn copy(self) -> io::Result<u64> {
+        generic_copy(self.read, self.write)
+    }
+}
+
+impl<'a, 'b, R: CopyRead + ?Sized, W: CopyWrite + ?Sized> SpecCopy for Copier<'a, 'b, R, W> {
+    fn copy(self) -> io::Result<u64> {
+        let (reader, read_kind) = self.read.properties();
+        let (writer, write_kind) = self.write.properties();
+
+        let mut written = 0;
+
+        if read_kind == FdKind::File && write_kind == FdKind::File {
+            match copy_regular_files(reader, writer) {
+                CopyResult::Ended(n) => return Ok(written + n),
+                CopyResult::Error(e) => return Err(e),
+                CopyResult::Fallback(n) => written += n,
+            }
+        }
+
+        if read_kind == FdKind::File {
+            match sendfile_splice(SpliceMode::Sendfile, reader, writer) {
+                CopyResult::Ended(n) => return Ok(written + n),
+                CopyResult::Error(e) => return Err(e),
+                CopyResult::Fallback(n) => written += n,
+            }
+        }
+
+        if read_kind == FdKind::Pipe || write_kind == FdKind::Pipe {
+            match sendfile_splice(SpliceMode::Splice, reader, writer) {
+                CopyResult::Ended(n) => return Ok(written + n),
+                CopyResult::Error(e) => return Err(e),
+                CopyResult::Fallback(n) => written += n,
+            }
+        }
+
+        Ok(written + generic_copy(self.read, self.write)?)
+    }
+}
+
+enum CopyResult {
+    /// The reader reached EOF after this many bytes.
+    Ended(u64),
+    /// The copy failed.
+    Error(io::Error),
+    /// The syscall can't be used for these file descriptors; this many bytes were copied
+    /// before that was found out.
+    Fallback(u64),
+}
+
+/// How much to move per syscall. Large enough to make the syscall overhead disappear, small
+/// enough to not trip over 32-bit `ssize_t`s.
+const MAX_CHUNK: usize = 0x4000_0000;
+
+// The kernel versions before 4.5 don't have copy_file_range and a seccomp filter may deny
+// any of these, so their availability is remembered to avoid unnecessary syscalls.
+static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+static HAS_SENDFILE: AtomicBool = AtomicBool::new(true);
+static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
+
+fn copy_regular_files(reader: RawFd, writer: RawFd) -> CopyResult {
+    unsafe fn copy_file_range(
+        fd_in: libc::c_int,
+        off_in: *mut libc::loff_t,
+        fd_out: libc::c_int,
+        off_out: *mut libc::loff_t,
+        len: libc::size_t,
+        flags: libc::c_uint,
+    ) -> libc::c_long {
+        libc::syscall(
+            libc::SYS_copy_file_range,
+            fd_in,
+            off_in,
+            fd_out,
+            off_out,
+            len,
+            flags,
+        )
+    }
+
+    if !HAS_COPY_FILE_RANGE.load(Ordering::Relaxed) {
+        return CopyResult::Fallback(0);
+    }
+
+    let mut written = 0u64;
+    loop {
+        // The file offsets don't need to be passed in, as copy_file_range uses and
+        // updates them just like read and write do.
+        let result = cvt(unsafe {
+            copy_file_range(reader, ptr::null_mut(), writer, ptr::null_mut(), MAX_CHUNK, 0)
+        });
+        match result {
+            // Some file systems (procfs, sysfs, ...) report their files as empty to
+            // copy_file_range, so only trust an immediate EOF from a real read.
+            Ok(0) if written == 0 => return CopyResult::Fallback(0),
+            Ok(0) => return CopyResult::Ended(written),
+            Ok(n) => written += n as u64,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                match e.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+                        return CopyResult::Fallback(written);
+                    }
+                    // - the files are on different file systems (EXDEV)
+                    // - the file system doesn't support it (EINVAL, EOPNOTSUPP)
+                    // - the output was opened with O_APPEND (EBADF)
+                    Some(libc::EXDEV) | Some(libc::EINVAL) | Some(libc::EOPNOTSUPP) |
+                    Some(libc::EBADF) if written == 0 => return CopyResult::Fallback(0),
+                    _ => return CopyResult::Error(e),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum SpliceMode {
+    Sendfile,
+    Splice,
+}
+
+fn sendfile_splice(mode: SpliceMode, reader: RawFd, writer: RawFd) -> CopyResult {
+    let available = match mode {
+        SpliceMode::Sendfile => &HAS_SENDFILE,
+        SpliceMode::Splice => &HAS_SPLICE,
+    };
+    if !available.load(Ordering::Relaxed) {
+        return CopyResult::Fallback(0);
+    }
+
+    let mut written = 0u64;
+    loop {
+        let result = match mode {
+            SpliceMode::Sendfile => cvt(unsafe {
+                libc::sendfile(writer, reader, ptr::null_mut(), MAX_CHUNK)
+            }),
+            SpliceMode::Splice => cvt(unsafe {
+                libc::splice(reader, ptr::null_mut(), writer, ptr::null_mut(), MAX_CHUNK, 0)
+            }),
+        };
+        match result {
+            Ok(0) => return CopyResult::Ended(written),
+            Ok(n) => written += n as u64,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                match e.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        available.store(false, Ordering::Relaxed);
+                        return CopyResult::Fallback(written);
+                    }
+                    // The file descriptors don't support this syscall.
+                    Some(libc::EINVAL) if written == 0 => return CopyResult::Fallback(0),
+                    _ => return CopyResult::Error(e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use fs::{File, OpenOptions};
+    use io::{self, Read, Seek, SeekFrom, Write};
+    use net::TcpListener;
+    use os::unix::net::UnixStream;
+    use process::{Command, Stdio};
+    use net::TcpStream;
+    use sys_common::io::test::{tmpdir, TempDir};
+    use thread;
+
+    fn contents() -> Vec<u8> {
+        (0..100_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn file_with_contents(name: &str) -> (TempDir, File) {
+        let dir = tmpdir();
+        let mut file = OpenOptions::new().read(true).write(true).create(true)
+                                         .open(dir.join(name)).unwrap();
+        file.write_all(&contents()).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        (dir, file)
+    }
+
+    #[test]
+    fn copy_file_to_file() {
+        let (dir, mut source) = file_with_contents("source");
+        // Copying starts at the current offset, like it would with read.
+        source.seek(SeekFrom::Start(10)).unwrap();
+        let mut sink = File::create(dir.join("sink")).unwrap();
+        assert_eq!(io::copy(&mut source, &mut sink).unwrap(), 100_000 - 10);
+
+        let mut copied = Vec::new();
+        File::open(dir.join("sink")).unwrap().read_to_end(&mut copied).unwrap();
+        assert_eq!(copied, &contents()[10..]);
+    }
+
+    #[test]
+    fn copy_file_to_appending_file() {
+        let (dir, mut source) = file_with_contents("source");
+        File::create(dir.join("sink")).unwrap().write_all(b"head").unwrap();
+        let mut sink = OpenOptions::new().append(true).open(dir.join("sink")).unwrap();
+        assert_eq!(io::copy(&mut source, &mut sink).unwrap(), 100_000);
+
+        let mut copied = Vec::new();
+        File::open(dir.join("sink")).unwrap().read_to_end(&mut copied).unwrap();
+        assert_eq!(&copied[..4], b"head");
+        assert_eq!(&copied[4..], &contents()[..]);
+    }
+
+    #[test]
+    fn copy_file_to_socket() {
+        let (_dir, mut source) = file_with_contents("source");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let reader = thread::spawn(move || {
+            let mut received = Vec::new();
+            listener.accept().unwrap().0.read_to_end(&mut received).unwrap();
+            received
+        });
+        {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            assert_eq!(io::copy(&mut source, &mut stream).unwrap(), 100_000);
+        }
+        assert_eq!(reader.join().unwrap(), contents());
+    }
+
+    #[test]
+    fn copy_pipe_to_socket() {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let writer = thread::spawn(move || stdin.write_all(&contents()).unwrap());
+
+        let (mut ours, mut theirs) = UnixStream::pair().unwrap();
+        let reader = thread::spawn(move || {
+            let mut received = Vec::new();
+            theirs.read_to_end(&mut received).unwrap();
+            received
+        });
+        let mut stdout = child.stdout.take().unwrap();
+        assert_eq!(io::copy(&mut stdout, &mut ours).unwrap(), 100_000);
+        drop(ours);
+
+        writer.join().unwrap();
+        assert_eq!(reader.join().unwrap(), contents());
+        assert!(child.wait().unwrap().success());
+    }
+}
@@ -419,6 +419,138 @@ pub mod net {
         }
     }
 
+    pub struct TcpSocket {
+        inner: Socket,
+    }
+
+    impl TcpSocket {
+        pub fn new(_: &SocketAddr) -> io::Result<TcpSocket> {
+            unimpl!();
+        }
+
+        pub fn set_reuseaddr(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn reuseaddr(&self) -> io::Result<bool> {
+            unimpl!();
+        }
+
+        pub fn set_reuseport(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn set_send_buffer_size(&self, _: usize) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn send_buffer_size(&self) -> io::Result<usize> {
+            unimpl!();
+        }
+
+        pub fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn recv_buffer_size(&self) -> io::Result<usize> {
+            unimpl!();
+        }
+
+        pub fn set_keepalive(&self, _: Option<Duration>) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn set_nodelay(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn set_only_v6(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn bind(&self, _: &SocketAddr) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+            unimpl!();
+        }
+
+        pub fn listen(self, _: u32) -> io::Result<TcpListener> {
+            unimpl!();
+        }
+
+        pub fn connect(self, _: &SocketAddr) -> io::Result<TcpStream> {
+            unimpl!();
+        }
+
+        pub fn connect_timeout(self, _: &SocketAddr, _: Duration) -> io::Result<TcpStream> {
+            unimpl!();
+        }
+    }
+
+    impl fmt::Debug for TcpSocket {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "No networking support on L4Re available.")
+        }
+    }
+
+    pub struct UdpSocketBuilder {
+        inner: Socket,
+    }
+
+    impl UdpSocketBuilder {
+        pub fn new(_: &SocketAddr) -> io::Result<UdpSocketBuilder> {
+            unimpl!();
+        }
+
+        pub fn set_reuseaddr(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn reuseaddr(&self) -> io::Result<bool> {
+            unimpl!();
+        }
+
+        pub fn set_reuseport(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn set_send_buffer_size(&self, _: usize) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn send_buffer_size(&self) -> io::Result<usize> {
+            unimpl!();
+        }
+
+        pub fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn recv_buffer_size(&self) -> io::Result<usize> {
+            unimpl!();
+        }
+
+        pub fn set_broadcast(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn set_only_v6(&self, _: bool) -> io::Result<()> {
+            unimpl!();
+        }
+
+        pub fn bind(self, _: &SocketAddr) -> io::Result<UdpSocket> {
+            unimpl!();
+        }
+    }
+
+    impl fmt::Debug for UdpSocketBuilder {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "No networking support on L4Re available.")
+        }
+    }
+
     pub struct LookupHost {
         original: *mut libc::addrinfo,
         cur: *mut libc::addrinfo,
@@ -41,12 +41,19 @@ pub mod android;
 #[cfg(feature = "backtrace")]
 pub mod backtrace;
 pub mod cmath;
+#[cfg_attr(target_os = "linux", path = "futex_condvar.rs")]
 pub mod condvar;
 pub mod env;
 pub mod ext;
 pub mod fast_thread_local;
 pub mod fd;
 pub mod fs;
+#[cfg(target_os = "linux")]
+pub mod futex;
+#[cfg(target_os = "linux")]
+pub mod futex_mutex;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod kernel_copy;
 pub mod memchr;
 pub mod mutex;
 #[cfg(not(target_os = "l4re"))]
@@ -61,6 +68,7 @@ pub mod path;
 pub mod pipe;
 pub mod process;
 pub mod rand;
+#[cfg_attr(target_os = "linux", path = "futex_rwlock.rs")]
 pub mod rwlock;
 pub mod stack_overflow;
 pub mod thread;
@@ -12,16 +12,30 @@ use cell::UnsafeCell;
 use libc;
 use mem;
 
+// On Linux the `Mutex` is built directly on futexes, which lets it be moved
+// and constructed without allocation. The reentrant mutex below is only ever
+// used boxed, so it keeps using pthreads everywhere.
+#[cfg(target_os = "linux")]
+pub use sys::futex_mutex::{Mutex, MovableMutex};
+
+#[cfg(not(target_os = "linux"))]
 pub struct Mutex { inner: UnsafeCell<libc::pthread_mutex_t> }
 
+#[cfg(not(target_os = "linux"))]
+pub type MovableMutex = Box<Mutex>;
+
+#[cfg(not(target_os = "linux"))]
 #[inline]
 pub unsafe fn raw(m: &Mutex) -> *mut libc::pthread_mutex_t {
     m.inner.get()
 }
 
+#[cfg(not(target_os = "linux"))]
 unsafe impl Send for Mutex {}
+#[cfg(not(target_os = "linux"))]
 unsafe impl Sync for Mutex {}
 
+#[cfg(not(target_os = "linux"))]
 #[allow(dead_code)] // sys isn't exported yet
 impl Mutex {
     pub const fn new() -> Mutex {
@@ -338,6 +338,14 @@ impl Socket {
         Ok(raw != 0)
     }
 
+    pub fn set_keepalive(&self, keepalive: Option<Duration>) -> io::Result<()> {
+        setsockopt(self, libc::SOL_SOCKET, libc::SO_KEEPALIVE, keepalive.is_some() as c_int)?;
+        match keepalive {
+            Some(dur) => set_keepalive_idle(self, dur),
+            None => Ok(()),
+        }
+    }
+
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         let mut nonblocking = nonblocking as libc::c_int;
         cvt(unsafe { libc::ioctl(*self.as_inner(), libc::FIONBIO, &mut nonblocking) }).map(|_| ())
@@ -353,6 +361,37 @@ impl Socket {
     }
 }
 
+// Sets how long a connection stays idle before keepalive probes are sent.
+// The option is in whole seconds, and has no portable name.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "freebsd", target_os = "netbsd",
+          target_os = "dragonfly"))]
+fn set_keepalive_idle(sock: &Socket, dur: Duration) -> io::Result<()> {
+    setsockopt(sock, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, keepalive_secs(dur))
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn set_keepalive_idle(sock: &Socket, dur: Duration) -> io::Result<()> {
+    setsockopt(sock, libc::IPPROTO_TCP, libc::TCP_KEEPALIVE, keepalive_secs(dur))
+}
+
+// Elsewhere the idle time can only be configured system-wide.
+#[cfg(not(any(target_os = "linux", target_os = "android",
+              target_os = "freebsd", target_os = "netbsd",
+              target_os = "dragonfly", target_os = "macos",
+              target_os = "ios")))]
+fn set_keepalive_idle(_sock: &Socket, _dur: Duration) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "freebsd", target_os = "netbsd",
+          target_os = "dragonfly", target_os = "macos",
+          target_os = "ios"))]
+fn keepalive_secs(dur: Duration) -> c_int {
+    cmp::min(cmp::max(dur.as_secs(), 1), c_int::max_value() as u64) as c_int
+}
+
 impl AsInner<c_int> for Socket {
     fn as_inner(&self) -> &c_int { self.0.as_inner() }
 }
@@ -13,6 +13,8 @@ use time::Duration;
 
 pub struct Condvar { }
 
+pub type MovableCondvar = Box<Condvar>;
+
 impl Condvar {
     pub const fn new() -> Condvar {
         Condvar { }
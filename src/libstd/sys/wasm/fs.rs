@@ -16,6 +16,8 @@ use path::{Path, PathBuf};
 use sys::time::SystemTime;
 use sys::{unsupported, Void};
 
+pub use sys_common::fs::WalkDir;
+
 pub struct File(Void);
 
 pub struct FileAttr(Void);
@@ -230,6 +232,26 @@ impl File {
         match self.0 {}
     }
 
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
@@ -299,6 +321,18 @@ pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
     unsupported()
 }
 
+pub fn tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    unsupported()
+}
+
+pub fn link_tmpfile(file: &File, _dst: &Path) -> io::Result<()> {
+    match file.0 {}
+}
+
+pub fn fsync_dir(_p: &Path) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn copy(_from: &Path, _to: &Path) -> io::Result<u64> {
     unsupported()
 }
@@ -113,6 +113,7 @@ pub const FILE_FLAG_BACKUP_SEMANTICS: DWORD = 0x02000000;
 pub const SECURITY_SQOS_PRESENT: DWORD = 0x00100000;
 
 pub const FIONBIO: c_ulong = 0x8004667e;
+pub const SIO_KEEPALIVE_VALS: DWORD = 0x98000004;
 
 #[repr(C)]
 #[derive(Copy)]
@@ -179,6 +180,7 @@ pub const ERROR_ACCESS_DENIED: DWORD = 5;
 pub const ERROR_INVALID_HANDLE: DWORD = 6;
 pub const ERROR_NO_MORE_FILES: DWORD = 18;
 pub const ERROR_HANDLE_EOF: DWORD = 38;
+pub const ERROR_LOCK_VIOLATION: DWORD = 33;
 pub const ERROR_FILE_EXISTS: DWORD = 80;
 pub const ERROR_INVALID_PARAMETER: DWORD = 87;
 pub const ERROR_BROKEN_PIPE: DWORD = 109;
@@ -231,6 +233,9 @@ pub const SOL_SOCKET: c_int = 0xffff;
 pub const SO_RCVTIMEO: c_int = 0x1006;
 pub const SO_SNDTIMEO: c_int = 0x1005;
 pub const SO_REUSEADDR: c_int = 0x0004;
+pub const SO_KEEPALIVE: c_int = 0x0008;
+pub const SO_SNDBUF: c_int = 0x1001;
+pub const SO_RCVBUF: c_int = 0x1002;
 pub const IPPROTO_IP: c_int = 0;
 pub const IPPROTO_TCP: c_int = 6;
 pub const IPPROTO_IPV6: c_int = 41;
@@ -289,6 +294,8 @@ pub const PIPE_ACCESS_INBOUND: DWORD = 0x00000001;
 pub const PIPE_ACCESS_OUTBOUND: DWORD = 0x00000002;
 pub const FILE_FLAG_FIRST_PIPE_INSTANCE: DWORD = 0x00080000;
 pub const FILE_FLAG_OVERLAPPED: DWORD = 0x40000000;
+pub const LOCKFILE_FAIL_IMMEDIATELY: DWORD = 0x00000001;
+pub const LOCKFILE_EXCLUSIVE_LOCK: DWORD = 0x00000002;
 pub const PIPE_WAIT: DWORD = 0x00000000;
 pub const PIPE_TYPE_BYTE: DWORD = 0x00000000;
 pub const PIPE_REJECT_REMOTE_CLIENTS: DWORD = 0x00000008;
@@ -885,6 +892,13 @@ pub struct timeval {
     pub tv_usec: c_long,
 }
 
+#[repr(C)]
+pub struct tcp_keepalive {
+    pub onoff: c_ulong,
+    pub keepalivetime: c_ulong,
+    pub keepaliveinterval: c_ulong,
+}
+
 extern "system" {
     pub fn WSAStartup(wVersionRequested: WORD,
                       lpWSAData: LPWSADATA) -> c_int;
@@ -902,6 +916,15 @@ extern "system" {
                       g: GROUP,
                       dwFlags: DWORD) -> SOCKET;
     pub fn ioctlsocket(s: SOCKET, cmd: c_long, argp: *mut c_ulong) -> c_int;
+    pub fn WSAIoctl(s: SOCKET,
+                    dwIoControlCode: DWORD,
+                    lpvInBuffer: LPVOID,
+                    cbInBuffer: DWORD,
+                    lpvOutBuffer: LPVOID,
+                    cbOutBuffer: DWORD,
+                    lpcbBytesReturned: LPDWORD,
+                    lpOverlapped: LPOVERLAPPED,
+                    lpCompletionRoutine: LPVOID) -> c_int;
     pub fn InitializeCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
     pub fn EnterCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
     pub fn TryEnterCriticalSection(CriticalSection: *mut CRITICAL_SECTION) -> BOOLEAN;
@@ -1020,6 +1043,10 @@ extern "system" {
     pub fn DeleteFileW(lpPathName: LPCWSTR) -> BOOL;
     pub fn GetCurrentDirectoryW(nBufferLength: DWORD, lpBuffer: LPWSTR) -> DWORD;
     pub fn SetCurrentDirectoryW(lpPathName: LPCWSTR) -> BOOL;
+    pub fn GetFullPathNameW(lpFileName: LPCWSTR,
+                            nBufferLength: DWORD,
+                            lpBuffer: LPWSTR,
+                            lpFilePart: *mut LPWSTR) -> DWORD;
     pub fn WideCharToMultiByte(CodePage: UINT,
                                dwFlags: DWORD,
                                lpWideCharStr: LPCWSTR,
@@ -1088,6 +1115,19 @@ extern "system" {
                             dwMoveMethod: DWORD)
                             -> BOOL;
     pub fn FlushFileBuffers(hFile: HANDLE) -> BOOL;
+    pub fn LockFileEx(hFile: HANDLE,
+                      dwFlags: DWORD,
+                      dwReserved: DWORD,
+                      nNumberOfBytesToLockLow: DWORD,
+                      nNumberOfBytesToLockHigh: DWORD,
+                      lpOverlapped: LPOVERLAPPED)
+                      -> BOOL;
+    pub fn UnlockFile(hFile: HANDLE,
+                      dwFileOffsetLow: DWORD,
+                      dwFileOffsetHigh: DWORD,
+                      nNumberOfBytesToUnlockLow: DWORD,
+                      nNumberOfBytesToUnlockHigh: DWORD)
+                      -> BOOL;
     pub fn CreateFileW(lpFileName: LPCWSTR,
                        dwDesiredAccess: DWORD,
                        dwShareMode: DWORD,
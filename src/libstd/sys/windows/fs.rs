@@ -25,6 +25,8 @@ use sys_common::FromInner;
 
 use super::to_u16s;
 
+pub use sys_common::fs::WalkDir;
+
 pub struct File { handle: Handle }
 
 #[derive(Clone)]
@@ -358,6 +360,36 @@ impl File {
         })
     }
 
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock_file(0)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK | c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    fn lock_file(&self, flags: c::DWORD) -> io::Result<()> {
+        // Lock the whole file, including any part it may grow into later.
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            c::LockFileEx(self.handle.raw(), flags, 0, !0, !0, &mut overlapped)
+        })?;
+        Ok(())
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe { c::UnlockFile(self.handle.raw(), 0, 0, !0, !0) })?;
+        Ok(())
+    }
+
     pub fn handle(&self) -> &Handle { &self.handle }
 
     pub fn into_handle(self) -> Handle { self.handle }
@@ -724,6 +756,20 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     get_path(&f)
 }
 
+pub fn tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+pub fn link_tmpfile(_file: &File, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "unnamed temporary files are not supported"))
+}
+
+pub fn fsync_dir(_p: &Path) -> io::Result<()> {
+    // Directories can't be flushed on Windows. NTFS journals the changes to
+    // directory entries along with the file metadata instead.
+    Ok(())
+}
+
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     unsafe extern "system" fn callback(
         _TotalFileSize: c::LARGE_INTEGER,
@@ -65,6 +65,7 @@ pub fn decode_error_kind(errno: i32) -> ErrorKind {
         c::ERROR_PATH_NOT_FOUND => return ErrorKind::NotFound,
         c::ERROR_NO_DATA => return ErrorKind::BrokenPipe,
         c::ERROR_OPERATION_ABORTED => return ErrorKind::TimedOut,
+        c::ERROR_LOCK_VIOLATION => return ErrorKind::WouldBlock,
         _ => {}
     }
 
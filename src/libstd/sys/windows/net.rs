@@ -316,6 +316,29 @@ impl Socket {
         Ok(raw != 0)
     }
 
+    pub fn set_keepalive(&self, keepalive: Option<Duration>) -> io::Result<()> {
+        // `SO_KEEPALIVE` can't configure the idle time, so use the ioctl
+        // instead. The interval between probes is Windows' default.
+        let time = keepalive.map_or(0, |dur| {
+            let ms = dur.as_secs().saturating_mul(1000)
+                .saturating_add(dur.subsec_nanos() as u64 / 1_000_000);
+            cmp::min(cmp::max(ms, 1), c_ulong::max_value() as u64) as c_ulong
+        });
+        let vals = c::tcp_keepalive {
+            onoff: keepalive.is_some() as c_ulong,
+            keepalivetime: time,
+            keepaliveinterval: 1000,
+        };
+        let mut out = 0;
+        cvt(unsafe {
+            c::WSAIoctl(self.0, c::SIO_KEEPALIVE_VALS,
+                        &vals as *const _ as *mut _, mem::size_of_val(&vals) as c::DWORD,
+                        ptr::null_mut(), 0, &mut out,
+                        ptr::null_mut(), ptr::null_mut())
+        })?;
+        Ok(())
+    }
+
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         let raw: c_int = net::getsockopt(self, c::SOL_SOCKET, c::SO_ERROR)?;
         if raw == 0 {
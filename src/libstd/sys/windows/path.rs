@@ -8,9 +8,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use path::{Prefix, PrefixComponent};
-use ffi::OsStr;
+use os::windows::prelude::*;
+
+use ffi::{OsStr, OsString};
+use io;
 use mem;
+use path::{Path, PathBuf, Prefix, PrefixComponent};
+use ptr;
+use sys::{c, fill_utf16_buf, to_u16s};
 
 fn os_str_as_u8_slice(s: &OsStr) -> &[u8] {
     unsafe { mem::transmute(s) }
@@ -110,3 +115,14 @@ fn parse_prefix_simple<'a>(path: &'a OsStr) -> Option<Prefix> {
 
 pub const MAIN_SEP_STR: &'static str = "\\";
 pub const MAIN_SEP: char = '\\';
+
+/// Makes `path` absolute with `GetFullPathNameW`, which also resolves `.` and
+/// `..` components without touching the filesystem.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let path = to_u16s(path)?;
+    fill_utf16_buf(|buf, sz| unsafe {
+        c::GetFullPathNameW(path.as_ptr(), sz, buf, ptr::null_mut())
+    }, |buf| {
+        PathBuf::from(OsString::from_wide(buf))
+    })
+}
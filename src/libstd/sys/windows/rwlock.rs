@@ -13,6 +13,8 @@ use sys::c;
 
 pub struct RWLock { inner: UnsafeCell<c::SRWLOCK> }
 
+pub type MovableRWLock = Box<RWLock>;
+
 unsafe impl Send for RWLock {}
 unsafe impl Sync for RWLock {}
 
@@ -46,22 +46,29 @@ pub struct Frame {
 }
 
 /// Max number of frames to print.
-const MAX_NB_FRAMES: usize = 100;
+pub const MAX_NB_FRAMES: usize = 100;
 
-/// Prints the current backtrace.
-pub fn print(w: &mut dyn Write, format: PrintFormat) -> io::Result<()> {
+/// Runs `f` while holding the lock which serializes unwinding and symbol
+/// resolution across threads.
+///
+/// This prevents mixed output in multithreading context, and some platforms
+/// also require it, like `SymFromAddr` on Windows. `f` must not panic.
+pub fn with_lock<F, R>(f: F) -> R where F: FnOnce() -> R {
     static LOCK: Mutex = Mutex::new();
 
-    // Use a lock to prevent mixed output in multithreading context.
-    // Some platforms also requires it, like `SymFromAddr` on Windows.
     unsafe {
         LOCK.lock();
-        let res = _print(w, format);
+        let res = f();
         LOCK.unlock();
         res
     }
 }
 
+/// Prints the current backtrace.
+pub fn print(w: &mut dyn Write, format: PrintFormat) -> io::Result<()> {
+    with_lock(|| _print(w, format))
+}
+
 fn _print(w: &mut dyn Write, format: PrintFormat) -> io::Result<()> {
     let mut frames = [Frame {
         exact_position: ptr::null(),
@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use time::Duration;
-use sys_common::mutex::{self, Mutex};
+use sys_common::mutex::{self, MovableMutex};
 use sys::condvar as imp;
 
 /// An OS-based condition variable.
@@ -18,21 +18,26 @@ use sys::condvar as imp;
 /// condition variables. It is consequently entirely unsafe to use. It is
 /// recommended to use the safer types at the top level of this crate instead of
 /// this type.
-pub struct Condvar(imp::Condvar);
+///
+/// The condition variable is initialized on construction and may be moved
+/// freely: platforms whose native condition variable must stay at a fixed
+/// address box it, others store it inline.
+pub struct Condvar(imp::MovableCondvar);
 
 impl Condvar {
-    /// Creates a new condition variable for use.
-    ///
-    /// Behavior is undefined if the condition variable is moved after it is
-    /// first used with any of the functions below.
-    pub const fn new() -> Condvar { Condvar(imp::Condvar::new()) }
+    /// Creates a new condition variable, ready for use.
+    pub fn new() -> Condvar {
+        let mut c = imp::MovableCondvar::from(imp::Condvar::new());
+        unsafe { c.init() };
+        Condvar(c)
+    }
 
-    /// Prepares the condition variable for use.
+    /// Creates a new condition variable in a constant context.
     ///
-    /// This should be called once the condition variable is at a stable memory
-    /// address.
-    #[inline]
-    pub unsafe fn init(&mut self) { self.0.init() }
+    /// This is only available where the native condition variable can be
+    /// stored inline and needs no initialization.
+    #[cfg(target_os = "linux")]
+    pub const fn new_const() -> Condvar { Condvar(imp::Condvar::new()) }
 
     /// Signals one waiter on this condition variable to wake up.
     #[inline]
@@ -48,7 +53,7 @@ impl Condvar {
     /// Behavior is also undefined if more than one mutex is used concurrently
     /// on this condition variable.
     #[inline]
-    pub unsafe fn wait(&self, mutex: &Mutex) { self.0.wait(mutex::raw(mutex)) }
+    pub unsafe fn wait(&self, mutex: &MovableMutex) { self.0.wait(mutex::raw(mutex)) }
 
     /// Waits for a signal on the specified mutex with a timeout duration
     /// specified by `dur` (a relative time into the future).
@@ -57,7 +62,7 @@ impl Condvar {
     /// Behavior is also undefined if more than one mutex is used concurrently
     /// on this condition variable.
     #[inline]
-    pub unsafe fn wait_timeout(&self, mutex: &Mutex, dur: Duration) -> bool {
+    pub unsafe fn wait_timeout(&self, mutex: &MovableMutex, dur: Duration) -> bool {
         self.0.wait_timeout(mutex::raw(mutex), dur)
     }
 
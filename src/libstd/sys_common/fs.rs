@@ -0,0 +1,112 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Filesystem helpers shared by the platform implementations.
+
+#![allow(dead_code)] // not used on all platforms
+
+use fmt;
+use io;
+use path::{Path, PathBuf};
+use sys::fs::{canonicalize, readdir, stat, DirEntry, ReadDir};
+
+/// The error reported when a directory walk following symbolic links comes
+/// across a directory which is also one of its own ancestors.
+pub fn loop_error(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::Other,
+                   format!("filesystem loop detected at {}", path.display()))
+}
+
+/// A recursive directory walk built on path-based functions, for platforms
+/// which don't have a race-free implementation.
+pub struct WalkDir {
+    // The directories being read, from the root down, along with their
+    // canonical paths which are compared to detect loops.
+    stack: Vec<(ReadDir, PathBuf)>,
+    // A directory returned by the last call to `next`, to be read by the
+    // following one.
+    pending: Option<PathBuf>,
+    follow_links: bool,
+}
+
+impl WalkDir {
+    pub fn new(root: &Path) -> io::Result<WalkDir> {
+        let dir = readdir(root)?;
+        let canonical = canonicalize(root)?;
+        Ok(WalkDir {
+            stack: vec![(dir, canonical)],
+            pending: None,
+            follow_links: false,
+        })
+    }
+
+    pub fn set_follow_links(&mut self, follow_links: bool) {
+        self.follow_links = follow_links;
+    }
+
+    fn is_dir(&self, entry: &DirEntry) -> io::Result<bool> {
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() && self.follow_links {
+            // A dangling link just isn't a directory.
+            Ok(stat(&entry.path()).map(|m| m.file_type().is_dir()).unwrap_or(false))
+        } else {
+            Ok(file_type.is_dir())
+        }
+    }
+
+    fn push(&mut self, path: PathBuf) -> io::Result<()> {
+        let canonical = canonicalize(&path)?;
+        if self.stack.iter().any(|&(_, ref p)| *p == canonical) {
+            return Err(loop_error(&path));
+        }
+        let dir = readdir(&path)?;
+        self.stack.push((dir, canonical));
+        Ok(())
+    }
+}
+
+impl Iterator for WalkDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        if let Some(path) = self.pending.take() {
+            if let Err(e) = self.push(path) {
+                return Some(Err(e));
+            }
+        }
+
+        loop {
+            let next = match self.stack.last_mut() {
+                Some(&mut (ref mut dir, _)) => dir.next(),
+                None => return None,
+            };
+            match next {
+                Some(Ok(entry)) => {
+                    match self.is_dir(&entry) {
+                        Ok(true) => self.pending = Some(entry.path()),
+                        Ok(false) => {}
+                        Err(e) => return Some(Err(e)),
+                    }
+                    return Some(Ok(entry));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => { self.stack.pop(); }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WalkDir")
+            .field("follow_links", &self.follow_links)
+            .finish()
+    }
+}
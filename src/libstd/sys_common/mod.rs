@@ -42,6 +42,7 @@ pub mod at_exit_imp;
 #[cfg(feature = "backtrace")]
 pub mod backtrace;
 pub mod condvar;
+pub mod fs;
 pub mod io;
 pub mod mutex;
 pub mod poison;
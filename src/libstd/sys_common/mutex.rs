@@ -19,6 +19,9 @@ pub struct Mutex(imp::Mutex);
 
 unsafe impl Sync for Mutex {}
 
+// `std::sync` uses `MovableMutex`, so only the statics in this crate use this
+// type and some of its methods are unused on some platforms.
+#[allow(dead_code)]
 impl Mutex {
     /// Creates a new mutex for use.
     ///
@@ -73,8 +76,58 @@ impl Mutex {
     pub unsafe fn destroy(&self) { self.0.destroy() }
 }
 
+/// An OS-based mutual exclusion lock which may be freely moved.
+///
+/// Unlike `Mutex` this type is initialized on construction and can be moved
+/// at any time. Platforms whose native mutex must stay at a fixed address
+/// box it, others store it inline.
+pub struct MovableMutex(imp::MovableMutex);
+
+unsafe impl Sync for MovableMutex {}
+
+impl MovableMutex {
+    /// Creates a new mutex, ready for use.
+    pub fn new() -> MovableMutex {
+        let mut mutex = imp::MovableMutex::from(imp::Mutex::new());
+        unsafe { mutex.init() };
+        MovableMutex(mutex)
+    }
+
+    /// Creates a new mutex in a constant context.
+    ///
+    /// This is only available where the native mutex can be stored inline
+    /// and needs no initialization.
+    #[cfg(target_os = "linux")]
+    pub const fn new_const() -> MovableMutex {
+        MovableMutex(imp::Mutex::new())
+    }
+
+    /// Locks the mutex blocking the current thread until it is available.
+    #[inline]
+    pub unsafe fn raw_lock(&self) { self.0.lock() }
+
+    /// Attempts to lock the mutex without blocking, returning whether it was
+    /// successfully acquired or not.
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool { self.0.try_lock() }
+
+    /// Unlocks the mutex.
+    ///
+    /// Behavior is undefined if the current thread does not actually hold the
+    /// mutex.
+    #[inline]
+    pub unsafe fn raw_unlock(&self) { self.0.unlock() }
+
+    /// Deallocates all resources associated with this mutex.
+    ///
+    /// Behavior is undefined if there are current or will be future users of
+    /// this mutex.
+    #[inline]
+    pub unsafe fn destroy(&self) { self.0.destroy() }
+}
+
 // not meant to be exported to the outside world, just the containing module
-pub fn raw(mutex: &Mutex) -> &imp::Mutex { &mutex.0 }
+pub fn raw(mutex: &MovableMutex) -> &imp::Mutex { &mutex.0 }
 
 #[must_use]
 /// A simple RAII utility for the above Mutex without the poisoning semantics.
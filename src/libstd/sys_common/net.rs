@@ -53,6 +53,22 @@ use libc::MSG_NOSIGNAL;
               target_os = "haiku", target_os = "bitrig")))]
 const MSG_NOSIGNAL: c_int = 0x0;
 
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "dragonfly", target_os = "freebsd",
+          target_os = "openbsd", target_os = "netbsd",
+          target_os = "bitrig", target_os = "ios", target_os = "macos"))]
+fn set_reuseport(sock: &Socket, reuseport: bool) -> io::Result<()> {
+    setsockopt(sock, c::SOL_SOCKET, c::SO_REUSEPORT, reuseport as c_int)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android",
+              target_os = "dragonfly", target_os = "freebsd",
+              target_os = "openbsd", target_os = "netbsd",
+              target_os = "bitrig", target_os = "ios", target_os = "macos")))]
+fn set_reuseport(_sock: &Socket, _reuseport: bool) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Other, "SO_REUSEPORT is not supported on this platform"))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // sockaddr and misc bindings
 ////////////////////////////////////////////////////////////////////////////////
@@ -318,25 +334,17 @@ pub struct TcpListener {
 
 impl TcpListener {
     pub fn bind(addr: &SocketAddr) -> io::Result<TcpListener> {
-        init();
-
-        let sock = Socket::new(addr, c::SOCK_STREAM)?;
+        let sock = TcpSocket::new(addr)?;
 
         // On platforms with Berkeley-derived sockets, this allows
         // to quickly rebind a socket, without needing to wait for
         // the OS to clean up the previous one.
         if !cfg!(windows) {
-            setsockopt(&sock, c::SOL_SOCKET, c::SO_REUSEADDR,
-                       1 as c_int)?;
+            sock.set_reuseaddr(true)?;
         }
 
-        // Bind our new socket
-        let (addrp, len) = addr.into_inner();
-        cvt(unsafe { c::bind(*sock.as_inner(), addrp, len as _) })?;
-
-        // Start listening
-        cvt(unsafe { c::listen(*sock.as_inner(), 128) })?;
-        Ok(TcpListener { inner: sock })
+        sock.bind(addr)?;
+        sock.listen(128)
     }
 
     pub fn socket(&self) -> &Socket { &self.inner }
@@ -409,6 +417,115 @@ impl fmt::Debug for TcpListener {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Unconnected TCP sockets
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct TcpSocket {
+    inner: Socket,
+}
+
+impl TcpSocket {
+    /// Creates a socket of the same address family as `addr`.
+    pub fn new(addr: &SocketAddr) -> io::Result<TcpSocket> {
+        init();
+
+        let sock = Socket::new(addr, c::SOCK_STREAM)?;
+        Ok(TcpSocket { inner: sock })
+    }
+
+    pub fn socket(&self) -> &Socket { &self.inner }
+
+    pub fn into_socket(self) -> Socket { self.inner }
+
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_REUSEADDR, reuseaddr as c_int)
+    }
+
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_REUSEADDR)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        set_reuseport(&self.inner, reuseport)
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_SNDBUF, size as c_int)
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_SNDBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_RCVBUF, size as c_int)
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_RCVBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_keepalive(&self, keepalive: Option<Duration>) -> io::Result<()> {
+        self.inner.set_keepalive(keepalive)
+    }
+
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.inner.set_nodelay(nodelay)
+    }
+
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)
+    }
+
+    pub fn bind(&self, addr: &SocketAddr) -> io::Result<()> {
+        let (addrp, len) = addr.into_inner();
+        cvt(unsafe { c::bind(*self.inner.as_inner(), addrp, len as _) })?;
+        Ok(())
+    }
+
+    pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+        sockname(|buf, len| unsafe {
+            c::getsockname(*self.inner.as_inner(), buf, len)
+        })
+    }
+
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        let backlog = cmp::min(backlog, c_int::max_value() as u32) as c_int;
+        cvt(unsafe { c::listen(*self.inner.as_inner(), backlog) })?;
+        Ok(TcpListener { inner: self.inner })
+    }
+
+    pub fn connect(self, addr: &SocketAddr) -> io::Result<TcpStream> {
+        let (addrp, len) = addr.into_inner();
+        cvt_r(|| unsafe { c::connect(*self.inner.as_inner(), addrp, len) })?;
+        Ok(TcpStream { inner: self.inner })
+    }
+
+    pub fn connect_timeout(self, addr: &SocketAddr, timeout: Duration)
+                           -> io::Result<TcpStream> {
+        self.inner.connect_timeout(addr, timeout)?;
+        Ok(TcpStream { inner: self.inner })
+    }
+}
+
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut res = f.debug_struct("TcpSocket");
+
+        if let Ok(addr) = self.socket_addr() {
+            res.field("addr", &addr);
+        }
+
+        let name = if cfg!(windows) {"socket"} else {"fd"};
+        res.field(name, &self.inner.as_inner())
+            .finish()
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // UDP
 ////////////////////////////////////////////////////////////////////////////////
@@ -610,6 +727,82 @@ impl fmt::Debug for UdpSocket {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// Unbound UDP sockets
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct UdpSocketBuilder {
+    inner: Socket,
+}
+
+impl UdpSocketBuilder {
+    /// Creates a socket of the same address family as `addr`.
+    pub fn new(addr: &SocketAddr) -> io::Result<UdpSocketBuilder> {
+        init();
+
+        let sock = Socket::new(addr, c::SOCK_DGRAM)?;
+        Ok(UdpSocketBuilder { inner: sock })
+    }
+
+    pub fn socket(&self) -> &Socket { &self.inner }
+
+    pub fn into_socket(self) -> Socket { self.inner }
+
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_REUSEADDR, reuseaddr as c_int)
+    }
+
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_REUSEADDR)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        set_reuseport(&self.inner, reuseport)
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_SNDBUF, size as c_int)
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_SNDBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_RCVBUF, size as c_int)
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.inner, c::SOL_SOCKET, c::SO_RCVBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_broadcast(&self, broadcast: bool) -> io::Result<()> {
+        setsockopt(&self.inner, c::SOL_SOCKET, c::SO_BROADCAST, broadcast as c_int)
+    }
+
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        setsockopt(&self.inner, c::IPPROTO_IPV6, c::IPV6_V6ONLY, only_v6 as c_int)
+    }
+
+    pub fn bind(self, addr: &SocketAddr) -> io::Result<UdpSocket> {
+        let (addrp, len) = addr.into_inner();
+        cvt(unsafe { c::bind(*self.inner.as_inner(), addrp, len as _) })?;
+        Ok(UdpSocket { inner: self.inner })
+    }
+}
+
+impl fmt::Debug for UdpSocketBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = if cfg!(windows) {"socket"} else {"fd"};
+        f.debug_struct("UdpSocketBuilder")
+            .field(name, &self.inner.as_inner())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,6 +17,9 @@ use sys::rwlock as imp;
 /// safer types at the top level of this crate instead of this type.
 pub struct RWLock(imp::RWLock);
 
+// `std::sync` uses `MovableRWLock`, so only the statics in this crate use this
+// type and some of its methods are unused.
+#[allow(dead_code)]
 impl RWLock {
     /// Creates a new reader-writer lock for use.
     ///
@@ -80,3 +83,68 @@ impl RWLock {
     #[inline]
     pub unsafe fn destroy(&self) { self.0.destroy() }
 }
+
+/// An OS-based reader-writer lock which may be freely moved.
+///
+/// Unlike `RWLock` this type can be moved at any time. Platforms whose native
+/// lock must stay at a fixed address box it, others store it inline.
+pub struct MovableRWLock(imp::MovableRWLock);
+
+impl MovableRWLock {
+    /// Creates a new reader-writer lock for use.
+    pub fn new() -> MovableRWLock {
+        MovableRWLock(imp::MovableRWLock::from(imp::RWLock::new()))
+    }
+
+    /// Creates a new reader-writer lock in a constant context.
+    ///
+    /// This is only available where the native lock can be stored inline.
+    #[cfg(target_os = "linux")]
+    pub const fn new_const() -> MovableRWLock {
+        MovableRWLock(imp::RWLock::new())
+    }
+
+    /// Acquires shared access to the underlying lock, blocking the current
+    /// thread to do so.
+    #[inline]
+    pub unsafe fn read(&self) { self.0.read() }
+
+    /// Attempts to acquire shared access to this lock, returning whether it
+    /// succeeded or not.
+    ///
+    /// This function does not block the current thread.
+    #[inline]
+    pub unsafe fn try_read(&self) -> bool { self.0.try_read() }
+
+    /// Acquires write access to the underlying lock, blocking the current thread
+    /// to do so.
+    #[inline]
+    pub unsafe fn write(&self) { self.0.write() }
+
+    /// Attempts to acquire exclusive access to this lock, returning whether it
+    /// succeeded or not.
+    ///
+    /// This function does not block the current thread.
+    #[inline]
+    pub unsafe fn try_write(&self) -> bool { self.0.try_write() }
+
+    /// Unlocks previously acquired shared access to this lock.
+    ///
+    /// Behavior is undefined if the current thread does not have shared access.
+    #[inline]
+    pub unsafe fn read_unlock(&self) { self.0.read_unlock() }
+
+    /// Unlocks previously acquired exclusive access to this lock.
+    ///
+    /// Behavior is undefined if the current thread does not currently have
+    /// exclusive access.
+    #[inline]
+    pub unsafe fn write_unlock(&self) { self.0.write_unlock() }
+
+    /// Destroys OS-related resources with this RWLock.
+    ///
+    /// Behavior is undefined if there are any currently active users of this
+    /// lock.
+    #[inline]
+    pub unsafe fn destroy(&self) { self.0.destroy() }
+}
@@ -211,6 +211,12 @@ declare_features! (
     // Allows let bindings and destructuring in `const fn` functions and constants.
     (active, const_let, "1.22.1", Some(48821), None),
 
+    // Allows branches and loops (`if`, `match`, `loop`, `while`) in `const fn` and constants.
+    (active, const_control_flow, "1.29.0", None, None),
+
+    // Allows setting the maximum number of steps of a constant evaluation.
+    (active, const_eval_limit, "1.29.0", None, None),
+
     // Allows using #[prelude_import] on glob `use` items.
     //
     // rustc internal
@@ -756,6 +762,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                   "no_core",
                                   "no_core is experimental",
                                   cfg_fn!(no_core))),
+    ("const_eval_limit", CrateLevel, Gated(Stability::Unstable,
+                                           "const_eval_limit",
+                                           "the `#[const_eval_limit]` attribute \
+                                            is an experimental feature",
+                                           cfg_fn!(const_eval_limit))),
     ("lang", Normal, Gated(Stability::Unstable,
                            "lang_items",
                            "language items are subject to change",
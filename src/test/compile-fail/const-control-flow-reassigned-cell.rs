@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a constant whose value comes from a local reassigned in a loop isn't promoted
+// when that local can hold a `Cell`, even if it didn't the first time it was read.
+
+#![feature(const_fn, const_let, const_control_flow)]
+
+use std::cell::Cell;
+
+// `y` is read before `x` is assigned the `Cell`, but in the second iteration it gets
+// the `Cell` assigned in the first.
+const C: Option<Cell<u32>> = {
+    let mut x = None;
+    let mut y = None;
+    let mut i = 0;
+    while i < 2 {
+        y = x;
+        x = Some(Cell::new(i));
+        i += 1;
+    }
+    y
+};
+
+fn main() {
+    let c: &'static Option<Cell<u32>> = &C; //~ ERROR does not live long enough
+    if let Some(ref cell) = *c {
+        cell.set(1);
+    }
+}
@@ -12,14 +12,14 @@
 
 const x: bool = match Some(true) {
     Some(value) => true,
-    //~^ ERROR: constant contains unimplemented expression type [E0019]
+    //~^ ERROR: branches and loops in constants are unstable [E0658]
     _ => false
 };
 
 const y: bool = {
     match Some(true) {
         Some(value) => true,
-        //~^ ERROR: constant contains unimplemented expression type [E0019]
+        //~^ ERROR: branches and loops in constants are unstable [E0658]
         _ => false
     }
 };
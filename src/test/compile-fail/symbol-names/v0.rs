@@ -0,0 +1,121 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags: -Z symbol-mangling-version=v0
+
+#![feature(rustc_attrs, non_ascii_idents)]
+#![allow(dead_code)]
+
+pub struct Unit;
+pub struct Wrapper<T>(T);
+
+pub trait Trait<T> {
+    fn method(&self);
+}
+
+mod foo {
+    pub struct Foo { x: u32 }
+
+    impl Foo {
+        #[rustc_symbol_name]
+        //~^ ERROR symbol-name(_RNvMNtCs
+        //~| ERROR demangling(<v0[
+        //~| ERROR demangling-alt(<v0::foo::Foo>::bar)
+        fn bar() { }
+    }
+}
+
+mod bar {
+    use foo::Foo;
+
+    impl Foo {
+        #[rustc_symbol_name]
+        //~^ ERROR symbol-name(_RNvMNtCs
+        //~| ERROR demangling(<v0[
+        //~| ERROR demangling-alt(<v0::foo::Foo>::baz)
+        fn baz() { }
+    }
+}
+
+impl Wrapper<Unit> {
+    #[rustc_symbol_name]
+    //~^ ERROR symbol-name(_RNvMCs
+    //~| ERROR demangling(<v0[
+    //~| ERROR demangling-alt(<v0::Wrapper<v0::Unit>>::get)
+    fn get() { }
+}
+
+impl Trait<u8> for [u32; 3] {
+    #[rustc_symbol_name]
+    //~^ ERROR symbol-name(_RNvXs_Cs
+    //~| ERROR demangling(<[u32; 3] as v0[
+    //~| ERROR demangling-alt(<[u32; 3] as v0::Trait<u8>>::method)
+    fn method(&self) { }
+}
+
+impl Trait<&'static str> for (u8, &'static mut [bool]) {
+    #[rustc_symbol_name]
+    //~^ ERROR symbol-name(_RNvXs0_Cs
+    //~| ERROR demangling(<(u8, &mut [bool]) as v0[
+    //~| ERROR demangling-alt(<(u8, &mut [bool]) as v0::Trait<&str>>::method)
+    fn method(&self) { }
+}
+
+impl Trait<unsafe extern "C" fn(*const u8, ...) -> i32> for dyn Iterator<Item = u8> {
+    #[rustc_symbol_name]
+    //~^ ERROR symbol-name(_RNvXs1_Cs
+    //~| ERROR demangling(<dyn core[
+    //~| ERROR demangling-alt(<dyn core::iter::iterator::Iterator<Item = u8> as v0::Trait<unsafe extern "C" fn(*const u8, ...) -> i32>>::method)
+    fn method(&self) { }
+}
+
+#[rustc_symbol_name]
+//~^ ERROR symbol-name(_RNvCs
+//~| ERROR demangling(v0[
+//~| ERROR demangling-alt(v0::über)
+fn über() { }
+
+impl<T> Wrapper<T> {
+    fn new() {
+        // Items nested in a generic impl are mangled without its substs.
+        #[rustc_symbol_name]
+        //~^ ERROR symbol-name(_RNvNvMs2_Cs
+        //~| ERROR demangling(<v0[
+        //~| ERROR demangling-alt(<v0::Wrapper<_>>::new::inner)
+        fn inner() { }
+
+        #[rustc_symbol_name]
+        //~^ ERROR symbol-name(_RNvNvMs2_Cs
+        //~| ERROR demangling(<v0[
+        //~| ERROR demangling-alt(<v0::Wrapper<_>>::new::STATIC)
+        static STATIC: u8 = 0;
+    }
+}
+
+impl<T: Iterator> Trait<T> for Wrapper<T::Item> {
+    fn method(&self) {
+        // The projection in the self type is printed as a placeholder, like a parameter.
+        #[rustc_symbol_name]
+        //~^ ERROR symbol-name(_RNvNvXs3_Cs
+        //~| ERROR demangling(<v0[
+        //~| ERROR demangling-alt(<v0::Wrapper<_> as v0::Trait<_>>::method::inner)
+        fn inner() { }
+    }
+}
+
+#[rustc_symbol_name]
+//~^ ERROR symbol-name(_RNvCs
+//~| ERROR demangling(v0[
+//~| ERROR demangling-alt(v0::日本語)
+fn 日本語() { }
+
+fn main() {
+}
@@ -0,0 +1,102 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[inline(never)]
+fn fill() -> [u8; 1024] {
+    [0; 1024]
+}
+
+#[inline(never)]
+fn touch(_: &mut [u8; 1024]) {}
+
+// The local is built directly in the return place.
+fn nrvo() -> [u8; 1024] {
+    let buf = fill();
+    buf
+}
+
+// The local is borrowed, so it can't share storage with the return place.
+fn borrowed() -> [u8; 1024] {
+    let mut buf = fill();
+    touch(&mut buf);
+    buf
+}
+
+#[inline(never)]
+fn step(s: [u64; 16]) -> [u64; 16] {
+    s
+}
+
+// The result of the call can't be written into `s` while `s` is the argument, so the call
+// keeps writing to a temporary that is copied out afterwards.
+fn reassign() -> [u64; 16] {
+    let mut s = [0; 16];
+    s = step(s);
+    s
+}
+
+fn main() {
+    nrvo();
+    borrowed();
+    reassign();
+}
+
+// END RUST SOURCE
+// START rustc.nrvo.DestinationPropagation.before.mir
+//  bb0: {
+//      StorageLive(_1);
+//      _1 = const fill() -> bb1;
+//  }
+//  bb1: {
+//      _0 = _1;
+//      StorageDead(_1);
+//      return;
+//  }
+// END rustc.nrvo.DestinationPropagation.before.mir
+// START rustc.nrvo.DestinationPropagation.after.mir
+//  bb0: {
+//      nop;
+//      _0 = const fill() -> bb1;
+//  }
+//  bb1: {
+//      nop;
+//      nop;
+//      return;
+//  }
+// END rustc.nrvo.DestinationPropagation.after.mir
+// START rustc.borrowed.DestinationPropagation.after.mir
+//  bb0: {
+//      StorageLive(_1);
+//      _1 = const fill() -> bb1;
+//  }
+//  ...
+//      _0 = _1;
+//      StorageDead(_1);
+//      return;
+//  }
+// END rustc.borrowed.DestinationPropagation.after.mir
+// START rustc.reassign.DestinationPropagation.after.mir
+//  bb0: {
+//      nop;
+//      _0 = [const 0u64; const 16usize];
+//      StorageLive(_2);
+//      nop;
+//      nop;
+//      _2 = const step(move _0) -> bb1;
+//  }
+//  bb1: {
+//      nop;
+//      _0 = move _2;
+//      StorageDead(_2);
+//      nop;
+//      nop;
+//      return;
+//  }
+// END rustc.reassign.DestinationPropagation.after.mir
@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// needs-profiler-support
+// compile-flags: -Z instrument-coverage
+
+// Checks that the counter of a block is called before the code of the block,
+// with the number of counters and the index of the counter. The source region
+// of the block is the span of the index, which is checked by the
+// `instrument-coverage` run-make tests.
+
+fn main() {
+    bar();
+}
+
+fn bar() -> bool {
+    true
+}
+
+// END RUST SOURCE
+// START rustc.bar.InstrumentCoverage.before.mir
+// bb0: {
+//     _0 = const true;
+//     return;
+// }
+// END rustc.bar.InstrumentCoverage.before.mir
+// START rustc.bar.InstrumentCoverage.after.mir
+// bb0: {
+//     _1 = const std::intrinsics::count_code_region(const 1u32, const 0u32) -> bb1;
+// }
+// bb1: {
+//     _0 = const true;
+//     return;
+// }
+// END rustc.bar.InstrumentCoverage.after.mir
@@ -0,0 +1,92 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a switch on the discriminant of an enum built in the same block is folded, and that
+// a switch on an enum built in the blocks jumping to it is threaded by duplicating the block.
+
+fn known(x: i32) -> i32 {
+    match Some(x) {
+        Some(y) => y,
+        None => 0,
+    }
+}
+
+// This is the shape `?` leaves behind once `into_result` is inlined.
+fn forwarded(c: bool) -> u32 {
+    let r = if c { Some(1) } else { None };
+    match r {
+        Some(_) => 1,
+        None => 2,
+    }
+}
+
+fn main() {
+    known(1);
+    forwarded(true);
+}
+
+// END RUST SOURCE
+// START rustc.known.JumpThreading.before.mir
+//  bb0: {
+//      ...
+//      _2 = std::option::Option<i32>::Some(move _3,);
+//      ...
+//      switchInt(move _5) -> [0isize: bb1, 1isize: bb2, otherwise: bb3];
+//  }
+// END rustc.known.JumpThreading.before.mir
+// START rustc.known.JumpThreading.after.mir
+//  bb0: {
+//      ...
+//      _2 = std::option::Option<i32>::Some(move _3,);
+//      ...
+//      goto -> bb2;
+//  }
+// END rustc.known.JumpThreading.after.mir
+// START rustc.forwarded.JumpThreading.before.mir
+//  bb1: {
+//      _2 = std::option::Option<u32>::Some(const 1u32,);
+//      goto -> bb3;
+//  }
+//  bb2: {
+//      _2 = std::option::Option<u32>::None;
+//      goto -> bb3;
+//  }
+//  bb3: {
+//      ...
+//      _4 = discriminant(_2);
+//      switchInt(move _4) -> [0isize: bb4, 1isize: bb5, otherwise: bb6];
+//  }
+// END rustc.forwarded.JumpThreading.before.mir
+// START rustc.forwarded.JumpThreading.after.mir
+//  bb1: {
+//      _2 = std::option::Option<u32>::Some(const 1u32,);
+//      goto -> bb8;
+//  }
+//  bb2: {
+//      _2 = std::option::Option<u32>::None;
+//      goto -> bb9;
+//  }
+//  bb3: {
+//      ...
+//      _4 = discriminant(_2);
+//      switchInt(move _4) -> [0isize: bb4, 1isize: bb5, otherwise: bb6];
+//  }
+//  ...
+//  bb8: {
+//      ...
+//      _4 = discriminant(_2);
+//      goto -> bb5;
+//  }
+//  bb9: {
+//      ...
+//      _4 = discriminant(_2);
+//      goto -> bb4;
+//  }
+// END rustc.forwarded.JumpThreading.after.mir
@@ -0,0 +1,62 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn first(a: u32, b: u32) -> u32 {
+    let t = (a, b);
+    t.0
+}
+
+fn borrowed(a: u32, b: u32) -> u32 {
+    let t = (a, b);
+    let r = &t;
+    r.1
+}
+
+fn main() {
+    first(1, 2);
+    borrowed(1, 2);
+}
+
+// END RUST SOURCE
+// START rustc.first.ScalarReplacementOfAggregates.before.mir
+//  bb0: {
+//      StorageLive(_3);
+//      ...
+//      (_3.0: u32) = move _4;
+//      (_3.1: u32) = move _5;
+//      ...
+//      _0 = (_3.0: u32);
+//      StorageDead(_3);
+//      return;
+//  }
+// END rustc.first.ScalarReplacementOfAggregates.before.mir
+// START rustc.first.ScalarReplacementOfAggregates.after.mir
+//  bb0: {
+//      StorageLive(_6);
+//      StorageLive(_7);
+//      ...
+//      _6 = move _4;
+//      _7 = move _5;
+//      ...
+//      _0 = _6;
+//      StorageDead(_6);
+//      StorageDead(_7);
+//      return;
+//  }
+// END rustc.first.ScalarReplacementOfAggregates.after.mir
+// START rustc.borrowed.ScalarReplacementOfAggregates.after.mir
+//  bb0: {
+//      StorageLive(_3);
+//      ...
+//      (_3.0: u32) = move _4;
+//      (_3.1: u32) = move _5;
+//      ...
+//  }
+// END rustc.borrowed.ScalarReplacementOfAggregates.after.mir
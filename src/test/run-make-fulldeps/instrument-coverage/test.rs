@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn)]
+
+fn collatz(mut n: u64) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        n = if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+        steps += 1;
+    }
+    steps
+}
+
+// Instrumented, but only counted when called at run time.
+const fn double(n: u32) -> u32 {
+    n * 2
+}
+
+const DOUBLED: u32 = double(21);
+
+fn main() {
+    assert_eq!(collatz(6), 8);
+    // Not `assert_eq!(double(21), ..)`, which would promote the call.
+    let doubled = double(21);
+    assert_eq!(doubled, DOUBLED);
+}
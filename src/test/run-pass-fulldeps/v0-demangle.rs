@@ -0,0 +1,123 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the demangler for the `v0` symbol mangling scheme, on symbols that can't be produced
+// with `#[rustc_symbol_name]`, like those of generic instances and closures.
+
+#![feature(rustc_private)]
+
+extern crate rustc_codegen_utils;
+
+use rustc_codegen_utils::symbol_names::demangle::demangle;
+
+macro_rules! t {
+    ($a:expr, $b:expr) => ({
+        assert_eq!(format!("{}", demangle($a).unwrap()), $b);
+    })
+}
+
+macro_rules! t_alt {
+    ($a:expr, $b:expr) => ({
+        assert_eq!(format!("{:#}", demangle($a).unwrap()), $b);
+    })
+}
+
+macro_rules! t_err {
+    ($a:expr) => ({
+        assert!(demangle($a).is_err());
+    })
+}
+
+fn demangle_crate_and_items() {
+    t!("_RNvC4test4main", "test::main");
+    t!("_RNvCs1_4test4main", "test[3]::main");
+    t_alt!("_RNvCs1_4test4main", "test::main");
+    t!("_RNvNtC4test3foos_3bar", "test::foo::bar");
+    t!("_RNvNtC4test3foo8__private", "test::foo::_private");
+}
+
+fn demangle_closures() {
+    t!("_RNCNvC4test4main0", "test::main::{closure#0}");
+    t!("_RNCNCNvC4test4mains_0s0_0", "test::main::{closure#1}::{closure#2}");
+}
+
+fn demangle_impls() {
+    t!("_RNvMNtC4test3fooNtB2_3Foo3bar", "<test::foo::Foo>::bar");
+    t!("_RNvXC4testAmj3_INtB2_5TraithE6method", "<[u32; 3] as test::Trait<u8>>::method");
+    t!("_RNvYINtC4test7WrapperuENtB5_5Trait6method",
+       "<test::Wrapper<()> as test::Trait>::method");
+}
+
+fn demangle_generic_args() {
+    t!("_RINvC4test7genericThRSbEE", "test::generic::<(u8, &[bool])>");
+    t!("_RINvC4test7genericTcEE", "test::generic::<(char,)>");
+    t!("_RINvC4test7genericQPaEC5other", "test::generic::<&mut *const i8>");
+}
+
+fn demangle_fn_ptrs_and_trait_objects() {
+    t!("_RINvC4test7genericFhEcE", "test::generic::<fn(u8) -> char>");
+    t!("_RINvC4test7genericFUKCPhvElE",
+       "test::generic::<unsafe extern \"C\" fn(*const u8, ...) -> i32>");
+    t!("_RINvC4test7genericFK8platformEuE", "test::generic::<extern \"platform\" fn()>");
+    t!("_RINvC4test7genericFK13platform_intrEuE",
+       "test::generic::<extern \"platform-intr\" fn()>");
+    t!("_RINvC4test7genericDNtB2_5Traitp4ItemhNtB2_4SendEE",
+       "test::generic::<dyn test::Trait<Item = u8> + test::Send>");
+    t!("_RINvC4test7genericDINtB2_5TraitcEp4ItemhEE",
+       "test::generic::<dyn test::Trait<char, Item = u8>>");
+}
+
+fn demangle_punycode() {
+    t!("_RNvC4testu7ber_goa", "test::über");
+    t!("_RNvC4testu9__ber__ova", "test::_ber_ü");
+    t!("_RNvC4testu10wgv71a119e", "test::日本語");
+    t!("_RNvC4testu3n3h", "test::☃");
+}
+
+// Symbols as the mangler prints them, with the instantiating crate of generic instances at the
+// end, and backrefs to paths and types that were printed before.
+fn demangle_mangler_output() {
+    // `test::generic::<u8>`, instantiated in `other`.
+    t!("_RINvCs0_4test7generichECs_5other", "test[2]::generic::<u8>");
+    t_alt!("_RINvCs0_4test7generichECs_5other", "test::generic::<u8>");
+    // `test::generic::<(test::Foo, test::Foo)>`: the crate root and the second `Foo` are
+    // backrefs.
+    t!("_RINvC4test7genericTNtB2_3FooBh_EE", "test::generic::<(test::Foo, test::Foo)>");
+    // `test::generic::<test::Wrapper<u8>, test::Wrapper<u8>>`.
+    t!("_RINvC4test7genericINtB2_7WrapperhEBg_E",
+       "test::generic::<test::Wrapper<u8>, test::Wrapper<u8>>");
+    // The first closure in `test::generic::<u8>`, instantiated in `other`.
+    t!("_RNCINvC4test7generichE0C5other", "test::generic::<u8>::{closure#0}");
+    // `<test::Wrapper<u8>>::get`, instantiated in `test` itself, which makes the instantiating
+    // crate a backref.
+    t!("_RNvMC4testINtB2_7WrapperhE3getB2_", "<test::Wrapper<u8>>::get");
+    // An item nested in a generic impl, with the impl's parameter as a placeholder.
+    t!("_RNvNvMC4testINtB4_7WrapperpE3new5inner", "<test::Wrapper<_>>::new::inner");
+}
+
+fn demangle_invalid() {
+    t_err!("_ZN4test4mainE");
+    t_err!("_RNvC4test");
+    t_err!("_RNvC4test4main4main");
+    t_err!("_RNvC4test4mainX");
+    t_err!("_RNvNtB_3foo3bar");
+    t_err!("_RINvC4test7genericAhj_EE");
+}
+
+fn main() {
+    demangle_crate_and_items();
+    demangle_closures();
+    demangle_impls();
+    demangle_generic_args();
+    demangle_fn_ptrs_and_trait_objects();
+    demangle_punycode();
+    demangle_mangler_output();
+    demangle_invalid();
+}
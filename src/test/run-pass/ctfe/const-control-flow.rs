@@ -0,0 +1,61 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_fn, const_let, const_control_flow)]
+
+const fn abs_diff(a: u32, b: u32) -> u32 {
+    if a > b { a - b } else { b - a }
+}
+
+const fn or_default(x: Option<u32>, default: u32) -> u32 {
+    match x {
+        Some(x) => x,
+        None => default,
+    }
+}
+
+const fn popcount(mut x: u32) -> u32 {
+    let mut count = 0;
+    while x != 0 {
+        count += x & 1;
+        x >>= 1;
+    }
+    count
+}
+
+const fn log2(mut x: u32) -> u32 {
+    let mut log = 0;
+    loop {
+        x >>= 1;
+        if x == 0 {
+            break;
+        }
+        log += 1;
+    }
+    log
+}
+
+const DIFF: u32 = abs_diff(3, 10);
+const SOME: u32 = or_default(Some(5), 1);
+const NONE: u32 = or_default(None, 1);
+const LOG: u32 = log2(1000);
+static POPCOUNTS: [u32; 4] = [popcount(0), popcount(1), popcount(2), popcount(0b1011_0111)];
+
+fn main() {
+    assert_eq!(DIFF, 7);
+    assert_eq!(SOME, 5);
+    assert_eq!(NONE, 1);
+    assert_eq!(LOG, 9);
+    assert_eq!(POPCOUNTS, [0, 1, 1, 6]);
+
+    assert_eq!(popcount(255), 8);
+    assert_eq!(log2(1), 0);
+}
@@ -0,0 +1,109 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that enums with several data-carrying variants, laid out around a niche in
+// the largest one, keep every variant and field apart, also when nested in `Option`.
+
+use std::mem::size_of;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CharOrInt {
+    Char(char, u32),
+    Int(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pair {
+    Both(char, char),
+    One(char),
+    Neither,
+}
+
+#[derive(Debug, PartialEq)]
+enum Refs<'a, T: 'a> {
+    A(u32, &'a T),
+    B(&'a T),
+    C,
+}
+
+#[inline(never)]
+fn check_char_or_int(x: CharOrInt, expected: (Option<char>, u32)) {
+    match x {
+        CharOrInt::Char(c, n) => assert_eq!((Some(c), n), expected),
+        CharOrInt::Int(n) => assert_eq!((None, n), expected),
+    }
+}
+
+#[inline(never)]
+fn check_pair(x: Pair, expected: (Option<char>, Option<char>)) {
+    match x {
+        Pair::Both(a, b) => assert_eq!((Some(a), Some(b)), expected),
+        Pair::One(a) => assert_eq!((Some(a), None), expected),
+        Pair::Neither => assert_eq!((None, None), expected),
+    }
+}
+
+#[inline(never)]
+fn check_refs(x: &Refs<u64>, expected: (Option<u32>, Option<u64>)) {
+    match *x {
+        Refs::A(n, &r) => assert_eq!((Some(n), Some(r)), expected),
+        Refs::B(&r) => assert_eq!((None, Some(r)), expected),
+        Refs::C => assert_eq!((None, None), expected),
+    }
+}
+
+fn main() {
+    assert_eq!(size_of::<CharOrInt>(), 8);
+    assert_eq!(size_of::<Pair>(), 8);
+
+    check_char_or_int(CharOrInt::Char('x', 7), (Some('x'), 7));
+    check_char_or_int(CharOrInt::Char('\u{10ffff}', !0), (Some('\u{10ffff}'), !0));
+    check_char_or_int(CharOrInt::Int(0), (None, 0));
+    check_char_or_int(CharOrInt::Int(!0), (None, !0));
+
+    check_pair(Pair::Both('a', 'b'), (Some('a'), Some('b')));
+    check_pair(Pair::One('c'), (Some('c'), None));
+    check_pair(Pair::Neither, (None, None));
+
+    let (x, y) = (1u64, !0u64);
+    check_refs(&Refs::A(3, &x), (Some(3), Some(1)));
+    check_refs(&Refs::A(!0, &y), (Some(!0), Some(!0)));
+    check_refs(&Refs::B(&y), (None, Some(!0)));
+    check_refs(&Refs::C, (None, None));
+
+    // `Option` has to find room for `None` next to the niche values already in use.
+    let pairs = [
+        Some(Pair::Both('a', 'b')),
+        Some(Pair::One('c')),
+        Some(Pair::Neither),
+        None,
+    ];
+    for (i, pair) in pairs.iter().enumerate() {
+        match (i, *pair) {
+            (0, Some(Pair::Both('a', 'b'))) |
+            (1, Some(Pair::One('c'))) |
+            (2, Some(Pair::Neither)) |
+            (3, None) => {}
+            _ => panic!("{} was read back as {:?}", i, pair),
+        }
+    }
+    let ints = [Some(CharOrInt::Char('z', 9)), Some(CharOrInt::Int(5)), None];
+    assert_eq!(ints[0], Some(CharOrInt::Char('z', 9)));
+    assert_eq!(ints[1], Some(CharOrInt::Int(5)));
+    assert_eq!(ints[2], None);
+    let refs = [Some(Refs::A(2, &x)), Some(Refs::B(&x)), Some(Refs::C), None];
+    assert_eq!(refs[0], Some(Refs::A(2, &1)));
+    assert_eq!(refs[1], Some(Refs::B(&1)));
+    assert_eq!(refs[2], Some(Refs::C));
+    assert_eq!(refs[3], None);
+    let nested: Option<Option<Pair>> = Some(None);
+    assert_eq!(nested, Some(None));
+    assert!(nested.unwrap().is_none());
+}
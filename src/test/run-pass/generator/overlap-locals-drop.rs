@@ -0,0 +1,102 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that generators whose saved locals share space drop exactly the locals that are live,
+// whether they are dropped while suspended or after completing.
+
+#![feature(generators, generator_trait)]
+
+use std::cell::RefCell;
+use std::ops::{Generator, GeneratorState};
+
+thread_local!(static DROPS: RefCell<Vec<usize>> = RefCell::new(Vec::new()));
+
+struct D {
+    id: usize,
+    check: usize,
+}
+
+impl D {
+    fn new(id: usize) -> D {
+        D { id: id, check: !id }
+    }
+
+    fn check(&self) {
+        assert_eq!(self.check, !self.id, "local {} was overwritten", self.id);
+    }
+}
+
+impl Drop for D {
+    fn drop(&mut self) {
+        self.check();
+        DROPS.with(|drops| drops.borrow_mut().push(self.id));
+    }
+}
+
+fn take_drops() -> Vec<usize> {
+    let mut drops = DROPS.with(|drops| drops.borrow_mut().split_off(0));
+    drops.sort();
+    drops
+}
+
+fn disjoint() -> impl Generator<Yield = (), Return = ()> {
+    || {
+        {
+            let a = D::new(1);
+            yield;
+            a.check();
+        }
+        {
+            let b = D::new(2);
+            yield;
+            b.check();
+        }
+    }
+}
+
+fn overlapping() -> impl Generator<Yield = (), Return = ()> {
+    || {
+        let a = D::new(1);
+        yield;
+        let b = D::new(2);
+        yield;
+        a.check();
+        b.check();
+    }
+}
+
+// Resumes `gen` `resumes` times, then drops it, and checks which locals were dropped before
+// and by dropping the generator.
+fn run<G>(mut gen: G, resumes: usize, while_running: &[usize], on_drop: &[usize])
+    where G: Generator<Yield = (), Return = ()>
+{
+    for i in 0..resumes {
+        match unsafe { gen.resume() } {
+            GeneratorState::Yielded(()) if i < 2 => {}
+            GeneratorState::Complete(()) if i == 2 => {}
+            s => panic!("bad state after {} resumes: {:?}", i + 1, s),
+        }
+    }
+    assert_eq!(take_drops(), while_running);
+    drop(gen);
+    assert_eq!(take_drops(), on_drop);
+}
+
+fn main() {
+    run(disjoint(), 0, &[], &[]);
+    run(disjoint(), 1, &[], &[1]);
+    run(disjoint(), 2, &[1], &[2]);
+    run(disjoint(), 3, &[1, 2], &[]);
+
+    run(overlapping(), 0, &[], &[]);
+    run(overlapping(), 1, &[], &[1]);
+    run(overlapping(), 2, &[], &[1, 2]);
+    run(overlapping(), 3, &[1, 2], &[]);
+}
@@ -0,0 +1,64 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that locals saved across different suspension points share space in the generator, and
+// keep their values.
+
+#![feature(generators, generator_trait)]
+
+use std::mem::size_of_val;
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut disjoint = || {
+        {
+            let a = [1u8; 1024];
+            yield;
+            assert!(a.iter().all(|&x| x == 1));
+        }
+        {
+            let b = [2u8; 1024];
+            yield;
+            assert!(b.iter().all(|&x| x == 2));
+        }
+    };
+    assert!(size_of_val(&disjoint) < 2048);
+
+    let mut overlapping = || {
+        let a = [1u8; 1024];
+        yield;
+        let b = [2u8; 1024];
+        yield;
+        assert!(a.iter().all(|&x| x == 1));
+        assert!(b.iter().all(|&x| x == 2));
+    };
+    assert!(size_of_val(&overlapping) >= 2048);
+
+    unsafe {
+        for _ in 0..2 {
+            match disjoint.resume() {
+                GeneratorState::Yielded(()) => {}
+                s => panic!("bad state: {:?}", s),
+            }
+            match overlapping.resume() {
+                GeneratorState::Yielded(()) => {}
+                s => panic!("bad state: {:?}", s),
+            }
+        }
+        match disjoint.resume() {
+            GeneratorState::Complete(()) => {}
+            s => panic!("bad state: {:?}", s),
+        }
+        match overlapping.resume() {
+            GeneratorState::Complete(()) => {}
+            s => panic!("bad state: {:?}", s),
+        }
+    }
+}
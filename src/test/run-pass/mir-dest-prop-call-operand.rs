@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=3
+
+// Destination propagation must not make a call or an aggregate write to the local it reads its
+// operands from.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct State {
+    a: u64,
+    b: u64,
+    rest: [u64; 14],
+}
+
+#[inline(never)]
+fn step(s: State) -> State {
+    let mut rest = s.rest;
+    rest[0] += 1;
+    State { a: s.b, b: s.a + s.b, rest }
+}
+
+fn iterate(n: u32) -> State {
+    let mut s = State { a: 0, b: 1, rest: [0; 14] };
+    let mut i = 0;
+    while i < n {
+        s = step(s);
+        i += 1;
+    }
+    s
+}
+
+fn swap_loop(n: u32) -> (u64, u64) {
+    let mut p = (1, 2);
+    let mut i = 0;
+    while i < n {
+        p = (p.1, p.0 + p.1);
+        i += 1;
+    }
+    p
+}
+
+fn main() {
+    let s = iterate(10);
+    assert_eq!((s.a, s.b), (55, 89));
+    assert_eq!(s.rest[0], 10);
+    assert_eq!(swap_loop(5), (13, 21));
+}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=3 -Z validate-mir
+
+// Exercise the MIR optimizations with the validator checking the result of each of them.
+
+struct Pair {
+    a: u32,
+    b: String,
+}
+
+fn parse(s: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("bad number: {}", s))
+}
+
+fn sum(a: &str, b: &str) -> Result<u32, String> {
+    let pair = (parse(a)?, parse(b)?);
+    Ok(pair.0 + pair.1)
+}
+
+fn build(a: u32) -> Pair {
+    let pair = Pair { a, b: a.to_string() };
+    pair
+}
+
+fn main() {
+    assert_eq!(sum("1", "2"), Ok(3));
+    assert!(sum("1", "x").is_err());
+
+    let pair = build(7);
+    assert_eq!(pair.a, 7);
+    assert_eq!(pair.b, "7");
+
+    let v: Vec<_> = (0..10).filter(|x| x % 2 == 0).collect();
+    assert_eq!(v, [0, 2, 4, 6, 8]);
+}
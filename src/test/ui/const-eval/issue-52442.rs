@@ -9,5 +9,5 @@
 // except according to those terms.
 
 fn main() {
-    [();  { &loop { break } as *const _ as usize } ]; //~ ERROR unimplemented expression type
+    [();  { &loop { break } as *const _ as usize } ]; //~ ERROR branches and loops
 }
@@ -11,8 +11,8 @@
 fn main() {
     [(); & { loop { continue } } ]; //~ ERROR mismatched types
     [(); loop { break }]; //~ ERROR mismatched types
-    [(); {while true {break}; 0}]; //~ ERROR constant contains unimplemented expression type
+    [(); {while true {break}; 0}]; //~ ERROR branches and loops in constants are unstable
     [(); { for _ in 0usize.. {}; 0}]; //~ ERROR calls in constants are limited to constant functions
-    //~^ ERROR constant contains unimplemented expression type
+    //~^ ERROR branches and loops in constants are unstable
     //~| ERROR could not evaluate repeat length
 }
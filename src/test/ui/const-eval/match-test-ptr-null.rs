@@ -14,7 +14,7 @@ fn main() {
     // bytes.
     let _: [u8; 0] = [4; { //~ ERROR could not evaluate repeat length
         match &1 as *const i32 as usize { //~ ERROR raw pointers cannot be cast to integers
-            0 => 42, //~ ERROR constant contains unimplemented expression type
+            0 => 42, //~ ERROR branches and loops in constants are unstable
             //~^ NOTE "pointer arithmetic or comparison" needs an rfc before being allowed
             n => n,
         }
@@ -18,7 +18,7 @@ const fn f(x: usize) -> usize {
     //~| statements in constant functions are unstable
     for i in 0..x {
         //~^ ERROR E0015
-        //~| ERROR E0019
+        //~| ERROR E0658
         sum += i;
     }
     sum
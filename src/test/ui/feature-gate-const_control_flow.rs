@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test branches in a const fn without the feature gate.
+
+#![feature(const_fn)]
+
+const fn pick(x: u32) -> u32 {
+    if x == 0 { 1 } else { x } //~ ERROR branches and loops in constant functions are unstable
+}
+
+fn main() {}
@@ -15,7 +15,7 @@ fn non_const() -> Thing {
 }
 
 pub const Q: i32 = match non_const() { //~ ERROR E0015
-    Thing::This => 1, //~ ERROR unimplemented expression type
+    Thing::This => 1, //~ ERROR branches and loops in constants are unstable
     Thing::That => 0
 };
 
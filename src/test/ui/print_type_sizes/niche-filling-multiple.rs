@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z print-type-sizes
+// compile-pass
+
+// This file illustrates niche-filling enums with more than one
+// data-carrying variant: the other variants' fields are placed after
+// the niche in the largest variant, so no separate tag is needed.
+//
+// It avoids using u64/i64 because on some targets that is only 4-byte
+// aligned (while on most it is 8-byte aligned) and so the resulting
+// padding and overall computed sizes can be quite different.
+
+#![feature(start)]
+#![allow(dead_code)]
+
+pub enum CharOrInt {
+    Char(char, u32),
+    Int(u32),
+}
+
+pub enum Pair {
+    Both(char, char),
+    One(char),
+    Neither,
+}
+
+// No niche in the largest variant, so this is still tagged.
+pub enum Tagged {
+    Wide(u32, u32),
+    Narrow(char),
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _a = CharOrInt::Int(0);
+    let _b = Pair::Neither;
+    let _c = Tagged::Narrow('a');
+    0
+}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z print-type-sizes
+// compile-pass
+// only-64bit
+
+// This file illustrates an enum whose largest variant has a niche in a
+// reference, next to two other variants. A reference only has a single
+// invalid value, null, while encoding both `B` and `C` in the niche would
+// need two, so the niche can't be used and the tag is kept.
+//
+// It is only run on 64-bit targets, as the sizes depend on the size of a
+// pointer.
+
+#![feature(start)]
+#![allow(dead_code)]
+
+pub enum Refs {
+    A(u32, &'static u8),
+    B(&'static u8),
+    C,
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _a = Refs::C;
+    0
+}
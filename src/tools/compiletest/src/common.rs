@@ -210,6 +210,9 @@ pub struct Config {
     /// Is LLVM a system LLVM
     pub system_llvm: bool,
 
+    /// Whether the profiler runtime was built
+    pub profiler_support: bool,
+
     /// Path to the android tools
     pub android_cross_path: PathBuf,
 
@@ -44,7 +44,9 @@ impl EarlyProps {
                 || (config.has_cfg_prefix(ln, "only")
                     && !config.parse_cfg_name_directive(ln, "only"))
                 || ignore_gdb(config, ln) || ignore_lldb(config, ln)
-                || ignore_llvm(config, ln);
+                || ignore_llvm(config, ln)
+                || (!config.profiler_support
+                    && config.parse_name_directive(ln, "needs-profiler-support"));
 
             if let Some(s) = config.parse_aux_build(ln) {
                 props.aux.push(s);
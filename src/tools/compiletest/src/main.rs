@@ -199,6 +199,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
             "VERSION STRING",
         )
         .optflag("", "system-llvm", "is LLVM the system LLVM")
+        .optflag("", "profiler-support", "is the profiler runtime available")
         .optopt(
             "",
             "android-cross-path",
@@ -328,6 +329,7 @@ pub fn parse_config(args: Vec<String>) -> Config {
         lldb_version: extract_lldb_version(matches.opt_str("lldb-version")),
         llvm_version: matches.opt_str("llvm-version"),
         system_llvm: matches.opt_present("system-llvm"),
+        profiler_support: matches.opt_present("profiler-support"),
         android_cross_path: opt_path(matches, "android-cross-path"),
         adb_path: opt_str2(matches.opt_str("adb-path")),
         adb_test_dir: opt_str2(matches.opt_str("adb-test-dir")),
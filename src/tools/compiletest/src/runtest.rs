@@ -2499,6 +2499,11 @@ impl<'test> TestCx<'test> {
             cmd.env("RUSTDOC", cwd.join(rustdoc));
         }
 
+        if let Some(ref filecheck) = self.config.llvm_filecheck {
+            // FileCheck is built next to the other LLVM tools, such as `llvm-cov`.
+            cmd.env("LLVM_BIN_DIR", cwd.join(filecheck.parent().unwrap()));
+        }
+
         if let Some(ref node) = self.config.nodejs {
             cmd.env("NODE", node);
         }